@@ -1,8 +1,10 @@
 // lib.rs
 extern crate proc_macro;
+mod module_macro;
 mod relation_macro;
 mod utils;
 
+use module_macro::diesel_linker_module_impl;
 use proc_macro::TokenStream;
 use relation_macro::diesel_linker_impl;
 
@@ -22,11 +24,336 @@ pub fn diesel_linker_derive(input: TokenStream) -> TokenStream {
 /// The following attributes are supported:
 ///
 /// `attrs`: The attributes provided to the macro, which describe the relationship:
-/// - `child`: The name of the table from which the relationship originates.
+/// - `child`: The child model type, optionally with its module path (e.g. `Post` or `crate::models::Post`), or a projection tuple (see `columns`). Its diesel table module is inferred as the plural snake_case model name (`posts`) unless `related_table` is set, and generated method names derive from the final path segment whatever its casing.
 /// - `fk`: Indicates the foreign key in the parent table linking to the child.
 /// - `join_table`: The name of the join table for many-to-many relationships.
 /// - `fk_parent`: The foreign key in the join table linking to the parent table.
-/// - `relation_type`: The type of relationship (one_to_one, one_to_many, many_to_one, many_to_many).
+/// - `relation_type`: The type of relationship (one_to_one, one_to_many, many_to_one, many_to_many, has_many_through).
+/// - `singular`: The singular name used for the `add_<singular>`/`remove_<singular>` methods of many-to-many relations (defaults to the snake_case child model name).
+/// - `plural`: The plural name used for the generated collection methods such as `get_<plural>_cursor` (defaults to the pluralized `singular` name).
+/// - `backend`: A comma separated list of backends (`postgres`, `sqlite`, `mysql`), or `any`, the generated methods are written for; see [Backends](#backends).
+/// - `order_by`: The child column ordering the loaded children, optionally followed by `asc` (the default) or `desc`, e.g. `"created_at desc"`. It applies to `children`, `related_entities` and the getters built on them, the row cursor, the pages and the batch loaders `load_with_<plural>`, so results are deterministic. Without it, one-to-many getters return the rows in database order, and many-to-many getters, pages and batch loaders order by primary key. `recent` and `sorted` only use its column, with their own direction.
+/// - `recent`: Generates `get_recent_<plural>(conn, n)` for one-to-many relations, returning the `n` most recent children ordered descending by `order_by` (required).
+/// - `aggregates`: A comma separated list of aggregates (`count`, `sum(<column>)`) for one-to-many relations. Generates a `<Child>Stats` struct and `load_with_<singular>_stats(parents, conn)`, computing every aggregate in a single grouped query; parents without children get zeroed stats. Summed columns must be integer columns.
+/// - `serialize_as`: A DTO type implementing `From<Child>` and `serde::Serialize`, for one-to-many and many-to-many relations. Generates `get_<dto plural>(conn)` (e.g. `get_post_dtos` for `PostDto`) which loads the children and converts each of them into the DTO.
+/// - `wrapper`: Generates a `<Parent>With<Plural>` wrapper for one-to-many relations (e.g. `UserWithPosts`). It derefs to the parent and its `<plural>(conn)` method loads the children on the first call only, then returns the cached rows.
+/// - `fk_parent_type`: The Rust type of `fk_parent` in the join table of many-to-many relations (defaults to `i32`), i.e. the type of the parent key.
+/// - `fk_child_type`: The Rust type of `fk_child` in the join table of many-to-many relations (defaults to `i32`). It types the loaded child ids and the `child_id` parameter of `add_<singular>`/`remove_<singular>`, independently of `fk_parent_type`.
+/// - `cache`: Generates `get_cached_<plural>(cache, conn)` for one-to-many relations. It looks the children up in `cache` by parent key and relation name (`"<Parent>::<plural>"`) before querying, and stores the loaded rows on a miss. See [Relation cache](#relation-cache).
+/// - `strict_one`: For one-to-one relations, makes `get_related_entity` load up to two rows and fail with a `DatabaseError(UniqueViolation, ..)` when more than one child is related, instead of returning an arbitrary one. Defaults to `false` (a single `first()` query).
+/// - `with_parent`: Generates `get_<plural>_with_parent(conn)` for one-to-many and many-to-many relations, returning `Vec<(Child, Parent)>` where each child is paired with a clone of the parent. Requires the parent to implement `Clone`.
+/// - `default_filter`: Conditions scoping the loaded children, e.g. `status = "active" AND deleted_at IS NULL`. Conditions compare a child column with a literal (`=`, `!=`, `<`, `>`), test it with `IS NULL`/`IS NOT NULL` or `IN (...)` a list of literals, and are combined with `AND`. They are the static scope of the relation, e.g. `plural = "published_posts", default_filter = "published = true"` for the published posts of a user: they apply to every loader of one-to-many relations, lazy (`children`, pages, `count_<plural>`, ...) and batched (`load_with_<plural>`, ...), and to `related_entities` of many-to-many relations, but not to `query_<plural>()` nor to `get_<plural>_filtered`, which is built on it. Those return the unscoped relation, whose boxed query type is named in their signatures.
+/// - `paginate`: Generates `get_<plural>_page(conn, page)` and its variants for one-to-many and many-to-many relations, loading one page of children ordered by `order_by` (or by primary key); see [Pagination](#pagination).
+/// - `query_fn`: Also generates the free function `<plural>_of(parent)` (e.g. `posts_of(&user)`) for one-to-many and many-to-many relations. It returns the same boxed query as `query_<plural>()`, to build hand-written diesel queries from the relation, e.g. on a non-standard `fk` that diesel's `belonging_to` can't infer.
+/// - `count`: Generates `count_<plural>(conn)` for one-to-many and many-to-many relations, counting the children in the database with `SELECT COUNT(*)` over the same scope as `children`/`related_entities` (including `default_filter`), without loading them.
+/// - `exists`: Generates `has_<plural>(conn)` for one-to-many and many-to-many relations, telling with an `EXISTS` query whether there is at least one child, and `has_<singular>(conn)` for one-to-one and many-to-one relations, telling whether the related row (the parent, for many-to-one) exists without loading it, e.g. `post.has_user(conn)` to detect a dangling `user_id`. The query is `SELECT EXISTS (SELECT ... WHERE ...)` on every backend: the subquery is an expression, not a derived table, so MySQL needs no alias for it and the same `has_*` API works on Postgres, SQLite and MySQL.
+/// - `full`: Enables `count`, `exists` and `eager_loading` at once (the latter for one-to-many and many-to-one relations only), on top of the methods every relation gets. Each of them can still be turned off individually, e.g. `full = true, eager_loading = false`. `wrapper` is not part of the bundle and stays opt-in.
+/// - `related_table`: The diesel table module of the child model, for tables whose name can't be inferred from the model (e.g. `status` for `Status`, which would be inferred as `statuses`). Used by every relation type, including the child table of many-to-many relations. It may be a module path relative to `schema_path`, e.g. `analytics::events` for a table of another Postgres schema declared in `crate::schema::analytics`; the parent and join tables stay in `schema_path`.
+/// - `selectable`: Loads the children with `.select(Child::as_select())` (and returns them with `as_returning()`) instead of relying on the column order of `Queryable`, so reordering the table columns doesn't break the relation. The child model must derive `Selectable`. Required for child models composed with `#[diesel(embed)]`, whose rows are not the flat column list of the table: every loader, including the batch loaders' `(fk, Child::as_select())` tuples and `get_<plural>_filtered`, then selects through `Selectable`.
+/// - `error_type`: An application error type implementing `From<diesel::result::Error>`, for one-to-one relations. Generates `get_<singular>_or(conn, not_found)` (e.g. `get_user_profile_or`), returning the related row, `not_found` when there is none, and any other diesel error converted with `From`.
+/// - `schema_path`: The module holding the diesel `table!` definitions (defaults to `crate::schema`), e.g. `crate::db::schema` when the schema isn't at the crate root. Every table referenced by the generated code is resolved in this module.
+/// - `with_usage`: Generates `get_<plural>_with_usage(conn)` for many-to-many relations, returning each child with the number of join table rows referencing it across all parents (e.g. how many posts use each tag). Requires the child model to have an `id` field of type `fk_child_type`, which must implement `Hash` and `Eq`.
+/// - `eager_loading`: Generates the batch loaders `load_with_<plural>(parents, conn)` and `load_with_<plural>_except(parents, skip_keys, conn)` for one-to-many relations. They load the children of every parent in a single query and return `Vec<(Parent, Vec<Child>)>` in the order of `parents`; parents whose key is in `skip_keys` (e.g. already cached) are left out of the query and get an empty `Vec`. It also generates `load_<plural>_map(parents, conn)`, which borrows `parents` and returns a `HashMap<ParentKey, Vec<Child>>` instead, for callers that already hold the parents elsewhere; parents without children are absent from the map. Many-to-one relations get the inverse loader `load_parents_with_children(children, conn)` instead, see below; one-to-one and many-to-many relations reject `eager_loading`.
+/// - `no_inflection`: Disables the names inferred with inflection (pluralization and snake_case conversion of model names), for deterministic naming: `singular`, `plural` and `related_table` become required, many-to-one relations need the struct table declared with `table_name`, and `serialize_as` is rejected since its getter name is inflected from the DTO type. A projection tuple `child` is named after `singular` as written, e.g. its `aggregates` struct is `<singular>Stats`. No separate method name override is needed: the generated method names are either fixed (`children`, `get_parent`, ...) or built from `singular` and `plural`, which are then taken verbatim.
+/// - `readonly`: Suppresses the mutators (`add_child`/`remove_child`, `set_related_entity`, `add_<singular>`/`remove_<singular>`) and only generates read-only loaders. Use it when `related_table` points at a SQL view declared with `table!`.
+/// - `debug_sql`: A boolean that generates `<plural>_query_debug()` on one_to_many relations, returning the `diesel::debug_query` output of the query run by `children()` (its SQL followed by diesel's `-- binds: [...]`, ordering and column selection included) and the `Debug` rendering of each bound value: the parent key followed by the values of the `default_filter` conditions and of the polymorphic discriminator. Requires `backend`, the SQL is rendered for the backend of each generated implementation.
+/// - `nonempty`: Generates `get_<plural>_nonempty(conn)` for one-to-many and many-to-many relations, returning `Option<(Child, Vec<Child>)>`: `None` when the relation is empty, otherwise the first child and the remaining ones, so the type encodes "at least one".
+/// - `fk_type`: The Rust type of `fk` in one-to-many relations (defaults to `i32`), i.e. the type of the parent key. Use it for keys with a custom SQL type, such as a `Copy` newtype deriving `AsExpression` and `FromSqlRow`: the batch loaders then take and return that type, and declare the `AsExpression`/`ToSql`/`FromSql` bounds their key queries need.
+/// - `group_by`: A child column (e.g. `"category_id"`) generating `get_<plural>_grouped_by_<column>(conn)` for one-to-many and many-to-many relations. It loads the children and groups them in Rust by that field, returning a `HashMap<Key, Vec<Child>>`.
+/// - `group_by_type`: The Rust type of the `group_by` field, used as the map key (defaults to `i32`). It must implement `Clone`, `Eq` and `Hash`.
+/// - `parent_key`: The parent column the relation joins on in one-to-one, many-to-one and one-to-many relations (defaults to `id`), for natural keys such as a unique `external_id` that is not the primary key. One-to-many batch loaders then group the children by that key, cloned from each parent; set `fk_type` to its type.
+/// - `child_key`: The child column the relation joins on in one-to-one and many-to-one relations (defaults to `fk`). The generated loaders filter on `child_key = parent_key` explicitly instead of relying on the primary key.
+/// - `eager_loading_stream`: Generates the async batch loader `load_with_<plural>_stream(parents, conn)` for one-to-many relations, taking a `diesel_async::AsyncConnection`. It has the same result as `load_with_<plural>`, but reads the children with `load_stream` and groups them as the rows arrive instead of buffering them first. Requires the `async` feature of diesel_linker, and your crate must depend on `diesel_async` and `futures_util`; without the feature no generated code references `diesel_async`, so sync-only crates don't need it.
+/// - `shared_children`: Generates `get_<plural>_shared(conn)` for one-to-many and many-to-many relations, returning `Vec<Arc<Child>>` so the loaded rows can be handed to several caches or views by cloning the `Arc` instead of the row.
+/// - `link_verb`: The prefix of the many-to-many mutator linking a child (defaults to `add`), e.g. `link_verb = "attach"` generates `attach_<singular>`.
+/// - `unlink_verb`: The prefix of the many-to-many mutator unlinking a child (defaults to `remove`), e.g. `unlink_verb = "detach"` generates `detach_<singular>`.
+/// - `latest`: A child column (e.g. `"created_at"`) generating `get_latest_<plural>(conn)` for one-to-many relations, loading the `latest_limit` newest children ordered by that column descending, after `default_filter`.
+/// - `latest_limit`: The number of children loaded by `get_latest_<plural>`, required with `latest`.
+/// - `update`: Generates `update_<singular>` for one-to-many (`update_<singular>(conn, child_id, changes)`) and one-to-one (`update_<singular>(conn, changes)`) relations. It applies `changes`, any `AsChangeset` type targeting the child table, to the related row and returns the updated row (`RETURNING`, so Postgres or SQLite). Suppressed by `readonly`.
+/// - `connection_type`: A custom connection type (e.g. `"crate::db::PoolConn"`) whose shared references implement `diesel::connection::LoadConnection`, such as a pool wrapper with a `&self` query API backed by interior mutability. Generated methods then take `conn: C` where `C: Copy + Deref<Target = ConnectionType>`, so they are called with `&pool_conn` instead of `&mut`. The row cursor still takes `&mut C`. Implementing diesel's connection traits requires diesel's `i-implement-a-third-party-backend-and-opt-into-breaking-changes` feature.
+/// - `filtered`: Generates `get_<plural>_filtered(conn, predicate)` for one-to-many and many-to-many relations, loading the children that also match `predicate`, a diesel boolean expression over the child table (e.g. `posts::published.eq(true)`), applied with `.filter` on `query_<plural>()`. The predicate must be accepted by the boxed query, i.e. appear on the child table only, be non-aggregate and have a `Bool` or `Nullable<Bool>` SQL type, which diesel checks at compile time.
+/// - `columns`: A comma separated list of child columns (e.g. `"id, title"`) selected by the loaders of one-to-many and many-to-many relations instead of the whole row, in that order. Combined with a tuple `child` such as `"(i32, String)"`, it loads ad-hoc projections without a dedicated struct, through both the lazy getters and the batch loaders, e.g. `load_with_post_titles(parents, conn)` returning `Vec<(User, Vec<(i32, String)>)>`. A tuple `child` requires `columns`, `related_table`, `singular` and `plural`, and gets no mutators. Can't be combined with `selectable`.
+/// - `ranked`: Generates `get_<plural>_ranked(conn)` for one-to-many relations, returning `Vec<(i64, Child)>` where each child comes with its 1-based rank in the relation, ordered by `order_by` (required), e.g. for numbered lists or leaderboards. On Postgres the rank is computed by `ROW_NUMBER() OVER (ORDER BY <order_by>)`, on the other backends it is numbered in Rust after the ordered load, with the same result.
+/// - `sorted`: Generates `get_<plural>_sorted(conn, dir)` for one-to-many relations, loading the children ordered by `order_by` (required) in the direction chosen at runtime, e.g. to toggle a sort in a UI without whitelisting columns. `dir` is a `SortDir` defined at the root of your crate, see [Sorting](#sorting).
+/// - `load_into`: Generates `get_<plural>_into(conn, buf)` for one-to-many relations, which clears `buf: &mut Vec<Child>` and fills it with the children in the order of `children` (`order_by` included), so a batch processor iterating many parents reuses one allocation. The rows are read with `load_iter` in `DefaultLoadingMode` and pushed into `buf` without an intermediate `Vec`. The benefit is limited to that `Vec`: the rows themselves are still allocated, and the driver may buffer the result set.
+/// - `visibility`: Visibility of the generated methods and support items, e.g. `"pub(crate)"`. Defaults to the visibility of the annotated struct, so relations on a private or `pub(crate)` struct don't leak it through `pub` methods. Trait implementations are unaffected.
+/// - `optional`: For many-to-one relations with `eager_loading`, declares that children may reference a parent that no longer exists (e.g. a soft-deleted or filtered row). Without it, `load_parents_with_children` checks with `debug_assert!` that every child found its parent, so orphans panic in debug builds instead of being dropped silently; release builds skip the check.
+/// - `collect_into`: A collection type (e.g. `"smallvec::SmallVec<[Post; 8]>"`) implementing `FromIterator<Child>`. Generates `get_<plural>_collected(conn)` for one-to-many relations, which collects the rows straight into that type, in the order of `children` (`order_by` included), so hot paths avoid a `Vec` conversion at the call site. The rows are read with `load_iter` in `DefaultLoadingMode`. The other getters keep returning `Vec`.
+/// - `soft_delete_column`: A nullable timestamp column of the child table marking soft-deleted rows (e.g. `"deleted_at"`), for one-to-many relations. Children where it is set are excluded from every loader, as if `<column> IS NULL` were added to `default_filter`. Unless the relation is `readonly`, it also generates `soft_delete_<plural>(conn)`, which sets the column to `now` on the children not yet deleted, and `hard_delete_<plural>(conn)`, which deletes all of them permanently. Both return the number of affected rows.
+/// - `fk_column`: The SQL column of the foreign key in one-to-many and one-to-one relations, for a child field mapped with `#[diesel(column_name = ...)]`. Defaults to `fk`. The child table is filtered on this column.
+/// - `fk_field`: The Rust field holding the foreign key in many-to-one relations, for a field mapped to another column with `#[diesel(column_name = ...)]`. Defaults to `fk`. `get_parent` and `load_parents_with_children` read the key from `self.<fk_field>`.
+/// - `async`: Generates the cheap queries `count_<plural>`, `has_<plural>` and `has_<singular>` as `async fn`s taking a `diesel_async::AsyncConnection`, awaited as `user.count_posts(&mut conn).await`, for all relation types. The other methods stay synchronous, except the chunked stream of `streaming`. Like `eager_loading_stream`, it requires the `async` feature of diesel_linker and a dependency on `diesel_async`.
+/// - `join_condition`: A comparison between a child column and fields of the parent, for interval and nested-set relations: `child.<column> <op> self.<field>` with `<`, `<=`, `>` or `>=`, or `child.<column> BETWEEN self.<field> AND self.<field>`. Generates `get_<plural>_matching(conn)` for one-to-many relations, loading the children satisfying it (with `default_filter`), next to the `fk` getters. `BETWEEN` is inclusive as in SQL, so on a nested-set tree (`child.lft BETWEEN self.lft AND self.rgt`) the node itself is among its descendants.
+/// - `streaming`: With `async`, generates `get_<plural>_chunks(conn, chunk_size)` for one-to-many relations, returning a `futures_util::Stream` of `QueryResult<Vec<Child>>` chunks of up to `chunk_size` children, for bulk async pipelines. The children are read from a single `load_stream` result and grouped as they arrive, so the chunks come from one consistent query rather than separate pages. `chunk_size` must not be zero. Your crate must depend on `futures_util`.
+/// - `context_errors`: Generates `<getter>_with_context(conn)` next to the main getter of the relation (`children`, `related_entities`, `get_related_entity` or `get_parent`), returning its errors wrapped in a `RelationError` that names the relation and the model, e.g. `children_with_context` failing with `RelationError { relation: "posts", model: "User", .. }`. `RelationError` is defined at the root of your crate, see [Relation errors](#relation-errors). The plain getters keep returning `diesel::result::Error`.
+/// - `cursor_column`: A unique, ordered child column (e.g. `"id"`) generating `get_<plural>_after(conn, cursor, limit)` for one-to-many and many-to-many relations: keyset pagination loading up to `limit` children whose column is greater than `cursor`, ordered by that column, after `default_filter`. Pass `None` for the first page, then the column value of the last child loaded. Unlike `paginate`, the database seeks straight to the cursor through the column index instead of skipping `offset` rows, so deep pages of infinite scroll APIs stay cheap. A non-unique column would skip the rows tied with the cursor.
+/// - `cursor_type`: The Rust type of the `cursor_column` field, i.e. of the `cursor` taken by `get_<plural>_after` (defaults to `i32`).
+/// - `where_closure`: Generates `get_<plural>_where(conn, refine)` for one-to-many and many-to-many relations. It passes `query_<plural>()` to `refine`, a closure returning the refined boxed query, e.g. `|query| query.filter(posts::published.eq(true)).order(posts::title)`, and loads the result. Any diesel filter, order or limit can be applied per call, since they all keep the boxed query type. Like `get_<plural>_filtered`, it starts from the unscoped relation, without `default_filter`.
+/// - `ids`: Generates `get_<singular>_ids(conn)` for one-to-many and many-to-many relations (e.g. `get_post_ids`), selecting only the `id` column of the children, in ascending order, after `default_filter`. It avoids deserializing whole rows when only the keys are needed, e.g. for authorization checks. Many-to-many relations read the ids in a single query, with the join table in a subquery.
+/// - `ids_type`: The Rust type of the child `id` column loaded by `get_<singular>_ids` in one-to-many relations (defaults to `i32`). Many-to-many relations use `fk_child_type`.
+/// - `latest_by`: A child column (e.g. `"created_at"`) generating `get_latest_<singular>(conn)` and `get_first_<singular>(conn)` for one-to-many relations (e.g. `get_latest_post`), returning the child with the greatest or the smallest value of that column as an `Option`, `None` when the relation is empty. Only that row is loaded, with `ORDER BY ... LIMIT 1` after `default_filter`.
+/// - `chunk_size`: Splits the parent keys of the batch loaders (`load_with_<plural>` and `load_with_<plural>_except`, `load_with_<plural>_stream`, `load_with_<singular>_stats`, `get_<plural>_count_map` and `load_parents_with_children`) into chunks of at most that many keys, e.g. `chunk_size = 900`, with one query per chunk whose results are merged. Without it each loader binds every key in a single `IN` list, which fails past the bind parameter limit of the backend (32766 on SQLite since 3.32, 999 before; 65535 on Postgres). The results are the same either way, each group keeping the order of `order_by`.
+/// - `nested`: The `plural` of an `eager_loading` one-to-many relation declared on the child model (e.g. `"comments"` on `User.posts` for `Post.comments`), for one-to-many relations with `eager_loading`. Generates `load_with_<plural>_and_<nested>(parents, conn)` (e.g. `load_with_posts_and_comments`), returning `Vec<(Parent, Vec<(Child, Vec<Grandchild>)>)>`: the children of all parents are loaded with `load_with_<plural>`, then the grandchildren of all children with the child's `load_with_<nested>`, so two levels take two batched queries whatever the number of rows. Both levels keep the order of their batch loader.
+/// - `nested_child`: The grandchild model returned by the `nested` loader of the child (e.g. `"Comment"`), required with `nested`.
+/// - `through`: The intermediate table of `has_many_through` relations (e.g. `"posts"` for the comments of a user through their posts), resolved in `schema_path`. Its primary key must be `id`.
+/// - `through_fk`: The column of the `through` table referencing the parent in `has_many_through` relations (e.g. `"user_id"`).
+/// - `polymorphic`: Declares a polymorphic one-to-many relation, whose child table holds the children of several parent models, e.g. the comments of both posts and photos: `relation_type = "one_to_many", child = "Comment", polymorphic = true, type_column = "commentable_type", id_column = "commentable_id"` on both `Post` and `Photo`. Requires `type_column` and `id_column`, which replaces `fk`. Every loader of the relation, including `query_<plural>()`, and every mutator acting on the existing children (`remove_child`, `update_<singular>`, `soft_delete_<plural>`, `hard_delete_<plural>`) then also matches `type_column` with `type_value`, so a `Photo` never reaches the comments of the `Post` with the same id. `add_child` inserts the child as is: set its discriminator yourself.
+/// - `type_column`: The child column naming the parent model of each row in `polymorphic` relations (e.g. `"commentable_type"`), a text column.
+/// - `id_column`: The child column holding the parent key in `polymorphic` relations (e.g. `"commentable_id"`), used as `fk`.
+/// - `type_value`: The value of `type_column` designating the annotated model in `polymorphic` relations (defaults to the struct name, e.g. `"Post"`).
+/// - `inverse`: For self-referential many-to-one relations, the plural name of the inverse relation (e.g. `"reports"`), generating `get_<inverse>(conn)`, see [Self-referential relations](#self-referential-relations).
+/// - `conn_type`: The connection type the relation is used with (e.g. `"diesel_logger::LoggingConnection<diesel::PgConnection>"` or an instrumented connection of your own). The generated methods already accept any `C: diesel::connection::LoadConnection<Backend = B>`, so `conn_type` doesn't change their signatures: it asserts where the model is declared that the type implements that bound, with an error naming the type instead of an unsatisfied bound at each call site. It takes the connection by `&mut`, like the methods; for a pool wrapper taken by shared reference use `connection_type`, the two are exclusive.
+/// - `pool`: Generates `<getter>_pooled(pool)` next to the main getter of the relation (`children`, `related_entities`, `get_related_entity`, `get_parent` or `get_<plural>` for has-many-through relations), taking a `&diesel::r2d2::Pool<M>` instead of a connection: it checks a connection out of the pool, runs the getter and returns the connection to the pool, e.g. `user.children_pooled(&pool)?` in a web handler. The pool and query errors are returned as `Box<dyn std::error::Error + Send + Sync>`, or as `pool_error`. Requires the `r2d2` feature of diesel; not compatible with `connection_type`. Async pools (deadpool, bb8, mobc) already hand out objects the async methods accept, see [Connection pools](#connection-pools).
+/// - `pool_error`: An application error type implementing `From<diesel::r2d2::PoolError>` and `From<diesel::result::Error>`, returned by the `_pooled` variants of `pool` instead of the boxed error, e.g. `pool_error = "crate::error::AppError"`.
+///
+/// One-to-many and many-to-many relations also get `query_<plural>()`, which returns the relation-scoped boxed query
+/// (`<child table>::BoxedQuery`) so callers can chain `filter`, `order`, `limit`, ... and load it themselves.
+/// It is the relation's reusable scope, the counterpart of diesel's `Child::belonging_to(&parent)`, and composes
+/// into larger queries: joined (`user.query_posts().inner_join(comments::table).select((posts::title, comments::body))`),
+/// aggregated (`user.query_posts().select(count_star())`) or as a subquery (`comments::post_id.eq_any(user.query_posts().select(posts::id))`).
+/// For many-to-many relations this query reads the join table in a subquery, so the child table and the join table must
+/// be listed together in `diesel::allow_tables_to_appear_in_same_query!`; the macro reports a compile error naming that call otherwise.
+/// The `fk_parent` and `fk_child` columns are also checked against the join table at the declaration, so a typo fails with
+/// "cannot find type `<column>` in module `<join_table>`". A transposed pair can't be detected without the schema.
+///
+/// The generated queries never go through `belonging_to`: they filter the child table explicitly, e.g.
+/// `posts.filter(user_id.eq(self.id))`. The child model only needs to load from its table (`Queryable`, or
+/// `Selectable` with `selectable`), so derive-less or view-backed structs without `Identifiable` or
+/// `Associations` can be related as is.
+///
+/// Methods taking a child id (`remove_child`, `add_<singular>`, `remove_<singular>`) accept `impl Into<KeyType>`,
+/// so a newtype id implementing `Into` the key type can be passed as is.
+///
+/// Many-to-many loaders (`related_entities` and the getters built on it) return the children ordered by `order_by`, or by their primary key, whatever the order of the join table rows.
+///
+/// `has_many_through` relations reach the children through an intermediate table, e.g. the comments of a user
+/// through their posts: `relation_type = "has_many_through", child = "Comment", through = "posts", through_fk = "user_id", fk = "post_id"`,
+/// where `fk` is the child column referencing the `id` of the `through` table. They generate `get_<plural>(conn)`
+/// (e.g. `get_comments`), which loads the children in a single query with the intermediate table in a subquery,
+/// ordered by `order_by` or by primary key and scoped by `default_filter`, and `query_<plural>()`, as well as
+/// `count_<plural>` and `has_<plural>` with `count` and `exists`. The parent key type is `fk_type` (defaults to `i32`). Like the join table of many-to-many relations,
+/// the `through` table must be listed with the child table in `diesel::allow_tables_to_appear_in_same_query!`.
+///
+/// Many-to-one relations generate `get_parent`, which compares the parent key with `fk` and so expects a non-nullable
+/// `fk`. With `eager_loading`, they also generate `load_parents_with_children(children, conn)`, the inverse batch loader:
+/// it loads the distinct parents of a flat child list in a single query and returns `Vec<(Parent, Vec<Child>)>`
+/// with each child grouped under its parent. Parents follow the order in which they first appear in `children`,
+/// and each group keeps the input order of its children. The `load_with_<plural>` loaders of one-to-many
+/// relations likewise return the parents in the order they were passed. When the `fk` field of the struct is an
+/// `Option<K>`, `load_parents_with_children` returns `Vec<(Option<Parent>, Vec<Child>)>` instead: only the `Some`
+/// keys are queried, and the children whose `fk` is `None` are grouped under a `None` parent, placed where the
+/// first of them appears.
+///
+/// Internal and advanced helpers are generated with `#[doc(hidden)]`, so the `cargo doc` page of a model lists the
+/// user-facing getters, loaders and mutators only. The hidden helpers are still public and callable:
+/// - `<plural>_query_debug()` (`debug_sql`), meant for logging and tests;
+/// - `load_with_<plural>_except(parents, skip_keys, conn)` (`eager_loading`), the building block of dataloaders,
+///   while `load_with_<plural>` stays documented.
+///
+/// The compile-time assertions (join columns, `allow_tables_to_appear_in_same_query!`) are anonymous constants and never appear in the docs.
+///
+/// Generated methods return their failures as `diesel::QueryResult`, so an `unwrap()` on their result already reports
+/// the caller's location. The only panic raised in a generated body is the orphan check of `load_parents_with_children`
+/// (see `optional`), which is marked `#[track_caller]` so that it points at the call site. Panics raised in diesel or
+/// in the user code the methods call (`From`, `RelationCache`, ...) keep their own location.
+///
+/// The table of the annotated struct itself (used by many-to-one relations) is read from `#[diesel(table_name = ...)]`
+/// or `#[table_name = "..."]` when present: a bare table name is resolved in the `schema_path` module, a module path
+/// (`some::module::users`) is used as is. Without it, the table is inferred as the plural snake_case struct name.
+///
+/// The generated loaders deserialize rows straight into the child model (`load::<Child>`), into key/count tuples, or into
+/// tuples pairing the model with a key or a rank (`(K, Child)`, selected as `(fk, all_columns)` by the batch loaders).
+/// The model always reads its own columns, so models annotated with `#[diesel(check_for_backend(...))]` keep working;
+/// use the same backend in `backend` to get the matching connection bound.
+///
+/// # Backends
+///
+/// A single backend gets one implementation, generated unconditionally. When several backends are listed, one
+/// implementation is generated per backend, gated by the crate feature of the same name; when several features
+/// are enabled the first listed backend wins. The features are those of the crate defining
+/// the models, which declares them (e.g. `postgres = ["diesel/postgres"]` and `sqlite = ["diesel/sqlite"]`), so a
+/// workspace can test its models on SQLite with `backend = "postgres, sqlite"` and `--features sqlite`, while the
+/// production build enables `postgres`. Cargo unifies the features of a crate across a build, so building both
+/// together yields the Postgres implementation only; keep the SQLite tests in their own build (or use `any`).
+/// A relation without `backend` takes the `default_backend` of an enclosing `#[diesel_linker]` module, see
+/// [`diesel_linker`](macro@diesel_linker).
+///
+/// The generated methods are not tied to a connection type: they are generic over
+/// `C: diesel::connection::LoadConnection<Backend = B>`, `B` being the backend, so the same model works with
+/// `PgConnection`, a pooled or instrumented connection, or any other connection of that backend, and a crate
+/// targeting several databases lists them all instead of duplicating its models. Without any backend, `B` is left
+/// generic too, which diesel can't type-check for the loading queries, so set one of them, or `any`.
+///
+/// With `backend = "any"` (alone), one implementation generic over the backend of the connection is generated for
+/// one-to-many, many-to-many and has-many-through relations: the getter (`children`, `related_entities` or
+/// `get_<plural>`), `count_<plural>` with `count`, and the `query_<plural>::<DB>()` boxed query with its `filtered`,
+/// `where_closure` and `query_fn` variants. Each method declares the diesel bounds of its query, checked against the
+/// concrete connection at the call site, so a library can take any `C: LoadConnection` whose backend supports the
+/// query. The other generated methods need a concrete backend and their attributes are rejected with `any`.
+///
+/// # Relation cache
+///
+/// A proc-macro crate can't export traits, so `cache = true` expects a `RelationCache` trait at the root of your crate
+/// with the following shape; implement it over any backend (a `HashMap` behind a `Mutex`, moka, Redis, ...):
+///
+// ```rust
+/// pub trait RelationCache<K, T> {
+///     fn get(&self, parent_key: &K, relation: &str) -> Option<Vec<T>>;
+///     fn put(&self, parent_key: &K, relation: &str, rows: &[T]);
+/// }
+// ```
+///
+/// `K` is the type of the parent key, `fk_type` (`i32` by default), so a relation keyed by a newtype or a `Uuid`
+/// is cached under that key.
+///
+/// The crate never invalidates entries. To invalidate in bulk, keep a generation counter in the cache, make it part of
+/// the stored key and bump it after writes.
+///
+/// # Pagination
+///
+/// For the same reason, `paginate = true` expects a `Page` type at the root of your crate, so every relation of the
+/// application shares it and clamping or validation lives in one place:
+///
+// ```rust
+/// pub struct Page {
+///     pub limit: i64,
+///     pub offset: i64,
+/// }
+// ```
+///
+/// Next to `get_<plural>_page(conn, page)`, `paginate` generates:
+///
+/// - `get_<plural>_page_has_more(conn, page)`, returning the page with whether another one follows, by loading one
+///   extra row rather than counting.
+/// - `get_<plural>_paged(conn, page, per_page)`, loading the same pages by 1-based page number and page size, e.g.
+///   `user.get_posts_paged(conn, 3, 20)` for posts 41 to 60.
+/// - With `count` also set, `get_<plural>_page_with_total(conn, page)`, returning the page with the total number of
+///   children, both read in one transaction.
+///
+/// Many-to-many pages follow the order of `related_entities`, and the page is taken from the final child query, after
+/// `default_filter`, so pages stay full; the related ids are still loaded in full first.
+///
+/// The total of `get_<plural>_page_with_total` only matches the page under concurrent writes if its transaction sees a
+/// single snapshot: SQLite transactions and MySQL's default `REPEATABLE READ` do, but on Postgres the default
+/// `READ COMMITTED` gives each query its own snapshot, so call it inside
+/// `conn.build_transaction().repeatable_read().run(...)`.
+///
+/// # Sorting
+///
+/// Likewise, `sorted = true` expects a `SortDir` enum at the root of your crate:
+///
+// ```rust
+/// #[derive(Clone, Copy)]
+/// pub enum SortDir {
+///     Asc,
+///     Desc,
+/// }
+// ```
+///
+/// # Grouping children
+///
+/// The grouped getters (`group_by`) and the batch loaders group the loaded rows by key in a `HashMap`. A proc-macro
+/// crate can't export that helper either; to group rows the same way in your own queries, copy this function into
+/// your crate:
+///
+// ```rust
+/// pub fn group_children_by<K, T>(
+///     children: impl IntoIterator<Item = T>,
+///     key_fn: impl Fn(&T) -> K,
+/// ) -> std::collections::HashMap<K, Vec<T>>
+/// where
+///     K: Eq + std::hash::Hash,
+/// {
+///     let mut groups: std::collections::HashMap<K, Vec<T>> = std::collections::HashMap::new();
+///     for child in children {
+///         groups.entry(key_fn(&child)).or_default().push(child);
+///     }
+///     groups
+/// }
+// ```
+///
+/// Each group keeps the order of `children`, e.g. `group_children_by(posts, |post| post.author_id)`.
+///
+/// # Relation errors
+///
+/// `context_errors = true` expects a `RelationError` struct at the root of your crate, with these three fields:
+///
+// ```rust
+/// #[derive(Debug)]
+/// pub struct RelationError {
+///     pub relation: &'static str,
+///     pub model: &'static str,
+///     pub source: diesel::result::Error,
+/// }
+///
+/// impl std::fmt::Display for RelationError {
+///     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+///         write!(f, "failed to load {}.{}: {}", self.model, self.relation, self.source)
+///     }
+/// }
+///
+/// impl std::error::Error for RelationError {
+///     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+///         Some(&self.source)
+///     }
+/// }
+// ```
+///
+/// # Self-referential relations
+///
+/// A many-to-one relation whose `child` is the annotated struct itself links rows of the same table, e.g. a
+/// hierarchy of employees:
+///
+// ```rust
+/// #[derive(DieselLinker, Queryable)]
+/// #[relation(child = "Employee", fk = "manager_id", relation_type = "many_to_one", inverse = "reports")]
+/// pub struct Employee {
+///     pub id: i32,
+///     pub manager_id: Option<i32>,
+/// }
+// ```
+///
+/// It generates `get_<singular>(conn)` (`singular` defaults to `fk` without its `_id` suffix, here `get_manager`),
+/// returning `Option<Self>`: `None` when `fk` is `None`, for the root of the hierarchy. The `fk` field may be
+/// `Option<K>` or `K`, `K` being `fk_type`. With `inverse`, it also generates `get_<inverse>(conn)` (here
+/// `get_reports`), loading the rows whose `fk` references this one, ordered by key. Both read the table of the
+/// struct alone, so no query joins the table with itself and no `diesel::alias!` is needed. The other
+/// many-to-one methods are not generated for self-referential relations: `eager_loading`, `exists`,
+/// `context_errors`, `pool` and `full` are rejected with a compile error on the attribute.
+///
+/// # Connection pools
+///
+/// The generated methods take `conn: &mut C` with `C: diesel::connection::LoadConnection<Backend = B>`, which
+/// diesel implements for `r2d2::PooledConnection<ConnectionManager<Conn>>` (with its `r2d2` feature) whenever
+/// `Conn` implements it. A pooled connection is then passed as is, without dereferencing it, and generic helpers
+/// can share the bound of the generated methods:
+///
+// ```rust
+/// let mut conn = pool.get()?;
+/// let posts = user.children(&mut conn)?;
+///
+/// fn posts_of<C>(user: &User, conn: &mut C) -> diesel::QueryResult<Vec<Post>>
+/// where
+///     C: diesel::connection::LoadConnection<Backend = diesel::pg::Pg>,
+/// {
+///     user.children(conn)
+/// }
+// ```
+///
+/// A pool wrapper whose connection is only reachable through `DerefMut` can pass `&mut *conn` instead, and with
+/// `pool = true` the main getter also gets a `_pooled` variant taking the r2d2 pool itself, e.g.
+/// `user.children_pooled(&pool)?`.
+///
+/// The same holds for the async methods (`async`, `eager_loading_stream`, `streaming`), generic over
+/// `C: diesel_async::AsyncConnection<Backend = B>`: with its `deadpool`, `bb8` or `mobc` feature, diesel_async
+/// implements that trait for every `DerefMut` wrapper of an async connection, so the pooled objects are passed
+/// directly, e.g. `user.count_posts(&mut pool.get().await?).await`.
+///
+/// # Reusing the code generation
+///
+/// Relations can only be declared with the `relation` attribute: a `proc-macro` crate can't export anything but its
+/// macros, so `RelationAttributes` and the generators behind this attribute can't be called from another proc-macro.
+/// Making them reusable means moving them into a regular library crate that this one depends on.
 ///
 /// Returns :
 ///
@@ -80,27 +407,51 @@ pub fn diesel_linker_derive(input: TokenStream) -> TokenStream {
 ///
 // ```rust
 /// impl User {
-///   pub fn posts(&self, conn: &PgConnection) -> diesel::QueryResult<Vec<Post>> {
+///   pub fn posts(&self, conn: &mut PgConnection) -> diesel::QueryResult<Vec<Post>> {
 ///         use crate::schema::posts::dsl::*;
 ///         posts.filter(user_id.eq(self.id)).load::<Post>(conn)
 ///     }
 /// }
 // ```
 ///
-//// # Usage in your code:
+/// # Usage in your code:
 ///
 /// How to use the generated methods in a Rust application:
 // ```rust
 /// Fetches all posts for a specific user from the database.
 ///
-/// fn get_user_posts(conn: &PgConnection, user_id: i32) -> QueryResult<Vec<Post>> {
+/// fn get_user_posts(conn: &mut PgConnection, user_id: i32) -> QueryResult<Vec<Post>> {
 ///    let user = users::table.find(user_id).first::<User>(conn)?;
 ///    user.posts(conn)
 /// }
 // ```
-///
-
 #[proc_macro_attribute]
 pub fn relation(attr: TokenStream, item: TokenStream) -> TokenStream {
     diesel_linker_impl(attr, item)
 }
+
+/// Sets module-level defaults for the `#[relation]` attributes of an inline module.
+///
+/// `default_backend` is added to every relation of the module, and of its nested inline modules,
+/// that doesn't set `backend` itself, so single-backend modules don't repeat it:
+///
+// ```rust
+/// #[diesel_linker(default_backend = "postgres")]
+/// mod models {
+///   #[relation(child = "Post", fk = "user_id", relation_type = "one_to_many")]
+///   pub struct User { pub id: i32 }
+///   #[relation(child = "Event", fk = "user_id", relation_type = "one_to_many", backend = "sqlite")]
+///   pub struct Account { pub id: i32 }
+/// }
+// ```
+///
+/// The module is rewritten before the relations inside it expand, which sets the ordering constraints:
+/// - The attribute must be placed on the module itself, and the module must be inline (`mod models { ... }`),
+///   since attributes on `mod models;` file modules aren't supported on stable Rust.
+/// - Only relations lexically inside the module get the default: relations of other modules, or of files
+///   included with `mod name;` from it, keep their own `backend` (or none).
+/// - An explicit `backend` on a relation always wins over the default.
+#[proc_macro_attribute]
+pub fn diesel_linker(attr: TokenStream, item: TokenStream) -> TokenStream {
+    diesel_linker_module_impl(attr, item)
+}
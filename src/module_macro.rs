@@ -0,0 +1,166 @@
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::quote;
+use syn::punctuated::Punctuated;
+use syn::{
+    self, parse_macro_input, AttributeArgs, Error, Item, ItemMod, Lit, LitStr, Meta, NestedMeta,
+    Result, Token,
+};
+
+// Expands `#[diesel_linker(default_backend = "...")]` on an inline module: the `#[relation]`
+// attributes found in the module, and in its nested inline modules, get the default backend
+// unless they set `backend` themselves. The module is rewritten before the relations expand,
+// so no state is shared between macro invocations.
+pub fn diesel_linker_module_impl(attrs: TokenStream, item: TokenStream) -> TokenStream {
+    let attrs = parse_macro_input!(attrs as AttributeArgs);
+    let mut item_mod = parse_macro_input!(item as ItemMod);
+
+    let result = parse_default_backend(attrs)
+        .and_then(|backend| apply_default_backend(&mut item_mod, &backend));
+    match result {
+        Ok(()) => TokenStream::from(quote! { #item_mod }),
+        Err(error) => TokenStream::from(error.to_compile_error()),
+    }
+}
+
+// Reads the `default_backend` argument, the only one the module attribute accepts.
+fn parse_default_backend(attrs: AttributeArgs) -> Result<LitStr> {
+    let mut default_backend = None;
+    for attr in attrs {
+        match attr {
+            NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("default_backend") => {
+                match nv.lit {
+                    Lit::Str(s) => default_backend = Some(s),
+                    _ => {
+                        return Err(Error::new(
+                            Span::call_site(),
+                            "Attribute 'default_backend' must be a string",
+                        ))
+                    }
+                }
+            }
+            _ => {
+                return Err(Error::new(
+                    Span::call_site(),
+                    "Unknown diesel_linker attribute, expected 'default_backend'",
+                ))
+            }
+        }
+    }
+    default_backend.ok_or_else(|| {
+        Error::new(
+            Span::call_site(),
+            "Attribute 'default_backend' is required by #[diesel_linker]",
+        )
+    })
+}
+
+// Adds `backend = <default>` to every `#[relation]` of the module that doesn't name a backend.
+fn apply_default_backend(item_mod: &mut ItemMod, backend: &LitStr) -> Result<()> {
+    let (_, items) = item_mod.content.as_mut().ok_or_else(|| {
+        Error::new(
+            Span::call_site(),
+            "#[diesel_linker] must be applied to an inline module (`mod name { ... }`)",
+        )
+    })?;
+    for item in items {
+        match item {
+            Item::Struct(item_struct) => {
+                for attr in &mut item_struct.attrs {
+                    let is_relation = attr
+                        .path
+                        .segments
+                        .last()
+                        .is_some_and(|segment| segment.ident == "relation");
+                    if !is_relation {
+                        continue;
+                    }
+                    let mut nested = match attr.parse_meta()? {
+                        Meta::List(list) => list.nested,
+                        _ => Punctuated::<NestedMeta, Token![,]>::new(),
+                    };
+                    let has_backend = nested.iter().any(|meta| {
+                        matches!(meta, NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("backend"))
+                    });
+                    if !has_backend {
+                        nested.push(NestedMeta::Meta(syn::parse_quote! { backend = #backend }));
+                        attr.tokens = quote! { (#nested) };
+                    }
+                }
+            }
+            Item::Mod(nested_mod) if nested_mod.content.is_some() => {
+                apply_default_backend(nested_mod, backend)?;
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::parser::parse_attributes;
+
+    // Returns the parsed `backend` of the `#[relation]` attribute of `item`.
+    fn relation_backend(item: &Item) -> Option<String> {
+        let Item::Struct(item_struct) = item else {
+            panic!("expected a struct");
+        };
+        let attr = &item_struct.attrs[0];
+        let Meta::List(list) = attr.parse_meta().unwrap() else {
+            panic!("expected a relation list");
+        };
+        parse_attributes(list.nested.into_iter().collect())
+            .unwrap()
+            .backend
+    }
+
+    #[test]
+    fn test_default_backend_fills_relations_without_backend() {
+        let mut item_mod: ItemMod = syn::parse_quote! {
+            mod models {
+                #[relation(child = "Post", fk = "user_id", relation_type = "one_to_many")]
+                pub struct User {
+                    pub id: i32,
+                }
+
+                #[relation(child = "Tag", fk = "post_id", relation_type = "one_to_many", backend = "sqlite")]
+                pub struct Post {
+                    pub id: i32,
+                }
+
+                mod nested {
+                    #[diesel_linker::relation(child = "Comment", fk = "post_id", relation_type = "one_to_many")]
+                    pub struct Post {
+                        pub id: i32,
+                    }
+                }
+            }
+        };
+        apply_default_backend(&mut item_mod, &syn::parse_quote! { "postgres" }).unwrap();
+
+        let items = &item_mod.content.as_ref().unwrap().1;
+        assert_eq!(relation_backend(&items[0]).as_deref(), Some("postgres"));
+        assert_eq!(relation_backend(&items[1]).as_deref(), Some("sqlite"));
+        let Item::Mod(nested) = &items[2] else {
+            panic!("expected the nested module");
+        };
+        let nested_items = &nested.content.as_ref().unwrap().1;
+        assert_eq!(
+            relation_backend(&nested_items[0]).as_deref(),
+            Some("postgres")
+        );
+    }
+
+    #[test]
+    fn test_default_backend_requires_inline_module() {
+        let mut item_mod: ItemMod = syn::parse_quote! { mod models; };
+        let error =
+            apply_default_backend(&mut item_mod, &syn::parse_quote! { "postgres" }).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "#[diesel_linker] must be applied to an inline module (`mod name { ... }`)"
+        );
+    }
+}
@@ -1,12 +1,18 @@
+use crate::utils::grouping::group_children_by;
 use crate::utils::parser::parse_attributes;
-use crate::utils::parser::ParsedAttrs;
+use crate::utils::parser::{
+    parse_aggregates, parse_default_filter, parse_join_condition, parse_order_by, Aggregate,
+    FilterCondition, JoinCondition, ParsedAttrs,
+};
+use inflector::Inflector;
 use proc_macro::TokenStream;
 use proc_macro2::Span;
 use quote::quote;
+use syn::parse::ParseStream;
 use syn::ItemStruct;
-use syn::{self, parse_macro_input, AttributeArgs, Ident};
+use syn::{self, parse_macro_input, AttributeArgs, Ident, Lit, LitStr, Meta, MetaNameValue, Token};
 
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct RelationAttributes {
     pub child_model: String,
     pub fk: String,
@@ -14,19 +20,103 @@ pub struct RelationAttributes {
     pub join_table: Option<String>,
     pub fk_parent: Option<String>,
     pub fk_child: Option<String>,
+    pub singular: Option<String>,
+    pub plural: Option<String>,
+    pub backends: Vec<String>,
+    pub order_by: Option<String>,
+    pub order_desc: bool,
+    pub recent: bool,
+    pub aggregates: Vec<Aggregate>,
+    pub serialize_as: Option<String>,
+    pub wrapper: bool,
+    pub fk_parent_type: Option<String>,
+    pub fk_child_type: Option<String>,
+    pub cache: bool,
+    pub table_name: Option<String>,
+    pub nullable_fk: bool,
+    pub strict_one: bool,
+    pub with_parent: bool,
+    pub default_filter: Vec<FilterCondition>,
+    pub paginate: bool,
+    pub query_fn: bool,
+    pub count: bool,
+    pub exists: bool,
+    pub related_table: Option<String>,
+    pub selectable: bool,
+    pub error_type: Option<String>,
+    pub schema_path: Option<String>,
+    pub with_usage: bool,
+    pub eager_loading: bool,
+    pub no_inflection: bool,
+    pub readonly: bool,
+    pub debug_sql: bool,
+    pub nonempty: bool,
+    pub fk_type: Option<String>,
+    pub group_by: Option<String>,
+    pub group_by_type: Option<String>,
+    pub parent_key: Option<String>,
+    pub child_key: Option<String>,
+    pub eager_loading_stream: bool,
+    pub shared_children: bool,
+    pub link_verb: Option<String>,
+    pub unlink_verb: Option<String>,
+    pub latest: Option<String>,
+    pub latest_limit: Option<i64>,
+    pub update: bool,
+    pub connection_type: Option<String>,
+    pub filtered: bool,
+    pub columns: Option<String>,
+    pub ranked: bool,
+    pub sorted: bool,
+    pub load_into: bool,
+    pub visibility: Option<String>,
+    pub optional: bool,
+    pub collect_into: Option<String>,
+    pub soft_delete_column: Option<String>,
+    pub fk_column: Option<String>,
+    pub fk_field: Option<String>,
+    pub async_: bool,
+    pub join_condition: Option<JoinCondition>,
+    pub streaming: bool,
+    pub context_errors: bool,
+    pub cursor_column: Option<String>,
+    pub cursor_type: Option<String>,
+    pub where_closure: bool,
+    pub ids: bool,
+    pub ids_type: Option<String>,
+    pub latest_by: Option<String>,
+    pub chunk_size: Option<usize>,
+    pub nested: Option<String>,
+    pub nested_child: Option<String>,
+    pub through: Option<String>,
+    pub through_fk: Option<String>,
+    pub type_column: Option<String>,
+    pub type_value: Option<String>,
+    pub inverse: Option<String>,
+    pub conn_type: Option<String>,
+    pub pool: bool,
+    pub pool_error: Option<String>,
+    pub spans: Vec<(String, Span)>,
 }
 
 // Extracts the relation attributes from the attributes passed to the macro.
 fn extract_relation_attrs(parsed_attrs: &ParsedAttrs) -> Result<RelationAttributes, syn::Error> {
+    let order_by = parsed_attrs
+        .order_by
+        .as_deref()
+        .map(parse_order_by)
+        .transpose()?;
     // Supposons que parsed_attrs contient déjà toutes les informations nécessaires
     Ok(RelationAttributes {
         child_model: parsed_attrs
             .child
             .clone()
             .ok_or_else(|| syn::Error::new(Span::call_site(), "child_model is missing"))?,
+        // Polymorphic relations name the parent key column with `id_column`.
         fk: parsed_attrs
             .fk
             .clone()
+            .or_else(|| parsed_attrs.id_column.clone())
             .ok_or_else(|| syn::Error::new(Span::call_site(), "fk is missing"))?,
         relation_type: parsed_attrs
             .relation_type
@@ -35,170 +125,6226 @@ fn extract_relation_attrs(parsed_attrs: &ParsedAttrs) -> Result<RelationAttribut
         join_table: parsed_attrs.join_table.clone(),
         fk_parent: parsed_attrs.fk_parent.clone(),
         fk_child: parsed_attrs.fk_child.clone(),
+        singular: parsed_attrs.singular.clone(),
+        plural: parsed_attrs.plural.clone(),
+        backends: parsed_attrs
+            .backend
+            .as_deref()
+            .map(|backend| backend.split(',').map(|b| b.trim().to_string()).collect())
+            .unwrap_or_default(),
+        order_desc: order_by.as_ref().is_some_and(|(_, descending)| *descending),
+        order_by: order_by.map(|(column, _)| column),
+        recent: parsed_attrs.recent,
+        aggregates: parsed_attrs
+            .aggregates
+            .as_deref()
+            .map(parse_aggregates)
+            .transpose()?
+            .unwrap_or_default(),
+        serialize_as: parsed_attrs.serialize_as.clone(),
+        wrapper: parsed_attrs.wrapper.unwrap_or_default(),
+        fk_parent_type: parsed_attrs.fk_parent_type.clone(),
+        fk_child_type: parsed_attrs.fk_child_type.clone(),
+        cache: parsed_attrs.cache,
+        // Renseigné depuis les attributs `diesel` de la structure, pas depuis `relation`.
+        table_name: None,
+        nullable_fk: false,
+        strict_one: parsed_attrs.strict_one,
+        with_parent: parsed_attrs.with_parent,
+        default_filter: parsed_attrs
+            .default_filter
+            .as_deref()
+            .map(parse_default_filter)
+            .transpose()?
+            .unwrap_or_default(),
+        paginate: parsed_attrs.paginate,
+        query_fn: parsed_attrs.query_fn,
+        count: parsed_attrs.count.unwrap_or(parsed_attrs.full),
+        exists: parsed_attrs.exists.unwrap_or(parsed_attrs.full),
+        related_table: parsed_attrs.related_table.clone(),
+        selectable: parsed_attrs.selectable,
+        error_type: parsed_attrs.error_type.clone(),
+        schema_path: parsed_attrs.schema_path.clone(),
+        with_usage: parsed_attrs.with_usage,
+        // `full` only enables the batch loaders of the relation types having them.
+        eager_loading: parsed_attrs.eager_loading.unwrap_or(
+            parsed_attrs.full
+                && matches!(
+                    parsed_attrs.relation_type.as_deref(),
+                    Some("one_to_many" | "many_to_one")
+                ),
+        ),
+        no_inflection: parsed_attrs.no_inflection,
+        readonly: parsed_attrs.readonly,
+        debug_sql: parsed_attrs.debug_sql,
+        nonempty: parsed_attrs.nonempty,
+        fk_type: parsed_attrs.fk_type.clone(),
+        group_by: parsed_attrs.group_by.clone(),
+        group_by_type: parsed_attrs.group_by_type.clone(),
+        parent_key: parsed_attrs.parent_key.clone(),
+        child_key: parsed_attrs.child_key.clone(),
+        eager_loading_stream: parsed_attrs.eager_loading_stream,
+        shared_children: parsed_attrs.shared_children,
+        link_verb: parsed_attrs.link_verb.clone(),
+        unlink_verb: parsed_attrs.unlink_verb.clone(),
+        latest: parsed_attrs.latest.clone(),
+        latest_limit: parsed_attrs.latest_limit,
+        update: parsed_attrs.update,
+        connection_type: parsed_attrs.connection_type.clone(),
+        filtered: parsed_attrs.filtered,
+        columns: parsed_attrs.columns.clone(),
+        ranked: parsed_attrs.ranked,
+        sorted: parsed_attrs.sorted,
+        load_into: parsed_attrs.load_into,
+        visibility: parsed_attrs.visibility.clone(),
+        optional: parsed_attrs.optional,
+        collect_into: parsed_attrs.collect_into.clone(),
+        soft_delete_column: parsed_attrs.soft_delete_column.clone(),
+        fk_column: parsed_attrs.fk_column.clone(),
+        fk_field: parsed_attrs.fk_field.clone(),
+        async_: parsed_attrs.async_,
+        join_condition: parsed_attrs
+            .join_condition
+            .as_deref()
+            .map(parse_join_condition)
+            .transpose()?,
+        streaming: parsed_attrs.streaming,
+        context_errors: parsed_attrs.context_errors,
+        cursor_column: parsed_attrs.cursor_column.clone(),
+        cursor_type: parsed_attrs.cursor_type.clone(),
+        where_closure: parsed_attrs.where_closure,
+        ids: parsed_attrs.ids,
+        ids_type: parsed_attrs.ids_type.clone(),
+        latest_by: parsed_attrs.latest_by.clone(),
+        chunk_size: parsed_attrs.chunk_size,
+        nested: parsed_attrs.nested.clone(),
+        nested_child: parsed_attrs.nested_child.clone(),
+        through: parsed_attrs.through.clone(),
+        through_fk: parsed_attrs.through_fk.clone(),
+        type_column: parsed_attrs.type_column.clone(),
+        type_value: parsed_attrs.type_value.clone(),
+        inverse: parsed_attrs.inverse.clone(),
+        conn_type: parsed_attrs.conn_type.clone(),
+        pool: parsed_attrs.pool,
+        pool_error: parsed_attrs.pool_error.clone(),
+        spans: parsed_attrs.spans.clone(),
     })
 }
 pub fn diesel_linker_impl(attrs: TokenStream, item: TokenStream) -> TokenStream {
     let item_struct = parse_macro_input!(item as ItemStruct);
     let attrs = parse_macro_input!(attrs as AttributeArgs);
 
-    // Utilisation dela fonction parse_attributes pour obtenir un objet ParsedAttrs depuis attrs
-    let parsed_attrs = parse_attributes(attrs).expect("Failed to parse attributes");
+    // Utilisation dela fonction parse_attributes pour obtenir un objet ParsedAttrs depuis attrs ;
+    // les attributs invalides deviennent une erreur de compilation placée sur l'attribut fautif.
+    let parsed_attrs = match parse_attributes(attrs) {
+        Ok(parsed_attrs) => parsed_attrs,
+        Err(error) => return error.to_compile_error().into(),
+    };
 
     // On construit un objet ParsedAttrs qui sera utilisé
-    let relation_attrs =
-        extract_relation_attrs(&parsed_attrs).expect("Failed to extract relation attributes");
+    let mut relation_attrs = match extract_relation_attrs(&parsed_attrs) {
+        Ok(relation_attrs) => relation_attrs,
+        Err(error) => return error.to_compile_error().into(),
+    };
+    relation_attrs.table_name = declared_table_name(&item_struct);
+    relation_attrs.nullable_fk = is_option_field(&item_struct, relation_fk_field(&relation_attrs));
+    if let Err(error) = check_self_table(&item_struct.ident, &relation_attrs) {
+        return error.to_compile_error().into();
+    }
+    if let Err(error) = check_self_referential(&item_struct.ident, &relation_attrs) {
+        return error.to_compile_error().into();
+    }
+    if relation_attrs.type_column.is_some() && relation_attrs.type_value.is_none() {
+        relation_attrs.type_value = Some(item_struct.ident.to_string());
+    }
+    if relation_attrs.visibility.is_none() {
+        relation_attrs.visibility = struct_visibility(&item_struct);
+    }
 
     let struct_name = &item_struct.ident;
-    let gen_code = generate_relation_code(
-        struct_name,
-        &relation_attrs.child_model,
-        &relation_attrs.fk,
-        &relation_attrs.relation_type,
-        relation_attrs.join_table,
-        relation_attrs.fk_parent,
-        relation_attrs.fk_child,
-    );
+    let support_items = generate_support_items(struct_name, &relation_attrs);
+    let gen_code = generate_relation_code(struct_name, &relation_attrs);
 
     TokenStream::from(quote! {
         #item_struct
+        #support_items
         #gen_code
     })
 }
 
-fn generate_relation_code(
+// Returns the visibility of a non-`pub` struct, which the generated items default to so that they
+// don't expose it through `pub` methods. `pub` structs keep the `pub` items.
+fn struct_visibility(item_struct: &ItemStruct) -> Option<String> {
+    let vis = &item_struct.vis;
+    (!matches!(vis, syn::Visibility::Public(_))).then(|| quote! { #vis }.to_string())
+}
+
+// Returns the Rust field of the annotated struct holding the foreign key of a many-to-one relation.
+fn relation_fk_field(relation_attrs: &RelationAttributes) -> &str {
+    relation_attrs
+        .child_key
+        .as_deref()
+        .or(relation_attrs.fk_field.as_deref())
+        .unwrap_or(&relation_attrs.fk)
+}
+
+// Whether the struct declares `field` as an `Option<...>`, i.e. a nullable column.
+fn is_option_field(item_struct: &ItemStruct, field: &str) -> bool {
+    item_struct.fields.iter().any(|item_field| {
+        let syn::Type::Path(ty) = &item_field.ty else {
+            return false;
+        };
+        item_field
+            .ident
+            .as_ref()
+            .is_some_and(|ident| ident == field)
+            && ty
+                .path
+                .segments
+                .last()
+                .is_some_and(|segment| segment.ident == "Option")
+    })
+}
+
+// Returns the table declared on the struct with `#[diesel(table_name = ...)]`, or with the older `#[table_name = "..."]`.
+fn declared_table_name(item_struct: &ItemStruct) -> Option<String> {
+    item_struct.attrs.iter().find_map(|attr| {
+        if attr.path.is_ident("table_name") {
+            return match attr.parse_meta() {
+                Ok(Meta::NameValue(MetaNameValue {
+                    lit: Lit::Str(s), ..
+                })) => Some(s.value()),
+                _ => None,
+            };
+        }
+        if !attr.path.is_ident("diesel") {
+            return None;
+        }
+        attr.parse_args_with(|input: ParseStream| {
+            let mut table_name = None;
+            while !input.is_empty() {
+                let key: Ident = input.parse()?;
+                if key == "table_name" {
+                    input.parse::<Token![=]>()?;
+                    table_name = Some(if input.peek(LitStr) {
+                        input.parse::<LitStr>()?.value()
+                    } else {
+                        let path: syn::Path = input.parse()?;
+                        quote!(#path).to_string().replace(' ', "")
+                    });
+                }
+                // Les autres options de diesel (`belongs_to(...)`, `check_for_backend(...)`, ...) sont ignorées.
+                while !input.is_empty() && !input.peek(Token![,]) {
+                    input.parse::<proc_macro2::TokenTree>()?;
+                }
+                if !input.is_empty() {
+                    input.parse::<Token![,]>()?;
+                }
+            }
+            Ok(table_name)
+        })
+        .ok()
+        .flatten()
+    })
+}
+
+// Many-to-one relations read the table of the annotated struct, which `no_inflection` forbids to infer:
+// it must then be declared with `table_name`, checked once that attribute of the struct is known.
+fn check_self_table(struct_name: &Ident, relation_attrs: &RelationAttributes) -> syn::Result<()> {
+    if relation_attrs.relation_type == "many_to_one"
+        && relation_attrs.no_inflection
+        && relation_attrs.table_name.is_none()
+    {
+        return Err(syn::Error::new(
+            struct_name.span(),
+            format!(
+                "no_inflection requires the table of {} to be declared with table_name",
+                struct_name
+            ),
+        ));
+    }
+    Ok(())
+}
+
+// A self-referential relation only generates `get_<singular>` and `get_<inverse>`: the attributes adding
+// methods around `get_parent` are rejected rather than silently ignored, pointing at `full` when
+// it enabled them.
+fn check_self_referential(
+    struct_name: &Ident,
+    relation_attrs: &RelationAttributes,
+) -> syn::Result<()> {
+    if !is_self_referential(struct_name, relation_attrs) {
+        return Ok(());
+    }
+    let unsupported = [
+        ("eager_loading", relation_attrs.eager_loading),
+        ("exists", relation_attrs.exists),
+        ("context_errors", relation_attrs.context_errors),
+        ("pool", relation_attrs.pool),
+    ];
+    let Some((name, _)) = unsupported.iter().find(|(_, enabled)| *enabled) else {
+        return Ok(());
+    };
+    let span_of = |name: &str| {
+        relation_attrs
+            .spans
+            .iter()
+            .find(|(attribute, _)| attribute == name)
+            .map(|(_, span)| *span)
+    };
+    let (name, span) = match (span_of(name), span_of("full")) {
+        (Some(span), _) => (*name, span),
+        (None, Some(span)) => ("full", span),
+        (None, None) => (*name, struct_name.span()),
+    };
+    Err(syn::Error::new(
+        span,
+        format!(
+            "Attribute '{}' is not supported by self-referential relations",
+            name
+        ),
+    ))
+}
+
+// Returns the diesel table module of the annotated struct itself.
+// A declared `table_name` made of a single segment is resolved in the schema module like the child table,
+// a module path is used as is; without declaration the table is inferred from the struct name
+// (`check_self_table` rejects that case with `no_inflection`).
+fn self_table(struct_name: &Ident, relation_attrs: &RelationAttributes) -> syn::Path {
+    let table_name = match &relation_attrs.table_name {
+        Some(table_name) => table_name.clone(),
+        None => struct_name.to_string().to_snake_case().to_plural(),
+    };
+    let path: syn::Path = syn::parse_str(&table_name).expect("Failed to parse the table_name path");
+    if path.leading_colon.is_none() && path.segments.len() == 1 {
+        let schema = schema_path(relation_attrs);
+        syn::parse_quote!(#schema::#path)
+    } else {
+        path
+    }
+}
+
+// Returns the module holding the diesel table definitions, `crate::schema` unless `schema_path` is set.
+fn schema_path(relation_attrs: &RelationAttributes) -> syn::Path {
+    syn::parse_str(
+        relation_attrs
+            .schema_path
+            .as_deref()
+            .unwrap_or("crate::schema"),
+    )
+    .expect("Failed to parse the schema_path")
+}
+
+// Returns the child model type, e.g. `Post`, `crate::models::Post` or a projection tuple such as `(i32, String)`.
+fn child_type(relation_attrs: &RelationAttributes) -> syn::Type {
+    syn::parse_str(&relation_attrs.child_model).expect("Failed to parse the child model type")
+}
+
+// Tells whether the child model is a tuple projection loaded through `columns` rather than a model struct.
+fn is_projection(relation_attrs: &RelationAttributes) -> bool {
+    matches!(child_type(relation_attrs), syn::Type::Tuple(_))
+}
+
+// Returns the name of the child model type, without its module path. A projection tuple
+// has no name, so it is named after the required `singular` attribute, converted to PascalCase
+// unless `no_inflection` asks for the names to be used as written.
+fn child_type_name(relation_attrs: &RelationAttributes) -> String {
+    match child_type(relation_attrs) {
+        syn::Type::Path(type_path) => type_path
+            .path
+            .segments
+            .last()
+            .map(|segment| segment.ident.to_string())
+            .unwrap_or_default(),
+        _ => {
+            let singular = relation_attrs.singular.as_deref().unwrap_or_default();
+            if relation_attrs.no_inflection {
+                singular.to_string()
+            } else {
+                singular.to_pascal_case()
+            }
+        }
+    }
+}
+
+// Returns the explicit child columns of the `columns` attribute as a selectable tuple.
+fn selected_columns(relation_attrs: &RelationAttributes) -> Option<proc_macro2::TokenStream> {
+    let columns = relation_attrs.columns.as_ref()?;
+    let child_schema = child_schema(relation_attrs);
+    let child_table = Ident::new(
+        &child_table_name(relation_attrs),
+        proc_macro2::Span::call_site(),
+    );
+    let columns = columns
+        .split(',')
+        .map(|column| Ident::new(column.trim(), proc_macro2::Span::call_site()));
+    Some(quote! { (#(#child_schema::#child_table::#columns,)*) })
+}
+
+// Returns the diesel table module of the child model: the `related_table` attribute,
+// otherwise inferred as the plural snake_case model name.
+fn child_table_name(relation_attrs: &RelationAttributes) -> String {
+    match &relation_attrs.related_table {
+        Some(related_table) => related_table
+            .rsplit("::")
+            .next()
+            .unwrap_or(related_table)
+            .to_string(),
+        None => child_type_name(relation_attrs).to_snake_case().to_plural(),
+    }
+}
+
+// Returns the module holding the child table: the schema path, extended with the leading segments
+// of a `related_table` path such as `analytics::events` for tables of another Postgres schema.
+fn child_schema(relation_attrs: &RelationAttributes) -> syn::Path {
+    let mut path = schema_path(relation_attrs);
+    if let Some(related_table) = &relation_attrs.related_table {
+        let mut modules: Vec<&str> = related_table.split("::").collect();
+        modules.pop();
+        path.segments.extend(modules.into_iter().map(|module| {
+            syn::PathSegment::from(Ident::new(module, proc_macro2::Span::call_site()))
+        }));
+    }
+    path
+}
+
+// Returns the singular name used in the generated method names of a relation.
+// The `singular` attribute wins, otherwise the child model name is only converted to snake_case:
+// model names are already singular, so no inflection is applied to them.
+// The name only depends on the final path segment, whatever its casing (`UserProfile` or `userProfile`).
+fn singular_name(relation_attrs: &RelationAttributes) -> String {
+    relation_attrs
+        .singular
+        .clone()
+        .unwrap_or_else(|| child_type_name(relation_attrs).to_snake_case())
+}
+
+// Returns the plural name used in the generated collection method names of a relation.
+fn plural_name(relation_attrs: &RelationAttributes) -> String {
+    relation_attrs
+        .plural
+        .clone()
+        .unwrap_or_else(|| singular_name(relation_attrs).to_plural())
+}
+
+// Returns the diesel backend type matching a `backend` attribute value.
+fn backend_type(backend: &str) -> proc_macro2::TokenStream {
+    match backend {
+        "postgres" => quote! { diesel::pg::Pg },
+        "sqlite" => quote! { diesel::sqlite::Sqlite },
+        "mysql" => quote! { diesel::mysql::Mysql },
+        _ => panic!("Unsupported backend: {}", backend),
+    }
+}
+
+// Returns the name of the stats struct generated for the `aggregates` attribute.
+fn stats_ident(relation_attrs: &RelationAttributes) -> Ident {
+    Ident::new(
+        &format!("{}Stats", child_type_name(relation_attrs)),
+        proc_macro2::Span::call_site(),
+    )
+}
+
+// Generates the items that live outside of the `impl` block, such as the stats struct.
+// They don't depend on the backend, so they are emitted once and never cfg-gated.
+fn generate_support_items(
     struct_name: &Ident,
-    child_model: &str,
-    fk: &str,
-    relation_type: &str,
-    join_table: Option<String>,
-    fk_parent: Option<String>,
-    fk_child: Option<String>,
+    relation_attrs: &RelationAttributes,
 ) -> proc_macro2::TokenStream {
-    let child_ident = Ident::new(child_model, proc_macro2::Span::call_site());
-    let fk_ident = Ident::new(fk, proc_macro2::Span::call_site());
-    match relation_type {
-        "one_to_many" => {
-            // Générer le code pour la relation one_to_many
-            quote! {
-                impl #struct_name {
-                    pub fn children<C>(&self, conn: &C) -> diesel::QueryResult<Vec<#child_ident>>
-                    where C: diesel::Connection,{
-                        use crate::schema::#child_ident::dsl::*;
-                        use diesel::prelude::*;
+    let mut items = proc_macro2::TokenStream::new();
+    if matches!(
+        relation_attrs.relation_type.as_str(),
+        "many_to_many" | "has_many_through"
+    ) {
+        items.extend(generate_same_query_assertion(relation_attrs));
+        items.extend(generate_join_columns_assertion(relation_attrs));
+    }
+    if relation_attrs.relation_type != "one_to_many" {
+        return items;
+    }
 
-                        #child_ident.filter(#fk_ident.eq(self.id)).load::<#child_ident>(conn)
-                    }
+    if !relation_attrs.aggregates.is_empty() {
+        let stats_ident = stats_ident(relation_attrs);
+        let fields = relation_attrs
+            .aggregates
+            .iter()
+            .map(|aggregate| Ident::new(&aggregate.field_name(), proc_macro2::Span::call_site()));
 
-                    pub fn add_child<C>(&self, conn: &C, new_child: &#child_ident) -> Result<usize, diesel::result::Error>
-                    where C: diesel::Connection, {
-                        use diesel::RunQueryDsl;
-                        diesel::insert_into(#child_ident::table).values(new_child).execute(conn)
-                    }
+        // Avec `no_inflection`, le nom vient tel quel de `singular` et peut ne pas être en CamelCase.
+        let allow_case = relation_attrs
+            .no_inflection
+            .then(|| quote! { #[allow(non_camel_case_types)] });
 
-                    // Supprimer un enfant spécifique
-                    pub fn remove_child<C>(&self, conn: &C, child_id: i32) -> Result<usize, diesel::result::Error>
-                    where C: diesel::Connection, {
-                        use diesel::RunQueryDsl;
-                        diesel::delete(#child_ident.filter(id.eq(child_id).and(#fk_ident.eq(self.id)))).execute(conn)
+        items.extend(quote! {
+            // Agrégats des enfants d'un parent, calculés par `load_with_<enfant>_stats`.
+            #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+            #allow_case
+            pub struct #stats_ident {
+                #(pub #fields: i64,)*
+            }
+        });
+    }
+
+    if relation_attrs.wrapper {
+        let wrapper_ident = wrapper_ident(struct_name, relation_attrs);
+        let child_type = child_type(relation_attrs);
+        let cache_ident = Ident::new(&plural_name(relation_attrs), proc_macro2::Span::call_site());
+
+        items.extend(quote! {
+            // Parent accompagné du cache de ses enfants, chargés au premier accès seulement.
+            pub struct #wrapper_ident {
+                inner: #struct_name,
+                #cache_ident: std::cell::OnceCell<Vec<#child_type>>,
+            }
+
+            impl #wrapper_ident {
+                pub fn new(inner: #struct_name) -> Self {
+                    Self {
+                        inner,
+                        #cache_ident: std::cell::OnceCell::new(),
                     }
                 }
+
+                pub fn into_inner(self) -> #struct_name {
+                    self.inner
+                }
             }
-        }
-        "many_to_one" => {
-            // Identifiant de l'entité parent et de la clé étrangère dans l'entité enfant.
-            let parent_model = "ParentModel"; // Replace "ParentModel" with the actual value of parent_model
-            let parent_ident = Ident::new(&parent_model, proc_macro2::Span::call_site());
-            let fk_ident = Ident::new(fk, proc_macro2::Span::call_site());
 
-            quote! {
-                impl #struct_name {
-                    // Récupère l'instance parente associée à cette instance enfant.
-                    pub fn get_parent<C>(&self, conn: &C) -> diesel::QueryResult<#parent_ident>
-                    where C: diesel::Connection, {
-                        use crate::schema::#parent_ident::dsl::*;
-                        use diesel::prelude::*;
+            impl From<#struct_name> for #wrapper_ident {
+                fn from(inner: #struct_name) -> Self {
+                    Self::new(inner)
+                }
+            }
 
-                        #parent_ident.filter(id.eq(self.#fk_ident)).first::<#parent_ident>(conn)
-                    }
+            impl std::ops::Deref for #wrapper_ident {
+                type Target = #struct_name;
 
-                    // Optionnellement, si vous voulez aussi définir la relation dans l'autre sens :
-                    impl #parent_ident {
-                        // Récupère toutes les instances enfants liées à cette instance parent.
-                        pub fn get_children<C>(&self, conn: &C) -> diesel::QueryResult<Vec<#struct_name>>
-                        where C: diesel::Connection, {
-                            use crate::schema::#struct_name::dsl::*;
-                            use diesel::prelude::*;
+                fn deref(&self) -> &Self::Target {
+                    &self.inner
+                }
+            }
+        });
+    }
 
-                            #struct_name.filter(#fk_ident.eq(self.id)).load::<#struct_name>(conn)
-                        }
-                    }
+    restrict_visibility(items, relation_attrs)
+}
+
+// `query_<plural>` of many_to_many relations filters the child table with a subquery on the join table,
+// which diesel only accepts when both tables are listed in `allow_tables_to_appear_in_same_query!`.
+// This assertion fails at the declaration with a message naming the missing invocation,
+// instead of a trait bound error deep inside the generated query.
+fn generate_same_query_assertion(
+    relation_attrs: &RelationAttributes,
+) -> Option<proc_macro2::TokenStream> {
+    let schema = schema_path(relation_attrs);
+    let child_schema = child_schema(relation_attrs);
+    // The `through` table of has_many_through relations is read in a subquery like a join table.
+    let join_table = relation_attrs
+        .join_table
+        .as_ref()
+        .or(relation_attrs.through.as_ref())?;
+    let child_table = child_table_name(relation_attrs);
+    let join_table_ident = Ident::new(join_table, proc_macro2::Span::call_site());
+    let child_table_ident = Ident::new(&child_table, proc_macro2::Span::call_site());
+    let message = format!(
+        "`{}` and `{}` can't appear in the same query",
+        child_table, join_table
+    );
+    let note = format!(
+        "add `diesel::allow_tables_to_appear_in_same_query!({}, {});` next to your `table!` definitions",
+        child_table, join_table
+    );
+
+    Some(quote! {
+        const _: fn() = {
+            #[diagnostic::on_unimplemented(message = #message, note = #note)]
+            trait AllowedInSameQuery<T> {}
+            impl<T, U> AllowedInSameQuery<T> for U
+            where
+                T: diesel::Table,
+                U: diesel::query_source::TableNotEqual<T>,
+            {
+            }
+            fn assert_allowed_in_same_query<T, U: AllowedInSameQuery<T>>() {}
+            assert_allowed_in_same_query::<#schema::#join_table_ident::table, #child_schema::#child_table_ident::table>
+        };
+    })
+}
+
+// The macro can't read the schema, but it can name the join table columns: a typo in `fk_parent`,
+// `fk_child` or `through_fk` then fails at the declaration with "cannot find type `x` in module `join_table`".
+fn generate_join_columns_assertion(
+    relation_attrs: &RelationAttributes,
+) -> Option<proc_macro2::TokenStream> {
+    let schema = schema_path(relation_attrs);
+    let join_table = Ident::new(
+        relation_attrs
+            .join_table
+            .as_ref()
+            .or(relation_attrs.through.as_ref())?,
+        proc_macro2::Span::call_site(),
+    );
+    let columns = [
+        &relation_attrs.fk_parent,
+        &relation_attrs.fk_child,
+        &relation_attrs.through_fk,
+    ]
+    .into_iter()
+    .flatten()
+    .map(|column| Ident::new(column, proc_macro2::Span::call_site()));
+
+    Some(quote! {
+        const _: fn() = || {
+            fn assert_join_column<T: diesel::Column<Table = #schema::#join_table::table>>() {}
+            #(assert_join_column::<#schema::#join_table::#columns>();)*
+        };
+    })
+}
+
+// Returns the name of the wrapper generated for the `wrapper` attribute, e.g. `UserWithPosts`.
+// Without inflection the snake_case `plural` is only split on `_` and each word capitalized.
+fn wrapper_ident(struct_name: &Ident, relation_attrs: &RelationAttributes) -> Ident {
+    let plural = plural_name(relation_attrs);
+    let plural = if relation_attrs.no_inflection {
+        plural
+            .split('_')
+            .map(|word| {
+                let mut chars = word.chars();
+                chars
+                    .next()
+                    .map(|first| first.to_uppercase().chain(chars).collect::<String>())
+                    .unwrap_or_default()
+            })
+            .collect()
+    } else {
+        plural.to_pascal_case()
+    };
+    Ident::new(
+        &format!("{}With{}", struct_name, plural),
+        proc_macro2::Span::call_site(),
+    )
+}
+
+// Generates the memoizing loader of the wrapper, which depends on the connection bound.
+fn generate_wrapper_impl(
+    struct_name: &Ident,
+    relation_attrs: &RelationAttributes,
+    connection: &ConnectionParam,
+) -> Option<proc_macro2::TokenStream> {
+    let ConnectionParam {
+        bound: conn_bound,
+        ty: conn_ty,
+        prologue: conn_prologue,
+        arg: conn_arg,
+    } = connection;
+    if relation_attrs.relation_type != "one_to_many" || !relation_attrs.wrapper {
+        return None;
+    }
+
+    let wrapper_ident = wrapper_ident(struct_name, relation_attrs);
+    let child_type = child_type(relation_attrs);
+    let cache_ident = Ident::new(&plural_name(relation_attrs), proc_macro2::Span::call_site());
+
+    Some(quote! {
+        impl #wrapper_ident {
+            // Charge les enfants au premier appel, puis renvoie ceux déjà en cache sans requête.
+            pub fn #cache_ident<C>(&self, conn: #conn_ty) -> diesel::QueryResult<&[#child_type]>
+            where C: #conn_bound, { #conn_prologue
+                if let Some(children) = self.#cache_ident.get() {
+                    return Ok(children);
                 }
+                let children = self.inner.children(#conn_arg)?;
+                Ok(self.#cache_ident.get_or_init(|| children))
             }
         }
-        "one_to_one" => {
-            let child_ident = Ident::new(&child_model, proc_macro2::Span::call_site());
-            let fk_ident = Ident::new(fk, proc_macro2::Span::call_site());
+    })
+}
 
-            quote! {
-                impl #struct_name {
-                    // Obtient l'entité liée depuis l'entité courante.
-                    pub fn get_related_entity<C>(&self, conn: &C) -> diesel::QueryResult<Option<#child_ident>>
-                    where C: diesel::Connection, {
-                        use crate::schema::#child_ident::dsl::*;
-                        use diesel::prelude::*;
+// Generates `get_<dto plural>` for the `serialize_as` attribute: it loads the children
+// through `getter` and converts each of them with `From`, so the conversion can't fail.
+fn generate_dto_method(
+    relation_attrs: &RelationAttributes,
+    child_type: &syn::Type,
+    getter: &Ident,
+    connection: &ConnectionParam,
+) -> Option<proc_macro2::TokenStream> {
+    let ConnectionParam {
+        bound: conn_bound,
+        ty: conn_ty,
+        prologue: conn_prologue,
+        arg: conn_arg,
+    } = connection;
+    let dto = relation_attrs.serialize_as.as_ref()?;
+    let dto_type: syn::Path = syn::parse_str(dto).expect("Failed to parse the serialize_as path");
+    let dto_name = dto_type.segments.last()?.ident.to_string();
+    let dto_method_ident = Ident::new(
+        &format!("get_{}", dto_name.to_snake_case().to_plural()),
+        proc_macro2::Span::call_site(),
+    );
 
-                        #child_ident.filter(#fk_ident.eq(self.id)).first::<#child_ident>(conn).optional()
-                    }
+    Some(quote! {
+        // Charge les enfants puis les convertit en DTO prêts à être sérialisés.
+        pub fn #dto_method_ident<C>(&self, conn: #conn_ty) -> diesel::QueryResult<Vec<#dto_type>>
+        where
+            C: #conn_bound,
+            #dto_type: From<#child_type> + serde::Serialize,
+        { #conn_prologue
+            self.#getter(#conn_arg)
+                .map(|children| children.into_iter().map(#dto_type::from).collect())
+        }
+    })
+}
 
-                    // Définit ou met à jour l'entité liée.
-                    pub fn set_related_entity<C>(&self, conn: &C, entity: &#child_ident) -> diesel::QueryResult<#child_ident>
-                    where C: diesel::Connection, {
-                        use diesel::RunQueryDsl;
-                        use crate::schema::#child_ident::dsl::*;
+// Generates `get_<plural>_with_parent` for the `with_parent` attribute: it loads the children
+// through `getter` and pairs each of them with a clone of the parent.
+fn generate_with_parent_method(
+    relation_attrs: &RelationAttributes,
+    child_type: &syn::Type,
+    getter: &Ident,
+    connection: &ConnectionParam,
+) -> Option<proc_macro2::TokenStream> {
+    let ConnectionParam {
+        bound: conn_bound,
+        ty: conn_ty,
+        prologue: conn_prologue,
+        arg: conn_arg,
+    } = connection;
+    if !relation_attrs.with_parent {
+        return None;
+    }
+    let with_parent_ident = Ident::new(
+        &format!("get_{}_with_parent", plural_name(relation_attrs)),
+        proc_macro2::Span::call_site(),
+    );
 
-                        diesel::insert_into(#child_ident::table)
-                            .values(entity)
-                            .on_conflict(#fk_ident)
-                            .do_update()
-                            .set(entity)
-                            .get_result::<#child_ident>(conn)
-                    }
+    Some(quote! {
+        // Charge les enfants et associe chacun d'eux à une copie du parent.
+        pub fn #with_parent_ident<C>(&self, conn: #conn_ty) -> diesel::QueryResult<Vec<(#child_type, Self)>>
+        where
+            C: #conn_bound,
+            Self: Clone,
+        { #conn_prologue
+            self.#getter(#conn_arg)
+                .map(|children| children.into_iter().map(|child| (child, self.clone())).collect())
+        }
+    })
+}
+
+// A many-to-one relation whose parent is the annotated struct itself (e.g. `Employee.manager_id`).
+fn is_self_referential(struct_name: &Ident, relation_attrs: &RelationAttributes) -> bool {
+    relation_attrs.relation_type == "many_to_one" && *struct_name == child_type_name(relation_attrs)
+}
+
+// Generates the getters of a self-referential relation: `get_<singular>` loads the parent row and
+// `get_<inverse>` the rows pointing at this one. Both read the table of the struct alone, so the
+// generated queries never join the table with itself and need no `diesel::alias!`.
+fn generate_self_referential_impl(
+    struct_name: &Ident,
+    relation_attrs: &RelationAttributes,
+    connection: &ConnectionParam,
+) -> proc_macro2::TokenStream {
+    let ConnectionParam {
+        bound: conn_bound,
+        ty: conn_ty,
+        prologue: conn_prologue,
+        ..
+    } = connection;
+    let self_table = self_table(struct_name, relation_attrs);
+    let fk = relation_attrs
+        .fk_field
+        .as_deref()
+        .unwrap_or(&relation_attrs.fk);
+    let fk_ident = Ident::new(fk, proc_macro2::Span::call_site());
+    let parent_key_ident = Ident::new(
+        relation_attrs.parent_key.as_deref().unwrap_or("id"),
+        proc_macro2::Span::call_site(),
+    );
+    let key_type = key_type(&relation_attrs.fk_type);
+    // `manager_id` names the parent `manager` unless `singular` says otherwise.
+    let singular = relation_attrs
+        .singular
+        .clone()
+        .unwrap_or_else(|| fk.trim_end_matches("_id").to_string());
+    let parent_getter = Ident::new(&format!("get_{}", singular), proc_macro2::Span::call_site());
+    let inverse_getter = relation_attrs.inverse.as_ref().map(|inverse| {
+        let inverse_ident = Ident::new(&format!("get_{}", inverse), proc_macro2::Span::call_site());
+        let fk_column = Ident::new(
+            relation_attrs
+                .fk_column
+                .as_deref()
+                .unwrap_or(&relation_attrs.fk),
+            proc_macro2::Span::call_site(),
+        );
+        let parent_key = parent_key_value(relation_attrs);
+        quote! {
+            // Charge les lignes de la même table qui désignent cette instance comme parent.
+            pub fn #inverse_ident<C>(&self, conn: #conn_ty) -> diesel::QueryResult<Vec<Self>>
+            where C: #conn_bound, { #conn_prologue
+                use diesel::prelude::*;
+
+                #self_table::table
+                    .filter(#self_table::#fk_column.eq(#parent_key))
+                    .order(#self_table::#parent_key_ident)
+                    .load::<Self>(conn)
+            }
+        }
+    });
+
+    quote! {
+        impl #struct_name {
+            // Charge le parent de cette instance dans la même table, `None` à la racine de la hiérarchie.
+            // La clé étrangère peut être nullable : `Option<K>` et `K` se convertissent tous deux en `Option<K>`.
+            pub fn #parent_getter<C>(&self, conn: #conn_ty) -> diesel::QueryResult<Option<Self>>
+            where C: #conn_bound, { #conn_prologue
+                use diesel::prelude::*;
+
+                let parent_key: Option<#key_type> = self.#fk_ident.clone().into();
+                match parent_key {
+                    Some(parent_key) => #self_table::table
+                        .filter(#self_table::#parent_key_ident.eq(parent_key))
+                        .first::<Self>(conn)
+                        .optional(),
+                    None => Ok(None),
                 }
             }
+
+            #inverse_getter
         }
-        "many_to_many" => {
-            if let (Some(join_table), Some(fk_parent), Some(fk_child)) =
-                (join_table, fk_parent, fk_child)
-            {
-                let join_table_ident = Ident::new(&join_table, proc_macro2::Span::call_site());
-                let parent_fk_ident = Ident::new(&fk_parent, proc_macro2::Span::call_site());
-                let child_fk_ident = Ident::new(&fk_child, proc_macro2::Span::call_site());
+    }
+}
 
-                quote! {
-                    impl #struct_name {
-                        pub fn related_entities<C>(&self, conn: &C) -> diesel::QueryResult<Vec<#child_ident>>
-                        where
-                            C: diesel::Connection,
-                        {
-                            use diesel::prelude::*;
-                            use crate::schema::#join_table_ident::dsl as join_dsl;
-                            use crate::schema::#child_ident::dsl::*;
+// Generates `get_<plural>_paged` next to `get_<plural>_page` for the `paginate` attribute: the
+// same page addressed by a 1-based page number and a page size instead of a `Page`.
+fn generate_paged_method(
+    relation_attrs: &RelationAttributes,
+    child_type: &syn::Type,
+    connection: &ConnectionParam,
+) -> Option<proc_macro2::TokenStream> {
+    let ConnectionParam {
+        bound: conn_bound,
+        ty: conn_ty,
+        prologue: conn_prologue,
+        arg: conn_arg,
+    } = connection;
+    if !relation_attrs.paginate {
+        return None;
+    }
+    let plural = plural_name(relation_attrs);
+    let page_ident = Ident::new(
+        &format!("get_{}_page", plural),
+        proc_macro2::Span::call_site(),
+    );
+    let paged_ident = Ident::new(
+        &format!("get_{}_paged", plural),
+        proc_macro2::Span::call_site(),
+    );
 
-                            let related_ids = join_dsl::#join_table_ident
-                                .filter(join_dsl::#parent_fk_ident.eq(self.id))
-                                .select(join_dsl::#child_fk_ident)
-                                .load::<i32>(conn)?;
+    Some(quote! {
+        // Charge la page `page` (numérotée à partir de 1) de `per_page` enfants.
+        pub fn #paged_ident<C>(&self, conn: #conn_ty, page: i64, per_page: i64) -> diesel::QueryResult<Vec<#child_type>>
+        where C: #conn_bound, { #conn_prologue
+            let offset = (page.max(1) - 1).saturating_mul(per_page);
+            self.#page_ident(#conn_arg, crate::Page { limit: per_page, offset })
+        }
+    })
+}
 
-                            #child_ident.filter(id.eq_any(related_ids)).load::<#child_ident>(conn)
-                        }
+// Generates `get_<plural>_page_has_more` next to `get_<plural>_page` for the `paginate` attribute:
+// it loads one extra row to tell whether another page exists, instead of counting the children.
+fn generate_has_more_method(
+    relation_attrs: &RelationAttributes,
+    child_type: &syn::Type,
+    connection: &ConnectionParam,
+) -> Option<proc_macro2::TokenStream> {
+    let ConnectionParam {
+        bound: conn_bound,
+        ty: conn_ty,
+        prologue: conn_prologue,
+        arg: conn_arg,
+    } = connection;
+    if !relation_attrs.paginate {
+        return None;
+    }
+    let plural = plural_name(relation_attrs);
+    let page_ident = Ident::new(
+        &format!("get_{}_page", plural),
+        proc_macro2::Span::call_site(),
+    );
+    let has_more_ident = Ident::new(
+        &format!("get_{}_page_has_more", plural),
+        proc_macro2::Span::call_site(),
+    );
+
+    Some(quote! {
+        // Charge une page d'enfants et indique s'il en existe une suivante, en lisant une ligne de plus.
+        pub fn #has_more_ident<C>(&self, conn: #conn_ty, page: crate::Page) -> diesel::QueryResult<(Vec<#child_type>, bool)>
+        where C: #conn_bound, { #conn_prologue
+            // Une limite négative est traitée comme nulle ; la ligne de plus ne déborde pas sur `i64::MAX`.
+            let limit = page.limit.max(0);
+            let mut children = self.#page_ident(#conn_arg, crate::Page { limit: limit.saturating_add(1), ..page })?;
+            let limit = usize::try_from(limit).unwrap_or(usize::MAX);
+            let has_more = children.len() > limit;
+            children.truncate(limit);
+            Ok((children, has_more))
+        }
+    })
+}
+
+// Generates `get_<plural>_page_with_total` when both `paginate` and `count` are set: it loads the
+// page and counts all the children in one transaction, so that both come from the same state of
+// the table as far as the isolation level of the connection allows.
+fn generate_page_with_total_method(
+    relation_attrs: &RelationAttributes,
+    child_type: &syn::Type,
+    connection: &ConnectionParam,
+) -> Option<proc_macro2::TokenStream> {
+    let ConnectionParam {
+        bound: conn_bound,
+        ty: conn_ty,
+        prologue: conn_prologue,
+        arg: conn_arg,
+    } = connection;
+    if !relation_attrs.paginate || !relation_attrs.count {
+        return None;
+    }
+    let plural = plural_name(relation_attrs);
+    let page_ident = Ident::new(
+        &format!("get_{}_page", plural),
+        proc_macro2::Span::call_site(),
+    );
+    let count_ident = Ident::new(&format!("count_{}", plural), proc_macro2::Span::call_site());
+    let with_total_ident = Ident::new(
+        &format!("get_{}_page_with_total", plural),
+        proc_macro2::Span::call_site(),
+    );
+
+    Some(quote! {
+        // Charge une page d'enfants et leur nombre total dans la même transaction.
+        pub fn #with_total_ident<C>(&self, conn: #conn_ty, page: crate::Page) -> diesel::QueryResult<(Vec<#child_type>, i64)>
+        where C: #conn_bound, { #conn_prologue
+            conn.transaction::<_, diesel::result::Error, _>(|conn| {
+                let children = self.#page_ident(#conn_arg, page)?;
+                let total = self.#count_ident(#conn_arg)?;
+                Ok((children, total))
+            })
+        }
+    })
+}
+
+// Generates `<getter>_with_context` for the `context_errors` attribute: it wraps the errors of
+// `getter` in the `RelationError` defined by the user crate, naming the relation and the model.
+fn generate_context_method(
+    struct_name: &Ident,
+    relation_attrs: &RelationAttributes,
+    relation: &str,
+    getter: &Ident,
+    output: proc_macro2::TokenStream,
+    connection: &ConnectionParam,
+) -> Option<proc_macro2::TokenStream> {
+    let ConnectionParam {
+        bound: conn_bound,
+        ty: conn_ty,
+        prologue: conn_prologue,
+        arg: conn_arg,
+    } = connection;
+    if !relation_attrs.context_errors {
+        return None;
+    }
+    let context_ident = Ident::new(
+        &format!("{}_with_context", getter),
+        proc_macro2::Span::call_site(),
+    );
+    let model = struct_name.to_string();
+
+    Some(quote! {
+        // Comme le getter de la relation, mais l'erreur indique la relation et le modèle concernés.
+        pub fn #context_ident<C>(&self, conn: #conn_ty) -> Result<#output, crate::RelationError>
+        where C: #conn_bound, { #conn_prologue
+            self.#getter(#conn_arg).map_err(|source| crate::RelationError {
+                relation: #relation,
+                model: #model,
+                source,
+            })
+        }
+    })
+}
+
+// Generates `<getter>_pooled` for the `pool` attribute: it checks a connection out of a diesel r2d2
+// pool, runs `getter` on it and returns it to the pool when dropped, converting both errors with `?`.
+fn generate_pooled_method(
+    relation_attrs: &RelationAttributes,
+    getter: &Ident,
+    output: proc_macro2::TokenStream,
+    connection: &ConnectionParam,
+) -> Option<proc_macro2::TokenStream> {
+    if !relation_attrs.pool {
+        return None;
+    }
+    let conn_bound = &connection.bound;
+    let pooled_ident = Ident::new(
+        &format!("{}_pooled", getter),
+        proc_macro2::Span::call_site(),
+    );
+    let (error, error_bound) = match &relation_attrs.pool_error {
+        Some(pool_error) => {
+            let pool_error: syn::Type =
+                syn::parse_str(pool_error).expect("Failed to parse the pool_error type");
+            let error_bound = quote! {
+                #pool_error: From<diesel::r2d2::PoolError> + From<diesel::result::Error>,
+            };
+            (quote! { #pool_error }, error_bound)
+        }
+        None => (
+            quote! { Box<dyn std::error::Error + Send + Sync> },
+            quote! {},
+        ),
+    };
+
+    Some(quote! {
+        // Comme le getter de la relation, avec une connexion empruntée au pool le temps de la requête.
+        pub fn #pooled_ident<M>(&self, pool: &diesel::r2d2::Pool<M>) -> Result<#output, #error>
+        where
+            M: diesel::r2d2::ManageConnection,
+            M::Connection: #conn_bound,
+            #error_bound
+        {
+            let mut conn = pool.get()?;
+            Ok(self.#getter(&mut *conn)?)
+        }
+    })
+}
+
+// Generates `get_<plural>_nonempty` for the `nonempty` attribute: it loads the children through
+// `getter` and splits off the first one, so an empty relation is `None` rather than an empty `Vec`.
+fn generate_nonempty_method(
+    relation_attrs: &RelationAttributes,
+    child_type: &syn::Type,
+    getter: &Ident,
+    connection: &ConnectionParam,
+) -> Option<proc_macro2::TokenStream> {
+    let ConnectionParam {
+        bound: conn_bound,
+        ty: conn_ty,
+        prologue: conn_prologue,
+        arg: conn_arg,
+    } = connection;
+    if !relation_attrs.nonempty {
+        return None;
+    }
+    let nonempty_ident = Ident::new(
+        &format!("get_{}_nonempty", plural_name(relation_attrs)),
+        proc_macro2::Span::call_site(),
+    );
+
+    Some(quote! {
+        // Charge les enfants : `None` si la relation est vide, sinon le premier enfant et les suivants.
+        pub fn #nonempty_ident<C>(&self, conn: #conn_ty) -> diesel::QueryResult<Option<(#child_type, Vec<#child_type>)>>
+        where C: #conn_bound, { #conn_prologue
+            let mut children = self.#getter(#conn_arg)?.into_iter();
+            Ok(children.next().map(|first| (first, children.collect())))
+        }
+    })
+}
+
+// Generates `get_<plural>_shared` for the `shared_children` attribute: it loads the children
+// through `getter` and wraps each of them in an `Arc`.
+fn generate_shared_method(
+    relation_attrs: &RelationAttributes,
+    child_type: &syn::Type,
+    getter: &Ident,
+    connection: &ConnectionParam,
+) -> Option<proc_macro2::TokenStream> {
+    let ConnectionParam {
+        bound: conn_bound,
+        ty: conn_ty,
+        prologue: conn_prologue,
+        arg: conn_arg,
+    } = connection;
+    if !relation_attrs.shared_children {
+        return None;
+    }
+    let shared_ident = Ident::new(
+        &format!("get_{}_shared", plural_name(relation_attrs)),
+        proc_macro2::Span::call_site(),
+    );
+
+    Some(quote! {
+        // Charge les enfants partagés derrière un `Arc`, clonables sans copier les lignes.
+        pub fn #shared_ident<C>(&self, conn: #conn_ty) -> diesel::QueryResult<Vec<std::sync::Arc<#child_type>>>
+        where C: #conn_bound, { #conn_prologue
+            self.#getter(#conn_arg)
+                .map(|children| children.into_iter().map(std::sync::Arc::new).collect())
+        }
+    })
+}
+
+// Generates `get_<plural>_grouped_by_<column>` for the `group_by` attribute: it loads the children
+// through `getter` and buckets them by the `group_by` field, keyed by `group_by_type`.
+fn generate_grouped_method(
+    relation_attrs: &RelationAttributes,
+    child_type: &syn::Type,
+    getter: &Ident,
+    connection: &ConnectionParam,
+) -> Option<proc_macro2::TokenStream> {
+    let ConnectionParam {
+        bound: conn_bound,
+        ty: conn_ty,
+        prologue: conn_prologue,
+        arg: conn_arg,
+    } = connection;
+    let column = relation_attrs.group_by.as_deref()?;
+    let grouped_ident = Ident::new(
+        &format!("get_{}_grouped_by_{}", plural_name(relation_attrs), column),
+        proc_macro2::Span::call_site(),
+    );
+    let column_ident = Ident::new(column, proc_macro2::Span::call_site());
+    let group_key_type = key_type(&relation_attrs.group_by_type);
+    let grouping = group_children_by(
+        &Ident::new("groups", proc_macro2::Span::call_site()),
+        &group_key_type,
+        child_type,
+        quote! { child },
+        quote! { self.#getter(#conn_arg)? },
+        quote! { child.#column_ident.clone() },
+        quote! { child },
+    );
+
+    Some(quote! {
+        // Charge les enfants et les regroupe par valeur de la colonne choisie.
+        pub fn #grouped_ident<C>(&self, conn: #conn_ty) -> diesel::QueryResult<std::collections::HashMap<#group_key_type, Vec<#child_type>>>
+        where C: #conn_bound, { #conn_prologue
+            #grouping
+            Ok(groups)
+        }
+    })
+}
+
+// Returns the parent side of a join: `self.id`, or a borrow of the `parent_key` field since natural
+// keys (`String`, `Uuid`, ...) are often not `Copy`.
+fn parent_key_value(relation_attrs: &RelationAttributes) -> proc_macro2::TokenStream {
+    match &relation_attrs.parent_key {
+        Some(parent_key) => {
+            let parent_key = Ident::new(parent_key, proc_macro2::Span::call_site());
+            quote! { &self.#parent_key }
+        }
+        None => quote! { self.id },
+    }
+}
+
+// Returns the rows of `load`, a batch query binding the parent keys `ids` in an `IN` list. With
+// `chunk_size`, `ids` is shadowed by each chunk of keys in turn and the rows of every chunk are
+// merged, so that large batches stay under the bind parameter limit of the backend.
+fn batched_load(
+    relation_attrs: &RelationAttributes,
+    ids: &Ident,
+    load: proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    match relation_attrs.chunk_size {
+        Some(chunk_size) => {
+            let chunk_size = proc_macro2::Literal::usize_unsuffixed(chunk_size);
+            quote! {
+                {
+                    let mut rows = Vec::new();
+                    for #ids in #ids.chunks(#chunk_size) {
+                        rows.extend(#load?);
                     }
-                }
-            } else {
-                quote! {
-                    compile_error!("join_table, fk_parent, and fk_child attributes are required for many_to_many relations");
+                    rows
                 }
             }
         }
-        _ => panic!("Unsupported relation type: {}", relation_type),
+        None => quote! { #load? },
+    }
+}
+
+// Returns the ordering of `order_by`, on `default` when it is not set: the bare column when ascending,
+// so that it reads like diesel's default order, and `<column>.desc()` when descending.
+fn order_expression(
+    relation_attrs: &RelationAttributes,
+    default: &str,
+) -> proc_macro2::TokenStream {
+    let column = Ident::new(
+        relation_attrs.order_by.as_deref().unwrap_or(default),
+        proc_macro2::Span::call_site(),
+    );
+    if relation_attrs.order_desc {
+        quote! { #column.desc() }
+    } else {
+        quote! { #column }
+    }
+}
+
+// Returns the `type_column = "<type_value>"` condition of polymorphic relations.
+fn polymorphic_condition(relation_attrs: &RelationAttributes) -> Option<FilterCondition> {
+    let type_column = relation_attrs.type_column.as_ref()?;
+    let type_value = relation_attrs.type_value.as_deref().unwrap_or_default();
+    Some(FilterCondition::Eq(
+        type_column.clone(),
+        format!("{:?}", type_value),
+    ))
+}
+
+// Returns the `.filter(...)` call matching the discriminator of polymorphic relations, for the
+// mutators which don't apply `default_filter` but must not reach the children of another model.
+fn polymorphic_filter(relation_attrs: &RelationAttributes) -> proc_macro2::TokenStream {
+    let Some(type_column) = &relation_attrs.type_column else {
+        return quote! {};
+    };
+    let child_schema = child_schema(relation_attrs);
+    let child_table = Ident::new(
+        &child_table_name(relation_attrs),
+        proc_macro2::Span::call_site(),
+    );
+    let type_column = Ident::new(type_column, proc_macro2::Span::call_site());
+    let type_value = relation_attrs.type_value.as_deref().unwrap_or_default();
+    quote! { .filter(#child_schema::#child_table::#type_column.eq(#type_value)) }
+}
+
+// Returns the conditions every child query is filtered on, in the order they are applied.
+fn filter_conditions(relation_attrs: &RelationAttributes) -> Vec<FilterCondition> {
+    // Soft-deleted children are hidden from the loaders like a `<column> IS NULL` condition.
+    let soft_delete = relation_attrs
+        .soft_delete_column
+        .as_ref()
+        .map(|column| FilterCondition::IsNull(column.clone()))
+        .filter(|condition| !relation_attrs.default_filter.contains(condition));
+    // Likewise, polymorphic relations only see the rows whose discriminator names the parent.
+    let discriminator = polymorphic_condition(relation_attrs);
+    relation_attrs
+        .default_filter
+        .iter()
+        .cloned()
+        .chain(soft_delete)
+        .chain(discriminator)
+        .collect()
+}
+
+// Returns the `.filter(...)` call applying the `default_filter` conditions to a child table query,
+// or nothing when the relation has no default filter.
+fn default_filter(relation_attrs: &RelationAttributes) -> proc_macro2::TokenStream {
+    let child_schema = child_schema(relation_attrs);
+    let child_table = Ident::new(
+        &child_table_name(relation_attrs),
+        proc_macro2::Span::call_site(),
+    );
+    let column = |column: &str| {
+        let column = Ident::new(column, proc_macro2::Span::call_site());
+        quote! { #child_schema::#child_table::#column }
+    };
+    let value = |value: &str| -> proc_macro2::TokenStream {
+        value
+            .parse()
+            .expect("Failed to parse the default_filter value")
+    };
+    let conditions = filter_conditions(relation_attrs);
+    let mut conditions = conditions.iter().map(|condition| match condition {
+        FilterCondition::Eq(col, val) => {
+            let (col, val) = (column(col), value(val));
+            quote! { #col.eq(#val) }
+        }
+        FilterCondition::NotEq(col, val) => {
+            let (col, val) = (column(col), value(val));
+            quote! { #col.ne(#val) }
+        }
+        FilterCondition::Lt(col, val) => {
+            let (col, val) = (column(col), value(val));
+            quote! { #col.lt(#val) }
+        }
+        FilterCondition::Gt(col, val) => {
+            let (col, val) = (column(col), value(val));
+            quote! { #col.gt(#val) }
+        }
+        FilterCondition::IsNull(col) => {
+            let col = column(col);
+            quote! { #col.is_null() }
+        }
+        FilterCondition::IsNotNull(col) => {
+            let col = column(col);
+            quote! { #col.is_not_null() }
+        }
+        FilterCondition::In(col, vals) => {
+            let col = column(col);
+            let vals = vals.iter().map(|val| value(val));
+            quote! { #col.eq_any([#(#vals),*]) }
+        }
+    });
+
+    match conditions.next() {
+        Some(first) => quote! { .filter(#first #(.and(#conditions))*) },
+        None => quote! {},
+    }
+}
+
+// Generates `<plural>_query_debug` for the `debug_sql` attribute, on the query `children` runs.
+// `debug_query` only exposes the binds through its `Display` output, so they are also rendered one by one
+// from the values the relation binds: the parent key, then the values of `filter_conditions`, in query order.
+fn generate_debug_sql_method(
+    relation_attrs: &RelationAttributes,
+    backend: Option<&proc_macro2::TokenStream>,
+    children_query: &proc_macro2::TokenStream,
+) -> Option<proc_macro2::TokenStream> {
+    let backend = backend.filter(|_| relation_attrs.debug_sql)?;
+    let debug_ident = Ident::new(
+        &format!("{}_query_debug", plural_name(relation_attrs)),
+        proc_macro2::Span::call_site(),
+    );
+    let child_schema = child_schema(relation_attrs);
+    let child_table = Ident::new(
+        &child_table_name(relation_attrs),
+        proc_macro2::Span::call_site(),
+    );
+    let parent_key = parent_key_value(relation_attrs);
+    let conditions = filter_conditions(relation_attrs);
+    let filter_values = conditions
+        .iter()
+        .flat_map(|condition| match condition {
+            FilterCondition::Eq(_, val)
+            | FilterCondition::NotEq(_, val)
+            | FilterCondition::Lt(_, val)
+            | FilterCondition::Gt(_, val) => vec![val],
+            FilterCondition::IsNull(_) | FilterCondition::IsNotNull(_) => vec![],
+            FilterCondition::In(_, vals) => vals.iter().collect(),
+        })
+        .map(|val| -> proc_macro2::TokenStream {
+            val.parse()
+                .expect("Failed to parse the default_filter value")
+        });
+
+    Some(quote! {
+        // Renvoie le rendu de `debug_query` pour la requête de `children` (`<sql> -- binds: [...]`)
+        // et le rendu `Debug` de chaque valeur liée, dans l'ordre de la requête.
+        #[doc(hidden)]
+        pub fn #debug_ident(&self) -> (String, Vec<String>) {
+            use #child_schema::#child_table::dsl::*;
+            use diesel::prelude::*;
+
+            let query = #children_query;
+            let rendered = diesel::debug_query::<#backend, _>(&query).to_string();
+            (rendered, vec![format!("{:?}", #parent_key), #(format!("{:?}", #filter_values)),*])
+        }
+    })
+}
+
+// Generates `count_<plural>` and `has_<plural>` for the `count` and `exists` attributes,
+// both running on `children`, the query selecting the children of the instance.
+fn generate_count_methods(
+    relation_attrs: &RelationAttributes,
+    children: &proc_macro2::TokenStream,
+    backend: Option<&proc_macro2::TokenStream>,
+    connection: &ConnectionParam,
+) -> Vec<proc_macro2::TokenStream> {
+    let QueryRunner {
+        connection,
+        asyncness,
+        use_diesel_async,
+        await_kw,
+    } = query_runner(relation_attrs, backend, connection);
+    let ConnectionParam {
+        bound: conn_bound,
+        ty: conn_ty,
+        prologue: conn_prologue,
+        ..
+    } = connection;
+    let plural = plural_name(relation_attrs);
+    let mut methods = Vec::new();
+    if relation_attrs.count {
+        let count_ident = Ident::new(&format!("count_{}", plural), proc_macro2::Span::call_site());
+        methods.push(quote! {
+            // Compte les enfants de cette instance sans les charger.
+            pub #asyncness fn #count_ident<C>(&self, conn: #conn_ty) -> diesel::QueryResult<i64>
+            where C: #conn_bound, { #conn_prologue
+                use diesel::prelude::*;
+                #use_diesel_async
+
+                #children.count().get_result::<i64>(conn)#await_kw
+            }
+        });
+    }
+    if relation_attrs.exists {
+        let exists_ident = Ident::new(&format!("has_{}", plural), proc_macro2::Span::call_site());
+        methods.push(quote! {
+            // Indique si cette instance a au moins un enfant, sans les charger.
+            pub #asyncness fn #exists_ident<C>(&self, conn: #conn_ty) -> diesel::QueryResult<bool>
+            where C: #conn_bound, { #conn_prologue
+                use diesel::prelude::*;
+                #use_diesel_async
+
+                diesel::select(diesel::dsl::exists(#children)).get_result::<bool>(conn)#await_kw
+            }
+        });
+    }
+    methods
+}
+
+// Gives the `pub` items of `items` the `visibility` of the relation: free functions, structs and
+// inherent methods. Trait implementations are left as they are, their visibility being the trait's.
+fn restrict_visibility(
+    items: proc_macro2::TokenStream,
+    relation_attrs: &RelationAttributes,
+) -> proc_macro2::TokenStream {
+    let Some(visibility) = &relation_attrs.visibility else {
+        return items;
+    };
+    let visibility: syn::Visibility =
+        syn::parse_str(visibility).expect("Failed to parse the visibility");
+    // Items that don't parse are emitted unchanged, for rustc to report them at the call site.
+    let Ok(mut file) = syn::parse2::<syn::File>(items.clone()) else {
+        return items;
+    };
+    let restrict = |vis: &mut syn::Visibility| {
+        if matches!(vis, syn::Visibility::Public(_)) {
+            *vis = visibility.clone();
+        }
+    };
+    for item in &mut file.items {
+        match item {
+            syn::Item::Fn(item_fn) => restrict(&mut item_fn.vis),
+            syn::Item::Struct(item_struct) => restrict(&mut item_struct.vis),
+            syn::Item::Impl(item_impl) if item_impl.trait_.is_none() => {
+                for impl_item in &mut item_impl.items {
+                    if let syn::ImplItem::Method(method) = impl_item {
+                        restrict(&mut method.vis);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    quote! { #file }
+}
+
+// Parses a key type attribute such as `fk_child_type`, defaulting to `i32`.
+fn key_type(key_type: &Option<String>) -> syn::Type {
+    syn::parse_str(key_type.as_deref().unwrap_or("i32")).expect("Failed to parse the key type")
+}
+
+fn generate_relation_code(
+    struct_name: &Ident,
+    relation_attrs: &RelationAttributes,
+) -> proc_macro2::TokenStream {
+    if relation_attrs.backends == ["any"] {
+        let items = generate_any_backend_impl(struct_name, relation_attrs)
+            .into_iter()
+            .map(|item| restrict_visibility(item, relation_attrs));
+        return quote! { #(#items)* };
+    }
+    if relation_attrs.backends.is_empty() {
+        let items = generate_relation_impl(struct_name, relation_attrs, None)
+            .into_iter()
+            .map(|item| restrict_visibility(item, relation_attrs));
+        return quote! { #(#items)* };
+    }
+
+    // Un seul backend : l'implémentation est toujours générée, sans dépendre d'une feature.
+    if let [backend] = &relation_attrs.backends[..] {
+        let backend_ty = backend_type(backend);
+        let items = generate_relation_impl(struct_name, relation_attrs, Some(&backend_ty))
+            .into_iter()
+            .map(|item| restrict_visibility(item, relation_attrs));
+        return quote! { #(#items)* };
+    }
+
+    // Une implémentation par backend, chacune derrière la feature du même nom.
+    // Les features déjà listées sont exclues pour que deux implémentations ne soient jamais actives ensemble.
+    let mut impls = proc_macro2::TokenStream::new();
+    for (index, backend) in relation_attrs.backends.iter().enumerate() {
+        let backend_ty = backend_type(backend);
+        let previous = &relation_attrs.backends[..index];
+        let items = generate_relation_impl(struct_name, relation_attrs, Some(&backend_ty))
+            .into_iter()
+            .map(|item| restrict_visibility(item, relation_attrs));
+
+        let cfg = quote! { #[cfg(all(feature = #backend, not(any(#(feature = #previous),*))))] };
+
+        impls.extend(quote! { #(#cfg #items)* });
+    }
+    impls
+}
+
+// Returns the type and the expression of the query selecting the children of `self`, before boxing,
+// for the relation types that have `query_<plural>()`. Many-to-many and has-many-through relations
+// read the join or `through` table in a subquery; polymorphic relations also match the discriminator.
+fn relation_query(
+    relation_attrs: &RelationAttributes,
+) -> (proc_macro2::TokenStream, proc_macro2::TokenStream) {
+    let schema = schema_path(relation_attrs);
+    let child_schema = child_schema(relation_attrs);
+    let child_table = Ident::new(
+        &child_table_name(relation_attrs),
+        proc_macro2::Span::call_site(),
+    );
+    let fk_ident = Ident::new(
+        relation_attrs
+            .fk_column
+            .as_deref()
+            .unwrap_or(&relation_attrs.fk),
+        proc_macro2::Span::call_site(),
+    );
+    match relation_attrs.relation_type.as_str() {
+        "many_to_many" => {
+            let join_table = Ident::new(
+                relation_attrs.join_table.as_deref().unwrap_or_default(),
+                proc_macro2::Span::call_site(),
+            );
+            let parent_fk = Ident::new(
+                relation_attrs.fk_parent.as_deref().unwrap_or_default(),
+                proc_macro2::Span::call_site(),
+            );
+            let child_fk = Ident::new(
+                relation_attrs.fk_child.as_deref().unwrap_or_default(),
+                proc_macro2::Span::call_site(),
+            );
+            let parent_key_type = key_type(&relation_attrs.fk_parent_type);
+            // The parent key is cloned, the boxed query being `'static`.
+            (
+                quote! {
+                    diesel::dsl::Filter<
+                        #child_schema::#child_table::table,
+                        diesel::dsl::EqAny<
+                            #child_schema::#child_table::id,
+                            diesel::dsl::Select<
+                                diesel::dsl::Filter<
+                                    #schema::#join_table::table,
+                                    diesel::dsl::Eq<#schema::#join_table::#parent_fk, #parent_key_type>,
+                                >,
+                                #schema::#join_table::#child_fk,
+                            >,
+                        >,
+                    >
+                },
+                quote! {
+                    #child_schema::#child_table::table.filter(
+                        #child_schema::#child_table::id.eq_any(
+                            #schema::#join_table::table
+                                .filter(#schema::#join_table::#parent_fk.eq(self.id.clone()))
+                                .select(#schema::#join_table::#child_fk),
+                        ),
+                    )
+                },
+            )
+        }
+        "has_many_through" => {
+            let through = Ident::new(
+                relation_attrs.through.as_deref().unwrap_or_default(),
+                proc_macro2::Span::call_site(),
+            );
+            let through_fk = Ident::new(
+                relation_attrs.through_fk.as_deref().unwrap_or_default(),
+                proc_macro2::Span::call_site(),
+            );
+            let parent_key_type = key_type(&relation_attrs.fk_type);
+            (
+                quote! {
+                    diesel::dsl::Filter<
+                        #child_schema::#child_table::table,
+                        diesel::dsl::EqAny<
+                            #child_schema::#child_table::#fk_ident,
+                            diesel::dsl::Select<
+                                diesel::dsl::Filter<
+                                    #schema::#through::table,
+                                    diesel::dsl::Eq<#schema::#through::#through_fk, #parent_key_type>,
+                                >,
+                                #schema::#through::id,
+                            >,
+                        >,
+                    >
+                },
+                quote! {
+                    #child_schema::#child_table::table.filter(
+                        #child_schema::#child_table::#fk_ident.eq_any(
+                            #schema::#through::table
+                                .filter(#schema::#through::#through_fk.eq(self.id.clone()))
+                                .select(#schema::#through::id),
+                        ),
+                    )
+                },
+            )
+        }
+        _ => {
+            let fk_key_type = key_type(&relation_attrs.fk_type);
+            // A natural `parent_key` is cloned, the boxed query being `'static`.
+            let self_key = match &relation_attrs.parent_key {
+                Some(parent_key) => {
+                    let parent_key = Ident::new(parent_key, proc_macro2::Span::call_site());
+                    quote! { self.#parent_key.clone() }
+                }
+                None => quote! { self.id },
+            };
+            let fk_filter = quote! {
+                diesel::dsl::Filter<
+                    #child_schema::#child_table::table,
+                    diesel::dsl::Eq<#child_schema::#child_table::#fk_ident, #fk_key_type>,
+                >
+            };
+            let query_type = match &relation_attrs.type_column {
+                Some(type_column) => {
+                    let type_column = Ident::new(type_column, proc_macro2::Span::call_site());
+                    quote! {
+                        diesel::dsl::Filter<
+                            #fk_filter,
+                            diesel::dsl::Eq<#child_schema::#child_table::#type_column, &'static str>,
+                        >
+                    }
+                }
+                None => fk_filter,
+            };
+            let discriminator = polymorphic_filter(relation_attrs);
+            (
+                query_type,
+                quote! {
+                    #child_schema::#child_table::table
+                        .filter(#child_schema::#child_table::#fk_ident.eq(#self_key))#discriminator
+                },
+            )
+        }
+    }
+}
+
+// Generates the relation for `backend = "any"`, generic over the backend of the connection. Every
+// method loads the boxed `query_<plural>()` and declares the diesel bounds of its query, which the
+// compiler checks where the method is called, against the concrete connection.
+fn generate_any_backend_impl(
+    struct_name: &Ident,
+    relation_attrs: &RelationAttributes,
+) -> Vec<proc_macro2::TokenStream> {
+    let connection = &connection_param(relation_attrs, None);
+    let ConnectionParam {
+        bound: conn_bound,
+        ty: conn_ty,
+        prologue: conn_prologue,
+        ..
+    } = connection;
+    let child_schema = child_schema(relation_attrs);
+    let child_table = Ident::new(
+        &child_table_name(relation_attrs),
+        proc_macro2::Span::call_site(),
+    );
+    let child_type = child_type(relation_attrs);
+    let plural = plural_name(relation_attrs);
+    let query_ident = Ident::new(&format!("query_{}", plural), proc_macro2::Span::call_site());
+    let (query_type, query) = relation_query(relation_attrs);
+    let (query_method, query_fn) = generate_query_method(
+        struct_name,
+        relation_attrs,
+        None,
+        connection,
+        query_type.clone(),
+        query,
+    );
+
+    let conn_db = quote! { <C as diesel::Connection>::Backend };
+    let boxed_type = quote! { #child_schema::#child_table::BoxedQuery<'static, #conn_db> };
+    let boxed_bound = quote! {
+        #query_type: diesel::query_dsl::methods::BoxedDsl<'static, #conn_db, Output = #boxed_type>,
+    };
+    // Same order as the other modes: `order_by`, or the primary key for the relations read through a subquery.
+    let default_order = (relation_attrs.relation_type != "one_to_many").then_some("id");
+    let (order, order_bound) = match relation_attrs.order_by.as_deref().or(default_order) {
+        Some(column) => {
+            let column = Ident::new(column, proc_macro2::Span::call_site());
+            let column = quote! { #child_schema::#child_table::#column };
+            let (order, order_type) = if relation_attrs.order_desc {
+                (
+                    quote! { #column.desc() },
+                    quote! { diesel::dsl::Desc<#column> },
+                )
+            } else {
+                (column.clone(), column)
+            };
+            (
+                quote! { .order(#order) },
+                quote! { #boxed_type: diesel::query_dsl::methods::OrderDsl<#order_type, Output = #boxed_type>, },
+            )
+        }
+        None => (quote! {}, quote! {}),
+    };
+    let getter_ident = match relation_attrs.relation_type.as_str() {
+        "one_to_many" => Ident::new("children", proc_macro2::Span::call_site()),
+        "many_to_many" => Ident::new("related_entities", proc_macro2::Span::call_site()),
+        _ => Ident::new(&format!("get_{}", plural), proc_macro2::Span::call_site()),
+    };
+    let count_method = relation_attrs.count.then(|| {
+        let count_ident = Ident::new(&format!("count_{}", plural), proc_macro2::Span::call_site());
+        quote! {
+            // Compte les enfants de cette instance sans les charger.
+            pub fn #count_ident<C>(&self, conn: #conn_ty) -> diesel::QueryResult<i64>
+            where
+                C: #conn_bound,
+                #boxed_bound
+                #boxed_type: diesel::query_dsl::methods::SelectDsl<diesel::dsl::CountStar>,
+                diesel::dsl::Select<#boxed_type, diesel::dsl::CountStar>: diesel::query_dsl::LoadQuery<'static, C, i64>,
+            { #conn_prologue
+                use diesel::prelude::*;
+
+                self.#query_ident::<#conn_db>().count().get_result::<i64>(conn)
+            }
+        }
+    });
+
+    let mut items = vec![quote! {
+        impl #struct_name {
+            // Charge les enfants de cette instance, quel que soit le backend de la connexion.
+            pub fn #getter_ident<C>(&self, conn: #conn_ty) -> diesel::QueryResult<Vec<#child_type>>
+            where
+                C: #conn_bound,
+                #boxed_bound
+                #order_bound
+                #boxed_type: diesel::query_dsl::LoadQuery<'static, C, #child_type>,
+            { #conn_prologue
+                use diesel::prelude::*;
+
+                self.#query_ident::<#conn_db>()#order.load::<#child_type>(conn)
+            }
+
+            #count_method
+
+            #query_method
+        }
+    }];
+    items.extend(query_fn);
+    items.extend(generate_conn_type_assertion(relation_attrs, connection));
+    items
+}
+
+// Generates `query_<plural>()`, the relation-scoped boxed query that callers refine with diesel
+// (`filter`, `order`, `limit`, ...) before loading it. Without a `backend` the query is generic over `DB`.
+// With the `query_fn` attribute, also returns the free function `<plural>_of(parent)` building the same query.
+// With `filtered`, the method comes with `get_<plural>_filtered(conn, predicate)`, which refines it with `filter`.
+fn generate_query_method(
+    struct_name: &Ident,
+    relation_attrs: &RelationAttributes,
+    backend: Option<&proc_macro2::TokenStream>,
+    connection: &ConnectionParam,
+    query_type: proc_macro2::TokenStream,
+    query: proc_macro2::TokenStream,
+) -> (proc_macro2::TokenStream, Option<proc_macro2::TokenStream>) {
+    let child_schema = child_schema(relation_attrs);
+    let child_table = Ident::new(
+        &child_table_name(relation_attrs),
+        proc_macro2::Span::call_site(),
+    );
+    let plural = plural_name(relation_attrs);
+    let query_ident = Ident::new(&format!("query_{}", plural), proc_macro2::Span::call_site());
+    let (db_generics, db_type, db_bound) = match backend {
+        Some(backend) => (quote! {}, backend.clone(), quote! {}),
+        None => (
+            quote! { <DB> },
+            quote! { DB },
+            quote! { DB: diesel::backend::Backend, },
+        ),
+    };
+    let where_clause = quote! {
+        where
+            #db_bound
+            #query_type: diesel::query_dsl::methods::BoxedDsl<'static, #db_type, Output = #child_schema::#child_table::BoxedQuery<'static, #db_type>>,
+    };
+
+    let query_method = quote! {
+        // Requête limitée aux enfants de cette instance, à compléter avant de la charger.
+        pub fn #query_ident #db_generics(&self) -> #child_schema::#child_table::BoxedQuery<'static, #db_type>
+        #where_clause
+        {
+            use diesel::prelude::*;
+
+            #query.into_boxed()
+        }
+    };
+    // The methods below load `query_<plural>()` once the caller has refined it.
+    let ConnectionParam {
+        bound: conn_bound,
+        ty: conn_ty,
+        prologue: conn_prologue,
+        ..
+    } = connection;
+    let child_type = child_type(relation_attrs);
+    let (conn_db, boxed_query) = match backend {
+        Some(backend) => (backend.clone(), quote! { self.#query_ident() }),
+        None => (
+            quote! { <C as diesel::Connection>::Backend },
+            quote! { self.#query_ident::<<C as diesel::Connection>::Backend>() },
+        ),
+    };
+    let boxed_type = quote! { #child_schema::#child_table::BoxedQuery<'static, #conn_db> };
+    // The boxed query reads every column of the table in order; `selectable` and `columns`
+    // re-select the row so that embedded (`#[diesel(embed)]`) models and projections load too.
+    let selection = if relation_attrs.selectable {
+        Some((
+            quote! { <#child_type as diesel::SelectableHelper<#conn_db>>::as_select() },
+            quote! { diesel::dsl::AsSelect<#child_type, #conn_db> },
+        ))
+    } else {
+        selected_columns(relation_attrs).map(|columns| (columns.clone(), columns))
+    };
+    let (select, loaded_type, select_bound) = match selection {
+        Some((selection, selection_type)) => (
+            quote! { .select(#selection) },
+            quote! { diesel::dsl::Select<#boxed_type, #selection_type> },
+            quote! { #boxed_type: diesel::query_dsl::methods::SelectDsl<#selection_type>, },
+        ),
+        None => (quote! {}, boxed_type.clone(), quote! {}),
+    };
+    let filtered_method = relation_attrs.filtered.then(|| {
+        let filtered_ident = Ident::new(&format!("get_{}_filtered", plural), proc_macro2::Span::call_site());
+        quote! {
+            // Charge les enfants qui satisfont aussi `predicate`, une expression booléenne diesel sur la table enfant.
+            pub fn #filtered_ident<C, P>(&self, conn: #conn_ty, predicate: P) -> diesel::QueryResult<Vec<#child_type>>
+            where
+                C: #conn_bound,
+                #query_type: diesel::query_dsl::methods::BoxedDsl<'static, #conn_db, Output = #boxed_type>,
+                #boxed_type: diesel::query_dsl::methods::FilterDsl<P, Output = #boxed_type>,
+                #select_bound
+                #loaded_type: diesel::query_dsl::LoadQuery<'static, C, #child_type>,
+            { #conn_prologue
+                use diesel::prelude::*;
+
+                #boxed_query.filter(predicate)#select.load::<#child_type>(conn)
+            }
+        }
+    });
+    let where_method = relation_attrs.where_closure.then(|| {
+        let where_ident = Ident::new(&format!("get_{}_where", plural), proc_macro2::Span::call_site());
+        quote! {
+            // Charge les enfants de la requête boxée telle que `refine` l'a complétée (filtres, tri, limite, ...).
+            pub fn #where_ident<C, F>(&self, conn: #conn_ty, refine: F) -> diesel::QueryResult<Vec<#child_type>>
+            where
+                C: #conn_bound,
+                F: FnOnce(#boxed_type) -> #boxed_type,
+                #query_type: diesel::query_dsl::methods::BoxedDsl<'static, #conn_db, Output = #boxed_type>,
+                #select_bound
+                #loaded_type: diesel::query_dsl::LoadQuery<'static, C, #child_type>,
+            { #conn_prologue
+                use diesel::prelude::*;
+
+                refine(#boxed_query)#select.load::<#child_type>(conn)
+            }
+        }
+    });
+    let query_method = quote! {
+        #query_method
+        #filtered_method
+        #where_method
+    };
+    let query_fn = relation_attrs.query_fn.then(|| {
+        let query_fn_ident = Ident::new(&format!("{}_of", plural), proc_macro2::Span::call_site());
+        quote! {
+            // Requête des enfants de `parent`, à combiner avec le DSL de diesel dans des requêtes écrites à la main.
+            pub fn #query_fn_ident #db_generics(parent: &#struct_name) -> #child_schema::#child_table::BoxedQuery<'static, #db_type>
+            #where_clause
+            {
+                parent.#query_ident()
+            }
+        }
+    });
+    (query_method, query_fn)
+}
+
+// How the generated methods receive their connection: the bound on `C`, the parameter type,
+// the statements turning the parameter into the `&mut C` diesel expects, and the argument
+// passed when a generated method delegates to another one.
+#[derive(Clone)]
+struct ConnectionParam {
+    bound: proc_macro2::TokenStream,
+    ty: proc_macro2::TokenStream,
+    prologue: proc_macro2::TokenStream,
+    arg: proc_macro2::TokenStream,
+}
+
+// By default methods take `&mut C`. With `connection_type`, `C` is a copyable shared
+// reference to that type (e.g. `&PoolConn`, whose `&self` API relies on interior mutability),
+// taken by value and rebound mutably so that the method bodies stay the same.
+fn connection_param(
+    relation_attrs: &RelationAttributes,
+    backend: Option<&proc_macro2::TokenStream>,
+) -> ConnectionParam {
+    let base_bound = match backend {
+        Some(backend) => quote! { diesel::connection::LoadConnection<Backend = #backend> },
+        None => quote! { diesel::connection::LoadConnection },
+    };
+    match &relation_attrs.connection_type {
+        Some(connection_type) => {
+            let connection_type: syn::Type =
+                syn::parse_str(connection_type).expect("Failed to parse the connection_type path");
+            ConnectionParam {
+                bound: quote! { #base_bound + Copy + std::ops::Deref<Target = #connection_type> },
+                ty: quote! { C },
+                prologue: quote! { let mut conn = conn; let conn = &mut conn; },
+                arg: quote! { *conn },
+            }
+        }
+        None => ConnectionParam {
+            bound: base_bound,
+            ty: quote! { &mut C },
+            prologue: quote! {},
+            arg: quote! { conn },
+        },
+    }
+}
+
+// `conn_type` doesn't change the generated signatures, already generic over the connection: this
+// assertion checks the declared type against their bound where the relation is declared, and names
+// that type in the error instead of leaving an unsatisfied bound at each call site.
+fn generate_conn_type_assertion(
+    relation_attrs: &RelationAttributes,
+    connection: &ConnectionParam,
+) -> Option<proc_macro2::TokenStream> {
+    let conn_type: syn::Type = syn::parse_str(relation_attrs.conn_type.as_ref()?)
+        .expect("Failed to parse the conn_type type");
+    let bound = &connection.bound;
+    let message = format!(
+        "`{}` can't be used as the connection of this relation",
+        relation_attrs.conn_type.as_deref().unwrap_or_default()
+    );
+    let note = format!("the generated methods require `C: {}`", bound)
+        .replace(" :: ", "::")
+        .replace(" < ", "<")
+        .replace(" >", ">");
+
+    Some(quote! {
+        const _: fn() = {
+            #[diagnostic::on_unimplemented(message = #message, note = #note)]
+            trait RelationConnection {}
+            impl<C: #bound> RelationConnection for C {}
+            fn assert_relation_connection<C: RelationConnection>() {}
+            assert_relation_connection::<#conn_type>
+        };
+    })
+}
+
+// Async methods take `&mut C` over a `diesel_async` connection, whatever `connection_type` says.
+fn async_connection_param(backend: Option<&proc_macro2::TokenStream>) -> ConnectionParam {
+    ConnectionParam {
+        bound: match backend {
+            Some(backend) => quote! { diesel_async::AsyncConnection<Backend = #backend> },
+            None => quote! { diesel_async::AsyncConnection },
+        },
+        ty: quote! { &mut C },
+        prologue: quote! {},
+        arg: quote! { conn },
+    }
+}
+
+// How the cheap queries (`count_<plural>`, `has_<plural>`, ...) are run: synchronously through
+// `connection`, or as `async fn`s over `diesel_async` with the `async` attribute, in which case
+// `use_diesel_async` brings its `RunQueryDsl` in scope over diesel's and `await_kw` awaits the result.
+struct QueryRunner {
+    connection: ConnectionParam,
+    asyncness: proc_macro2::TokenStream,
+    use_diesel_async: proc_macro2::TokenStream,
+    await_kw: proc_macro2::TokenStream,
+}
+
+fn query_runner(
+    relation_attrs: &RelationAttributes,
+    backend: Option<&proc_macro2::TokenStream>,
+    connection: &ConnectionParam,
+) -> QueryRunner {
+    if cfg!(feature = "async") && relation_attrs.async_ {
+        QueryRunner {
+            connection: async_connection_param(backend),
+            asyncness: quote! { async },
+            use_diesel_async: quote! { use diesel_async::RunQueryDsl; },
+            await_kw: quote! { .await },
+        }
+    } else {
+        QueryRunner {
+            connection: connection.clone(),
+            asyncness: quote! {},
+            use_diesel_async: quote! {},
+            await_kw: quote! {},
+        }
+    }
+}
+
+fn generate_relation_impl(
+    struct_name: &Ident,
+    relation_attrs: &RelationAttributes,
+    backend: Option<&proc_macro2::TokenStream>,
+) -> Vec<proc_macro2::TokenStream> {
+    let schema = schema_path(relation_attrs);
+    let child_schema = child_schema(relation_attrs);
+    let connection = &connection_param(relation_attrs, backend);
+    let ConnectionParam {
+        bound: conn_bound,
+        ty: conn_ty,
+        prologue: conn_prologue,
+        arg: conn_arg,
+    } = connection;
+    // Colonne SQL de la clé étrangère, distincte du champ Rust quand il est renommé.
+    let fk = relation_attrs
+        .fk_column
+        .as_deref()
+        .unwrap_or(&relation_attrs.fk);
+    let relation_type = relation_attrs.relation_type.as_str();
+    let child_type = child_type(relation_attrs);
+    let child_table = Ident::new(
+        &child_table_name(relation_attrs),
+        proc_macro2::Span::call_site(),
+    );
+    let fk_ident = Ident::new(fk, proc_macro2::Span::call_site());
+    let default_filter = default_filter(relation_attrs);
+    // Avec `selectable`, les colonnes sont choisies par `Selectable` plutôt que par leur ordre dans la table ;
+    // avec `columns`, seules les colonnes listées sont lues, dans leur ordre.
+    let columns = selected_columns(relation_attrs);
+    let (select_child, returning_child) = if relation_attrs.selectable {
+        (
+            quote! { .select(<#child_type as diesel::SelectableHelper<_>>::as_select()) },
+            quote! { .returning(<#child_type as diesel::SelectableHelper<_>>::as_returning()) },
+        )
+    } else if let Some(columns) = &columns {
+        (
+            quote! { .select(#columns) },
+            quote! { .returning(#columns) },
+        )
+    } else {
+        (quote! {}, quote! {})
+    };
+    // Une projection en tuple n'est pas une ligne complète : elle ne peut être ni insérée ni modifiée.
+    let readonly = relation_attrs.readonly || is_projection(relation_attrs);
+    // Éléments générés hors du bloc `impl`, chacun soumis au `cfg` du backend.
+    let mut free_items = Vec::new();
+    let relation_impl = match relation_type {
+        "one_to_many" => {
+            let plural = plural_name(relation_attrs);
+            let cursor_ident = Ident::new(
+                &format!("get_{}_cursor", plural),
+                proc_macro2::Span::call_site(),
+            );
+            let count_map_ident = Ident::new(
+                &format!("get_{}_count_map", plural),
+                proc_macro2::Span::call_site(),
+            );
+
+            let fk_key_type = key_type(&relation_attrs.fk_type);
+            // Clé du parent : `id`, ou la colonne `parent_key` des clés naturelles, clonée quand elle est copiée.
+            let parent_key = parent_key_value(relation_attrs);
+            // Sans `order_by`, les enfants restent dans l'ordre renvoyé par la base.
+            let children_order = relation_attrs.order_by.is_some().then(|| {
+                let child_order = order_expression(relation_attrs, "id");
+                quote! { .order(#child_order) }
+            });
+            // Requête des enfants de l'instance, écrite avec les noms de `dsl::*` : `children`, le curseur,
+            // `_into`, `_collected` et `<plural>_query_debug` la partagent pour rester identiques.
+            let children_query = quote! {
+                #child_table.filter(#fk_ident.eq(#parent_key))#default_filter #children_order #select_child
+            };
+            let parent_field = Ident::new(
+                relation_attrs.parent_key.as_deref().unwrap_or("id"),
+                proc_macro2::Span::call_site(),
+            );
+            let owned_parent_key =
+                |receiver: proc_macro2::TokenStream| match &relation_attrs.parent_key {
+                    Some(_) => quote! { #receiver.#parent_field.clone() },
+                    None => quote! { #receiver.#parent_field },
+                };
+            let each_parent_key = owned_parent_key(quote! { parent });
+            // Une clé de type personnalisé doit être comparable à la colonne `fk` et relue depuis celle-ci ;
+            // les bornes sont déclarées pour que les erreurs pointent la clé plutôt que la requête.
+            let key_bounds_for = |connection: proc_macro2::TokenStream| {
+                relation_attrs.fk_type.as_ref().map(|_| {
+                    let fk_sql_type = quote! { diesel::dsl::SqlTypeOf<#child_schema::#child_table::#fk_ident> };
+                    quote! {
+                        #fk_key_type: diesel::expression::AsExpression<#fk_sql_type>
+                            + diesel::serialize::ToSql<#fk_sql_type, <C as #connection>::Backend>
+                            + diesel::deserialize::FromSql<#fk_sql_type, <C as #connection>::Backend>,
+                    }
+                })
+            };
+            let key_bounds = key_bounds_for(quote! { diesel::Connection });
+            // Discriminant des relations polymorphes, pour les requêtes qui n'appliquent pas `default_filter`.
+            let discriminator = polymorphic_filter(relation_attrs);
+
+            // Méthodes optionnelles, activées par les attributs de la relation.
+            let mut extra_methods = Vec::new();
+            extra_methods.extend(generate_dto_method(
+                relation_attrs,
+                &child_type,
+                &Ident::new("children", proc_macro2::Span::call_site()),
+                connection,
+            ));
+            extra_methods.extend(generate_with_parent_method(
+                relation_attrs,
+                &child_type,
+                &Ident::new("children", proc_macro2::Span::call_site()),
+                connection,
+            ));
+            extra_methods.extend(generate_nonempty_method(
+                relation_attrs,
+                &child_type,
+                &Ident::new("children", proc_macro2::Span::call_site()),
+                connection,
+            ));
+            extra_methods.extend(generate_has_more_method(
+                relation_attrs,
+                &child_type,
+                connection,
+            ));
+            extra_methods.extend(generate_paged_method(
+                relation_attrs,
+                &child_type,
+                connection,
+            ));
+            extra_methods.extend(generate_context_method(
+                struct_name,
+                relation_attrs,
+                &plural,
+                &Ident::new("children", proc_macro2::Span::call_site()),
+                quote! { Vec<#child_type> },
+                connection,
+            ));
+            extra_methods.extend(generate_pooled_method(
+                relation_attrs,
+                &Ident::new("children", proc_macro2::Span::call_site()),
+                quote! { Vec<#child_type> },
+                connection,
+            ));
+            extra_methods.extend(generate_page_with_total_method(
+                relation_attrs,
+                &child_type,
+                connection,
+            ));
+            extra_methods.extend(generate_grouped_method(
+                relation_attrs,
+                &child_type,
+                &Ident::new("children", proc_macro2::Span::call_site()),
+                connection,
+            ));
+            extra_methods.extend(generate_shared_method(
+                relation_attrs,
+                &child_type,
+                &Ident::new("children", proc_macro2::Span::call_site()),
+                connection,
+            ));
+            let (query_type, query) = relation_query(relation_attrs);
+            let (query_method, query_fn) = generate_query_method(
+                struct_name,
+                relation_attrs,
+                backend,
+                connection,
+                query_type,
+                query,
+            );
+            extra_methods.push(query_method);
+            free_items.extend(query_fn);
+            extra_methods.extend(generate_count_methods(
+                relation_attrs,
+                &quote! {
+                    #child_schema::#child_table::table
+                        .filter(#child_schema::#child_table::#fk_ident.eq(#parent_key))#default_filter
+                },
+                backend,
+                connection,
+            ));
+            extra_methods.extend(generate_debug_sql_method(
+                relation_attrs,
+                backend,
+                &children_query,
+            ));
+            if let (true, Some(order_by)) = (relation_attrs.recent, &relation_attrs.order_by) {
+                let recent_ident = Ident::new(
+                    &format!("get_recent_{}", plural),
+                    proc_macro2::Span::call_site(),
+                );
+                let order_ident = Ident::new(order_by, proc_macro2::Span::call_site());
+                extra_methods.push(quote! {
+                    // Récupère les `n` enfants les plus récents, du plus récent au plus ancien.
+                    pub fn #recent_ident<C>(&self, conn: #conn_ty, n: i64) -> diesel::QueryResult<Vec<#child_type>>
+                    where C: #conn_bound, { #conn_prologue
+                        use #child_schema::#child_table::dsl::*;
+                        use diesel::prelude::*;
+
+                        #child_table
+                            .filter(#fk_ident.eq(#parent_key))#default_filter
+                            .order(#order_ident.desc())
+                            .limit(n)
+                            #select_child.load::<#child_type>(conn)
+                    }
+                });
+            }
+            // Colonnes lues à côté d'une autre valeur (rang, clé du parent), comme dans `(user_id, all_columns)`.
+            let child_columns = if relation_attrs.selectable {
+                quote! { <#child_type as diesel::SelectableHelper<_>>::as_select() }
+            } else if let Some(columns) = &columns {
+                columns.clone()
+            } else {
+                quote! { #child_schema::#child_table::all_columns }
+            };
+            if relation_attrs.load_into {
+                let into_ident = Ident::new(
+                    &format!("get_{}_into", plural),
+                    proc_macro2::Span::call_site(),
+                );
+                extra_methods.push(quote! {
+                    // Remplit `buf` avec les enfants après l'avoir vidé, pour réutiliser son allocation d'un parent à l'autre.
+                    // Les lignes sont lues une à une, sans `Vec` intermédiaire.
+                    pub fn #into_ident<C>(&self, conn: #conn_ty, buf: &mut Vec<#child_type>) -> diesel::QueryResult<()>
+                    where C: #conn_bound, { #conn_prologue
+                        use #child_schema::#child_table::dsl::*;
+                        use diesel::prelude::*;
+
+                        buf.clear();
+                        let rows = #children_query
+                            .load_iter::<#child_type, diesel::connection::DefaultLoadingMode>(conn)?;
+                        for row in rows {
+                            buf.push(row?);
+                        }
+                        Ok(())
+                    }
+                });
+            }
+            if let Some(collect_into) = &relation_attrs.collect_into {
+                let collected_ident = Ident::new(
+                    &format!("get_{}_collected", plural),
+                    proc_macro2::Span::call_site(),
+                );
+                let collection: syn::Type =
+                    syn::parse_str(collect_into).expect("Failed to parse the collect_into type");
+                extra_methods.push(quote! {
+                    // Collecte les enfants directement dans la collection choisie, via `FromIterator`.
+                    pub fn #collected_ident<C>(&self, conn: #conn_ty) -> diesel::QueryResult<#collection>
+                    where C: #conn_bound, { #conn_prologue
+                        use #child_schema::#child_table::dsl::*;
+                        use diesel::prelude::*;
+
+                        #children_query
+                            .load_iter::<#child_type, diesel::connection::DefaultLoadingMode>(conn)?
+                            .collect()
+                    }
+                });
+            }
+            if let Some(join_condition) = &relation_attrs.join_condition {
+                let matching_ident = Ident::new(
+                    &format!("get_{}_matching", plural),
+                    proc_macro2::Span::call_site(),
+                );
+                let ident = |name: &str| Ident::new(name, proc_macro2::Span::call_site());
+                let condition = match join_condition {
+                    JoinCondition::Lt(column, field) => {
+                        let (column, field) = (ident(column), ident(field));
+                        quote! { #column.lt(&self.#field) }
+                    }
+                    JoinCondition::Le(column, field) => {
+                        let (column, field) = (ident(column), ident(field));
+                        quote! { #column.le(&self.#field) }
+                    }
+                    JoinCondition::Gt(column, field) => {
+                        let (column, field) = (ident(column), ident(field));
+                        quote! { #column.gt(&self.#field) }
+                    }
+                    JoinCondition::Ge(column, field) => {
+                        let (column, field) = (ident(column), ident(field));
+                        quote! { #column.ge(&self.#field) }
+                    }
+                    JoinCondition::Between(column, low, high) => {
+                        let (column, low, high) = (ident(column), ident(low), ident(high));
+                        quote! { #column.between(&self.#low, &self.#high) }
+                    }
+                };
+                extra_methods.push(quote! {
+                    // Charge les enfants vérifiant la condition de jointure (intervalle, ensemble imbriqué…)
+                    // plutôt que l'égalité sur la clé étrangère.
+                    pub fn #matching_ident<C>(&self, conn: #conn_ty) -> diesel::QueryResult<Vec<#child_type>>
+                    where C: #conn_bound, { #conn_prologue
+                        use #child_schema::#child_table::dsl::*;
+                        use diesel::prelude::*;
+
+                        #child_table.filter(#condition)#default_filter #select_child.load::<#child_type>(conn)
+                    }
+                });
+            }
+            if let (true, Some(order_by)) = (relation_attrs.sorted, &relation_attrs.order_by) {
+                let sorted_ident = Ident::new(
+                    &format!("get_{}_sorted", plural),
+                    proc_macro2::Span::call_site(),
+                );
+                let order_ident = Ident::new(order_by, proc_macro2::Span::call_site());
+                extra_methods.push(quote! {
+                    // Charge les enfants triés sur `order_by` dans le sens choisi à l'exécution ;
+                    // la requête est boxée pour que les deux sens aient le même type.
+                    pub fn #sorted_ident<C>(&self, conn: #conn_ty, dir: crate::SortDir) -> diesel::QueryResult<Vec<#child_type>>
+                    where C: #conn_bound, { #conn_prologue
+                        use #child_schema::#child_table::dsl::*;
+                        use diesel::prelude::*;
+
+                        let query = #child_table.filter(#fk_ident.eq(#parent_key))#default_filter.into_boxed();
+                        let query = match dir {
+                            crate::SortDir::Asc => query.order(#order_ident.asc()),
+                            crate::SortDir::Desc => query.order(#order_ident.desc()),
+                        };
+                        query #select_child.load::<#child_type>(conn)
+                    }
+                });
+            }
+            if let (true, Some(order_by)) = (relation_attrs.ranked, &relation_attrs.order_by) {
+                let ranked_ident = Ident::new(
+                    &format!("get_{}_ranked", plural),
+                    proc_macro2::Span::call_site(),
+                );
+                let child_order = order_expression(relation_attrs, "id");
+                let is_postgres = backend.is_some_and(|backend| {
+                    backend.to_string() == backend_type("postgres").to_string()
+                });
+                let ranked_children = if is_postgres {
+                    let direction = if relation_attrs.order_desc {
+                        " DESC"
+                    } else {
+                        ""
+                    };
+                    let row_number =
+                        format!("ROW_NUMBER() OVER (ORDER BY {}{})", order_by, direction);
+                    quote! {
+                        // Postgres numérote les lignes lui-même avec une fonction de fenêtrage.
+                        #child_table
+                            .filter(#fk_ident.eq(#parent_key))#default_filter
+                            .order(#child_order)
+                            .select((diesel::dsl::sql::<diesel::sql_types::BigInt>(#row_number), #child_columns))
+                            .load::<(i64, #child_type)>(conn)
+                    }
+                } else {
+                    quote! {
+                        // Les autres backends chargent les enfants dans l'ordre et les numérotent ensuite.
+                        #child_table
+                            .filter(#fk_ident.eq(#parent_key))#default_filter
+                            .order(#child_order)
+                            #select_child.load::<#child_type>(conn)
+                            .map(|children| (1..).zip(children).collect())
+                    }
+                };
+                extra_methods.push(quote! {
+                    // Récupère les enfants accompagnés de leur rang, de 1 à N dans l'ordre de `order_by`.
+                    pub fn #ranked_ident<C>(&self, conn: #conn_ty) -> diesel::QueryResult<Vec<(i64, #child_type)>>
+                    where C: #conn_bound, { #conn_prologue
+                        use #child_schema::#child_table::dsl::*;
+                        use diesel::prelude::*;
+
+                        #ranked_children
+                    }
+                });
+            }
+            if let (Some(latest), Some(limit)) =
+                (&relation_attrs.latest, relation_attrs.latest_limit)
+            {
+                let latest_ident = Ident::new(
+                    &format!("get_latest_{}", plural),
+                    proc_macro2::Span::call_site(),
+                );
+                let latest_column = Ident::new(latest, proc_macro2::Span::call_site());
+                let limit = proc_macro2::Literal::i64_unsuffixed(limit);
+                extra_methods.push(quote! {
+                    // Récupère les derniers enfants, du plus récent au plus ancien, pour les fils d'activité.
+                    pub fn #latest_ident<C>(&self, conn: #conn_ty) -> diesel::QueryResult<Vec<#child_type>>
+                    where C: #conn_bound, { #conn_prologue
+                        use #child_schema::#child_table::dsl::*;
+                        use diesel::prelude::*;
+
+                        #child_table
+                            .filter(#fk_ident.eq(#parent_key))#default_filter
+                            .order(#latest_column.desc())
+                            .limit(#limit)
+                            #select_child.load::<#child_type>(conn)
+                    }
+                });
+            }
+            if let Some(latest_by) = &relation_attrs.latest_by {
+                let singular = singular_name(relation_attrs);
+                let latest_ident = Ident::new(
+                    &format!("get_latest_{}", singular),
+                    proc_macro2::Span::call_site(),
+                );
+                let first_ident = Ident::new(
+                    &format!("get_first_{}", singular),
+                    proc_macro2::Span::call_site(),
+                );
+                let latest_by = Ident::new(latest_by, proc_macro2::Span::call_site());
+                extra_methods.push(quote! {
+                    // Récupère l'enfant le plus récent selon `latest_by`, s'il y en a un.
+                    pub fn #latest_ident<C>(&self, conn: #conn_ty) -> diesel::QueryResult<Option<#child_type>>
+                    where C: #conn_bound, { #conn_prologue
+                        use #child_schema::#child_table::dsl::*;
+                        use diesel::prelude::*;
+
+                        #child_table
+                            .filter(#fk_ident.eq(#parent_key))#default_filter
+                            .order(#latest_by.desc())
+                            #select_child.first::<#child_type>(conn)
+                            .optional()
+                    }
+
+                    // Récupère l'enfant le plus ancien selon `latest_by`, s'il y en a un.
+                    pub fn #first_ident<C>(&self, conn: #conn_ty) -> diesel::QueryResult<Option<#child_type>>
+                    where C: #conn_bound, { #conn_prologue
+                        use #child_schema::#child_table::dsl::*;
+                        use diesel::prelude::*;
+
+                        #child_table
+                            .filter(#fk_ident.eq(#parent_key))#default_filter
+                            .order(#latest_by.asc())
+                            #select_child.first::<#child_type>(conn)
+                            .optional()
+                    }
+                });
+            }
+            if !relation_attrs.aggregates.is_empty() {
+                let stats_ident = stats_ident(relation_attrs);
+                let load_stats_ident = Ident::new(
+                    &format!("load_with_{}_stats", singular_name(relation_attrs)),
+                    proc_macro2::Span::call_site(),
+                );
+                let mut selects = Vec::new();
+                let mut row_types = Vec::new();
+                let mut values = Vec::new();
+                let mut fields = Vec::new();
+                for aggregate in &relation_attrs.aggregates {
+                    let field = Ident::new(&aggregate.field_name(), proc_macro2::Span::call_site());
+                    match aggregate {
+                        Aggregate::Count => {
+                            selects.push(quote! { diesel::dsl::count_star() });
+                            row_types.push(quote! { i64 });
+                            values.push(quote! { #field });
+                        }
+                        Aggregate::Sum(column) => {
+                            let column_ident = Ident::new(column, proc_macro2::Span::call_site());
+                            selects.push(quote! { diesel::dsl::sum(#column_ident) });
+                            row_types.push(quote! { Option<i64> });
+                            values.push(quote! { #field.unwrap_or(0) });
+                        }
+                    }
+                    fields.push(field);
+                }
+                let load_stats_rows = batched_load(
+                    relation_attrs,
+                    &Ident::new("parent_ids", proc_macro2::Span::call_site()),
+                    quote! {
+                        #child_table
+                            .filter(#fk_ident.eq_any(parent_ids))#default_filter
+                            .group_by(#fk_ident)
+                            .select((#fk_ident, #(#selects),*))
+                            .load::<(#fk_key_type, #(#row_types),*)>(conn)
+                    },
+                );
+                extra_methods.push(quote! {
+                    // Charge les agrégats des enfants de chaque parent en une seule requête groupée.
+                    // Les parents sans enfant reçoivent des agrégats à zéro.
+                    pub fn #load_stats_ident<C>(parents: Vec<Self>, conn: #conn_ty) -> diesel::QueryResult<Vec<(Self, #stats_ident)>>
+                    where C: #conn_bound, #key_bounds { #conn_prologue
+                        use #child_schema::#child_table::dsl::*;
+                        use diesel::prelude::*;
+
+                        let parent_ids: Vec<#fk_key_type> = parents.iter().map(|parent| #each_parent_key).collect();
+                        let rows: Vec<(#fk_key_type, #(#row_types),*)> = #load_stats_rows;
+
+                        let mut stats: std::collections::HashMap<#fk_key_type, #stats_ident> = rows
+                            .into_iter()
+                            .map(|(parent_id, #(#fields),*)| (parent_id, #stats_ident { #(#fields: #values),* }))
+                            .collect();
+                        Ok(parents
+                            .into_iter()
+                            .map(|parent| {
+                                let parent_stats = stats.remove(&parent.#parent_field).unwrap_or_default();
+                                (parent, parent_stats)
+                            })
+                            .collect())
+                    }
+                });
+            }
+
+            if relation_attrs.paginate {
+                let page_ident = Ident::new(
+                    &format!("get_{}_page", plural),
+                    proc_macro2::Span::call_site(),
+                );
+                let child_order = order_expression(relation_attrs, "id");
+                extra_methods.push(quote! {
+                    // Charge une page d'enfants, dans un ordre stable d'une page à l'autre.
+                    pub fn #page_ident<C>(&self, conn: #conn_ty, page: crate::Page) -> diesel::QueryResult<Vec<#child_type>>
+                    where C: #conn_bound, { #conn_prologue
+                        use #child_schema::#child_table::dsl::*;
+                        use diesel::prelude::*;
+
+                        #child_table
+                            .filter(#fk_ident.eq(#parent_key))#default_filter
+                            .order(#child_order)
+                            .limit(page.limit)
+                            .offset(page.offset)
+                            #select_child.load::<#child_type>(conn)
+                    }
+                });
+            }
+            if relation_attrs.ids {
+                let ids_ident = Ident::new(
+                    &format!("get_{}_ids", singular_name(relation_attrs)),
+                    proc_macro2::Span::call_site(),
+                );
+                let id_type = key_type(&relation_attrs.ids_type);
+                extra_methods.push(quote! {
+                    // Charge seulement les identifiants des enfants, sans désérialiser les lignes.
+                    pub fn #ids_ident<C>(&self, conn: #conn_ty) -> diesel::QueryResult<Vec<#id_type>>
+                    where C: #conn_bound, { #conn_prologue
+                        use #child_schema::#child_table::dsl::*;
+                        use diesel::prelude::*;
+
+                        #child_table
+                            .filter(#fk_ident.eq(#parent_key))#default_filter
+                            .order(id)
+                            .select(id)
+                            .load::<#id_type>(conn)
+                    }
+                });
+            }
+            if let Some(cursor_column) = &relation_attrs.cursor_column {
+                let after_ident = Ident::new(
+                    &format!("get_{}_after", plural),
+                    proc_macro2::Span::call_site(),
+                );
+                let cursor_ident = Ident::new(cursor_column, proc_macro2::Span::call_site());
+                let cursor_type = key_type(&relation_attrs.cursor_type);
+                extra_methods.push(quote! {
+                    // Pagination par curseur : les `limit` enfants suivant `cursor` sur `cursor_column`,
+                    // que la base atteint par l'index au lieu de sauter des lignes.
+                    pub fn #after_ident<C>(&self, conn: #conn_ty, cursor: Option<#cursor_type>, limit: i64) -> diesel::QueryResult<Vec<#child_type>>
+                    where C: #conn_bound, { #conn_prologue
+                        use #child_schema::#child_table::dsl::*;
+                        use diesel::prelude::*;
+
+                        let mut query = #child_table.filter(#fk_ident.eq(#parent_key))#default_filter.into_boxed();
+                        if let Some(cursor) = cursor {
+                            query = query.filter(#cursor_ident.gt(cursor));
+                        }
+                        query.order(#cursor_ident.asc()).limit(limit) #select_child.load::<#child_type>(conn)
+                    }
+                });
+            }
+            // Ordre commun aux chargeurs groupés, synchrones ou non.
+            let child_order = order_expression(relation_attrs, "id");
+            if relation_attrs.eager_loading {
+                let load_with_ident = Ident::new(
+                    &format!("load_with_{}", plural),
+                    proc_macro2::Span::call_site(),
+                );
+                let load_with_except_ident = Ident::new(
+                    &format!("load_with_{}_except", plural),
+                    proc_macro2::Span::call_site(),
+                );
+                let load_map_ident = Ident::new(
+                    &format!("load_{}_map", plural),
+                    proc_macro2::Span::call_site(),
+                );
+                let grouping = group_children_by(
+                    &Ident::new("children", proc_macro2::Span::call_site()),
+                    &fk_key_type,
+                    &child_type,
+                    quote! { (parent_id, child) },
+                    batched_load(
+                        relation_attrs,
+                        &Ident::new("parent_ids", proc_macro2::Span::call_site()),
+                        quote! {
+                            #child_table
+                                .filter(#fk_ident.eq_any(parent_ids))#default_filter
+                                .order(#child_order)
+                                .select((#fk_ident, #child_columns))
+                                .load::<(#fk_key_type, #child_type)>(conn)
+                        },
+                    ),
+                    quote! { parent_id },
+                    quote! { child },
+                );
+                extra_methods.push(quote! {
+                    // Charge les enfants de plusieurs parents en une seule requête.
+                    pub fn #load_with_ident<C>(parents: Vec<Self>, conn: #conn_ty) -> diesel::QueryResult<Vec<(Self, Vec<#child_type>)>>
+                    where C: #conn_bound, #key_bounds { #conn_prologue
+                        Self::#load_with_except_ident(parents, &std::collections::HashSet::new(), #conn_arg)
+                    }
+
+                    // Comme le chargement groupé, mais les parents dont la clé est dans `skip_keys` sont exclus
+                    // de la requête et reçoivent une liste vide, pour garder la forme de `parents`.
+                    #[doc(hidden)]
+                    pub fn #load_with_except_ident<C>(
+                        parents: Vec<Self>,
+                        skip_keys: &std::collections::HashSet<#fk_key_type>,
+                        conn: #conn_ty,
+                    ) -> diesel::QueryResult<Vec<(Self, Vec<#child_type>)>>
+                    where C: #conn_bound, #key_bounds { #conn_prologue
+                        use #child_schema::#child_table::dsl::*;
+                        use diesel::prelude::*;
+
+                        let parent_ids: Vec<#fk_key_type> = parents
+                            .iter()
+                            .map(|parent| #each_parent_key)
+                            .filter(|parent_id| !skip_keys.contains(parent_id))
+                            .collect();
+                        #grouping
+                        Ok(parents
+                            .into_iter()
+                            .map(|parent| {
+                                let parent_children = children.remove(&parent.#parent_field).unwrap_or_default();
+                                (parent, parent_children)
+                            })
+                            .collect())
+                    }
+
+                    // Charge les enfants de plusieurs parents, rangés par clé de parent, sans consommer `parents`.
+                    // Les parents sans enfant n'apparaissent pas dans la map.
+                    pub fn #load_map_ident<C>(parents: &[Self], conn: #conn_ty) -> diesel::QueryResult<std::collections::HashMap<#fk_key_type, Vec<#child_type>>>
+                    where C: #conn_bound, #key_bounds { #conn_prologue
+                        use #child_schema::#child_table::dsl::*;
+                        use diesel::prelude::*;
+
+                        let parent_ids: Vec<#fk_key_type> = parents.iter().map(|parent| #each_parent_key).collect();
+                        #grouping
+                        Ok(children)
+                    }
+                });
+            }
+            if let (Some(nested), Some(nested_child)) =
+                (&relation_attrs.nested, &relation_attrs.nested_child)
+            {
+                let load_with_ident = Ident::new(
+                    &format!("load_with_{}", plural),
+                    proc_macro2::Span::call_site(),
+                );
+                let load_nested_ident = Ident::new(
+                    &format!("load_with_{}_and_{}", plural, nested),
+                    proc_macro2::Span::call_site(),
+                );
+                let child_load_ident = Ident::new(
+                    &format!("load_with_{}", nested),
+                    proc_macro2::Span::call_site(),
+                );
+                let nested_child: syn::Type =
+                    syn::parse_str(nested_child).expect("Failed to parse the nested_child type");
+                extra_methods.push(quote! {
+                    // Charge deux niveaux de relations en deux requêtes groupées : les enfants de tous les
+                    // parents, puis les petits-enfants de tous les enfants, redistribués dans l'ordre.
+                    pub fn #load_nested_ident<C>(parents: Vec<Self>, conn: #conn_ty) -> diesel::QueryResult<Vec<(Self, Vec<(#child_type, Vec<#nested_child>)>)>>
+                    where C: #conn_bound, #key_bounds { #conn_prologue
+                        let loaded = Self::#load_with_ident(parents, #conn_arg)?;
+                        let mut counts = Vec::with_capacity(loaded.len());
+                        let mut all_children = Vec::new();
+                        for (parent, parent_children) in loaded {
+                            counts.push((parent, parent_children.len()));
+                            all_children.extend(parent_children);
+                        }
+                        let mut grandchildren = <#child_type>::#child_load_ident(all_children, #conn_arg)?.into_iter();
+                        Ok(counts
+                            .into_iter()
+                            .map(|(parent, count)| (parent, grandchildren.by_ref().take(count).collect()))
+                            .collect())
+                    }
+                });
+            }
+            // Like the other async methods, only emitted with the `async` feature.
+            if cfg!(feature = "async") && relation_attrs.async_ && relation_attrs.streaming {
+                let chunks_ident = Ident::new(
+                    &format!("get_{}_chunks", plural),
+                    proc_macro2::Span::call_site(),
+                );
+                let async_conn_bound = async_connection_param(backend).bound;
+                extra_methods.push(quote! {
+                    // Flux des enfants par lots d'au plus `chunk_size`, regroupés au fil d'une seule requête.
+                    pub fn #chunks_ident<'a, C>(&'a self, conn: &'a mut C, chunk_size: usize) -> impl futures_util::Stream<Item = diesel::QueryResult<Vec<#child_type>>> + Send + 'a
+                    where C: #async_conn_bound, {
+                        use #child_schema::#child_table::dsl::*;
+                        use diesel::prelude::*;
+                        use diesel_async::RunQueryDsl;
+                        use futures_util::TryStreamExt;
+
+                        futures_util::stream::once(
+                            #child_table.filter(#fk_ident.eq(#parent_key))#default_filter #select_child.load_stream::<#child_type>(conn),
+                        )
+                        .try_flatten()
+                        .try_chunks(chunk_size)
+                        .map_err(|error| error.1)
+                    }
+                });
+            }
+            // Like the `async` cheap queries, only emitted with the `async` feature, so that sync-only
+            // crates never see `diesel_async`.
+            if cfg!(feature = "async") && relation_attrs.eager_loading_stream {
+                let load_with_stream_ident = Ident::new(
+                    &format!("load_with_{}_stream", plural),
+                    proc_macro2::Span::call_site(),
+                );
+                let async_conn_bound = async_connection_param(backend).bound;
+                let key_bounds = key_bounds_for(quote! { diesel_async::AsyncConnection });
+                let stream_rows = quote! {
+                    let rows = #child_table
+                        .filter(#fk_ident.eq_any(parent_ids))#default_filter
+                        .order(#child_order)
+                        .select((#fk_ident, #child_columns))
+                        .load_stream::<(#fk_key_type, #child_type)>(conn)
+                        .await?;
+                    let mut rows = std::pin::pin!(rows);
+                    while let Some((parent_id, child)) = rows.try_next().await? {
+                        children.entry(parent_id).or_default().push(child);
+                    }
+                };
+                // With `chunk_size`, one stream per chunk of keys, read one after the other.
+                let stream_rows = match relation_attrs.chunk_size {
+                    Some(chunk_size) => {
+                        let chunk_size = proc_macro2::Literal::usize_unsuffixed(chunk_size);
+                        quote! {
+                            for parent_ids in parent_ids.chunks(#chunk_size) {
+                                #stream_rows
+                            }
+                        }
+                    }
+                    None => stream_rows,
+                };
+                extra_methods.push(quote! {
+                    // Chargement groupé asynchrone : les enfants sont regroupés au fil du flux de lignes,
+                    // sans être mis en tampon avant le regroupement.
+                    pub async fn #load_with_stream_ident<C>(parents: Vec<Self>, conn: &mut C) -> diesel::QueryResult<Vec<(Self, Vec<#child_type>)>>
+                    where C: #async_conn_bound, #key_bounds {
+                        use #child_schema::#child_table::dsl::*;
+                        use diesel::{BoolExpressionMethods, ExpressionMethods, QueryDsl};
+                        use diesel_async::RunQueryDsl;
+                        use futures_util::TryStreamExt;
+
+                        let parent_ids: Vec<#fk_key_type> = parents.iter().map(|parent| #each_parent_key).collect();
+                        let mut children: std::collections::HashMap<#fk_key_type, Vec<#child_type>> =
+                            std::collections::HashMap::new();
+                        #stream_rows
+                        Ok(parents
+                            .into_iter()
+                            .map(|parent| {
+                                let parent_children = children.remove(&parent.#parent_field).unwrap_or_default();
+                                (parent, parent_children)
+                            })
+                            .collect())
+                    }
+                });
+            }
+            if relation_attrs.cache {
+                let cached_ident = Ident::new(
+                    &format!("get_cached_{}", plural),
+                    proc_macro2::Span::call_site(),
+                );
+                let relation_name = format!("{}::{}", struct_name, plural);
+                extra_methods.push(quote! {
+                    // Consulte le cache fourni par l'application avant d'interroger la base,
+                    // puis y range les enfants chargés en cas d'absence.
+                    pub fn #cached_ident<R, C>(&self, cache: &R, conn: #conn_ty) -> diesel::QueryResult<Vec<#child_type>>
+                    where R: crate::RelationCache<#fk_key_type, #child_type>, C: #conn_bound, { #conn_prologue
+                        let parent_key: &#fk_key_type = &self.#parent_field;
+                        if let Some(cached) = cache.get(parent_key, #relation_name) {
+                            return Ok(cached);
+                        }
+                        let loaded = self.children(#conn_arg)?;
+                        cache.put(parent_key, #relation_name, &loaded);
+                        Ok(loaded)
+                    }
+                });
+            }
+
+            // Mutateurs, absents des relations en lecture seule (`readonly`).
+            let update_method = relation_attrs.update.then(|| {
+                let update_ident = Ident::new(
+                    &format!("update_{}", singular_name(relation_attrs)),
+                    proc_macro2::Span::call_site(),
+                );
+                quote! {
+                    // Applique `changes` à un enfant de cette instance et renvoie la ligne modifiée.
+                    pub fn #update_ident<C, Changes>(&self, conn: #conn_ty, child_id: impl Into<i32>, changes: Changes) -> diesel::QueryResult<#child_type>
+                    where
+                        C: #conn_bound,
+                        Changes: diesel::query_builder::AsChangeset<Target = #child_schema::#child_table::table>,
+                        Changes::Changeset: diesel::query_builder::QueryFragment<<C as diesel::Connection>::Backend>,
+                    { #conn_prologue
+                        use #child_schema::#child_table::dsl::*;
+                        use diesel::prelude::*;
+
+                        let child_id: i32 = child_id.into();
+                        diesel::update(#child_table.filter(id.eq(child_id)).filter(#fk_ident.eq(#parent_key))#discriminator)
+                            .set(changes)
+                            #returning_child.get_result::<#child_type>(conn)
+                    }
+                }
+            });
+            let soft_delete_methods = relation_attrs.soft_delete_column.as_ref().map(|column| {
+                let column = Ident::new(column, proc_macro2::Span::call_site());
+                let soft_delete_ident = Ident::new(
+                    &format!("soft_delete_{}", plural),
+                    proc_macro2::Span::call_site(),
+                );
+                let hard_delete_ident = Ident::new(
+                    &format!("hard_delete_{}", plural),
+                    proc_macro2::Span::call_site(),
+                );
+                quote! {
+                    // Marque comme supprimés les enfants qui ne le sont pas encore, sans effacer les lignes.
+                    pub fn #soft_delete_ident<C>(&self, conn: #conn_ty) -> Result<usize, diesel::result::Error>
+                    where C: #conn_bound, { #conn_prologue
+                        use #child_schema::#child_table::dsl::*;
+                        use diesel::prelude::*;
+
+                        diesel::update(#child_table.filter(#fk_ident.eq(#parent_key))#discriminator.filter(#column.is_null()))
+                            .set(#column.eq(diesel::dsl::now))
+                            .execute(conn)
+                    }
+
+                    // Supprime définitivement tous les enfants, y compris ceux déjà marqués comme supprimés.
+                    pub fn #hard_delete_ident<C>(&self, conn: #conn_ty) -> Result<usize, diesel::result::Error>
+                    where C: #conn_bound, { #conn_prologue
+                        use #child_schema::#child_table::dsl::*;
+                        use diesel::prelude::*;
+
+                        diesel::delete(#child_table.filter(#fk_ident.eq(#parent_key))#discriminator).execute(conn)
+                    }
+                }
+            });
+            let mutators = (!readonly).then(|| {
+                quote! {
+                    pub fn add_child<C>(&self, conn: #conn_ty, new_child: &#child_type) -> Result<usize, diesel::result::Error>
+                    where C: #conn_bound, { #conn_prologue
+                        use diesel::RunQueryDsl;
+                        diesel::insert_into(#child_schema::#child_table::table).values(new_child).execute(conn)
+                    }
+
+                    // Supprimer un enfant spécifique
+                    pub fn remove_child<C>(&self, conn: #conn_ty, child_id: impl Into<i32>) -> Result<usize, diesel::result::Error>
+                    where C: #conn_bound, { #conn_prologue
+                        use #child_schema::#child_table::dsl::*;
+                        use diesel::prelude::*;
+
+                        let child_id: i32 = child_id.into();
+                        diesel::delete(#child_table.filter(id.eq(child_id).and(#fk_ident.eq(#parent_key)))#discriminator).execute(conn)
+                    }
+
+                    #update_method
+                    #soft_delete_methods
+                }
+            });
+
+            let count_map_rows = batched_load(
+                relation_attrs,
+                &Ident::new("parent_ids", proc_macro2::Span::call_site()),
+                quote! {
+                    #child_table
+                        .filter(#fk_ident.eq_any(parent_ids))#default_filter
+                        .group_by(#fk_ident)
+                        .select((#fk_ident, diesel::dsl::count_star()))
+                        .load::<(#fk_key_type, i64)>(conn)
+                },
+            );
+            // Générer le code pour la relation one_to_many
+            quote! {
+                impl #struct_name {
+                    pub fn children<C>(&self, conn: #conn_ty) -> diesel::QueryResult<Vec<#child_type>>
+                    where C: #conn_bound,{ #conn_prologue
+                        use #child_schema::#child_table::dsl::*;
+                        use diesel::prelude::*;
+
+                        #children_query.load::<#child_type>(conn)
+                    }
+
+                    #mutators
+
+                    // Parcourt les enfants ligne par ligne au lieu de les charger tous en mémoire.
+                    // Le mode de chargement est choisi par l'appelant : `diesel::pg::PgRowByRowLoadingMode`
+                    // lit les lignes sans les mettre en tampon sur Postgres, `DefaultLoadingMode` le fait déjà sur SQLite.
+                    pub fn #cursor_ident<'conn, M, C>(&self, conn: &'conn mut C) -> diesel::QueryResult<impl Iterator<Item = diesel::QueryResult<#child_type>> + 'conn>
+                    where C: diesel::connection::LoadConnection<M> + #conn_bound, M: 'conn, {
+                        use #child_schema::#child_table::dsl::*;
+                        use diesel::prelude::*;
+
+                        #children_query.load_iter::<#child_type, M>(conn)
+                    }
+
+                    // Compte les enfants de chaque parent en une seule requête groupée (COUNT ... GROUP BY fk).
+                    // Les parents sans enfant n'apparaissent pas dans la map : l'appelant les considère à 0.
+                    pub fn #count_map_ident<C>(parents: &[Self], conn: #conn_ty) -> diesel::QueryResult<std::collections::HashMap<#fk_key_type, i64>>
+                    where C: #conn_bound, #key_bounds { #conn_prologue
+                        use #child_schema::#child_table::dsl::*;
+                        use diesel::prelude::*;
+
+                        let parent_ids: Vec<#fk_key_type> = parents.iter().map(|parent| #each_parent_key).collect();
+                        let counts: Vec<(#fk_key_type, i64)> = #count_map_rows;
+                        Ok(counts.into_iter().collect())
+                    }
+
+                    #(#extra_methods)*
+                }
+            }
+        }
+        "many_to_one" if is_self_referential(struct_name, relation_attrs) => {
+            generate_self_referential_impl(struct_name, relation_attrs, connection)
+        }
+        "many_to_one" => {
+            // Identifiant de l'entité parent et de la clé étrangère dans l'entité enfant.
+            let parent_model = "ParentModel"; // Replace "ParentModel" with the actual value of parent_model
+            let parent_ident = Ident::new(parent_model, proc_macro2::Span::call_site());
+            let fk_ident = Ident::new(
+                relation_fk_field(relation_attrs),
+                proc_macro2::Span::call_site(),
+            );
+            let parent_key_ident = Ident::new(
+                relation_attrs.parent_key.as_deref().unwrap_or("id"),
+                proc_macro2::Span::call_site(),
+            );
+            let child_key = match &relation_attrs.child_key {
+                Some(_) => quote! { &self.#fk_ident },
+                None => quote! { self.#fk_ident },
+            };
+            let parent_key = parent_key_value(relation_attrs);
+            let self_table = self_table(struct_name, relation_attrs);
+            // Chargement groupé inverse : `child` désigne ici le modèle parent.
+            let load_parents_method = relation_attrs.eager_loading.then(|| {
+                let key_type = key_type(&relation_attrs.fk_type);
+                // Without `optional`, a child whose parent wasn't loaded points at a missing row.
+                let orphan_check = (!relation_attrs.optional).then(|| {
+                    let message = format!(
+                        "load_parents_with_children: {{}} `{}` row(s) reference a missing `{}`",
+                        struct_name,
+                        child_type_name(relation_attrs)
+                    );
+                    quote! {
+                        debug_assert!(groups.is_empty(), #message, groups.values().map(Vec::len).sum::<usize>());
+                    }
+                });
+                // With a nullable `fk`, the children without parent are grouped under a `None` parent,
+                // at the place of their first occurrence like the other groups.
+                let nullable = relation_attrs.nullable_fk;
+                let (group_key_type, loaded_parent_type, query_keys) = if nullable {
+                    (
+                        quote! { Option<#key_type> },
+                        quote! { Option<#child_type> },
+                        Ident::new("loaded_keys", proc_macro2::Span::call_site()),
+                    )
+                } else {
+                    (
+                        quote! { #key_type },
+                        quote! { #child_type },
+                        Ident::new("parent_keys", proc_macro2::Span::call_site()),
+                    )
+                };
+                let loaded_keys = nullable.then(|| {
+                    quote! {
+                        let loaded_keys: Vec<#key_type> = parent_keys.iter().filter_map(|key| key.clone()).collect();
+                    }
+                });
+                // The orphan check panics in the body of the loader, reported at the caller's location.
+                let track_caller = orphan_check.as_ref().map(|_| quote! { #[track_caller] });
+                let parent = if nullable {
+                    quote! {
+                        let parent = match &key {
+                            Some(parent_key) => Some(parents.remove(parent_key)?),
+                            None => None,
+                        };
+                    }
+                } else {
+                    quote! { let parent = parents.remove(&key)?; }
+                };
+                let load_parents = batched_load(
+                    relation_attrs,
+                    &query_keys,
+                    quote! {
+                        #child_schema::#child_table::table
+                            .filter(#child_schema::#child_table::#parent_key_ident.eq_any(&#query_keys[..]))
+                            #select_child.load::<#child_type>(conn)
+                    },
+                );
+                let grouping = group_children_by(
+                    &Ident::new("groups", proc_macro2::Span::call_site()),
+                    &group_key_type,
+                    &quote! { Self },
+                    quote! { child },
+                    quote! { children },
+                    quote! { child.#fk_ident.clone() },
+                    quote! { child },
+                );
+                quote! {
+                    // Charge en une seule requête les parents distincts d'une liste d'enfants,
+                    // chacun suivi de ses enfants, dans l'ordre où les parents apparaissent parmi les enfants.
+                    #track_caller
+                    pub fn load_parents_with_children<C>(children: Vec<Self>, conn: #conn_ty) -> diesel::QueryResult<Vec<(#loaded_parent_type, Vec<Self>)>>
+                    where C: #conn_bound, { #conn_prologue
+                        use diesel::prelude::*;
+
+                        // Clés des parents dans l'ordre de leur première apparition, l'ordre rendu à l'appelant.
+                        let mut seen = std::collections::HashSet::new();
+                        let parent_keys: Vec<#group_key_type> = children
+                            .iter()
+                            .map(|child| child.#fk_ident.clone())
+                            .filter(|key| seen.insert(key.clone()))
+                            .collect();
+                        #loaded_keys
+                        #grouping
+                        let mut parents: std::collections::HashMap<#key_type, #child_type> = #load_parents
+                            .into_iter()
+                            .map(|parent| (parent.#parent_key_ident.clone(), parent))
+                            .collect();
+                        let loaded: Vec<(#loaded_parent_type, Vec<Self>)> = parent_keys
+                            .into_iter()
+                            .filter_map(|key| {
+                                #parent
+                                let children = groups.remove(&key).unwrap_or_default();
+                                Some((parent, children))
+                            })
+                            .collect();
+                        #orphan_check
+                        Ok(loaded)
+                    }
+                }
+            });
+
+            let exists_method = relation_attrs.exists.then(|| {
+                let exists_ident = Ident::new(
+                    &format!("has_{}", singular_name(relation_attrs)),
+                    proc_macro2::Span::call_site(),
+                );
+                let QueryRunner {
+                    connection,
+                    asyncness,
+                    use_diesel_async,
+                    await_kw,
+                } = query_runner(relation_attrs, backend, connection);
+                let ConnectionParam {
+                    bound: conn_bound,
+                    ty: conn_ty,
+                    prologue: conn_prologue,
+                    ..
+                } = connection;
+                quote! {
+                    // Indique si le parent référencé existe encore, sans le charger.
+                    pub #asyncness fn #exists_ident<C>(&self, conn: #conn_ty) -> diesel::QueryResult<bool>
+                    where C: #conn_bound, { #conn_prologue
+                        use diesel::prelude::*;
+                        #use_diesel_async
+
+                        diesel::select(diesel::dsl::exists(
+                            #child_schema::#child_table::table
+                                .filter(#child_schema::#child_table::#parent_key_ident.eq(#child_key)),
+                        ))
+                        .get_result::<bool>(conn)#await_kw
+                    }
+                }
+            });
+
+            let context_method = generate_context_method(
+                struct_name,
+                relation_attrs,
+                &singular_name(relation_attrs),
+                &Ident::new("get_parent", proc_macro2::Span::call_site()),
+                quote! { #parent_ident },
+                connection,
+            );
+            let pooled_method = generate_pooled_method(
+                relation_attrs,
+                &Ident::new("get_parent", proc_macro2::Span::call_site()),
+                quote! { #parent_ident },
+                connection,
+            );
+
+            quote! {
+                impl #struct_name {
+                    // Récupère l'instance parente associée à cette instance enfant.
+                    pub fn get_parent<C>(&self, conn: #conn_ty) -> diesel::QueryResult<#parent_ident>
+                    where C: #conn_bound, { #conn_prologue
+                        use #schema::#parent_ident::dsl::*;
+                        use diesel::prelude::*;
+
+                        #parent_ident.filter(#parent_key_ident.eq(#child_key)).first::<#parent_ident>(conn)
+                    }
+
+                    #load_parents_method
+
+                    #exists_method
+
+                    #context_method
+
+                    #pooled_method
+
+                    // Optionnellement, si vous voulez aussi définir la relation dans l'autre sens :
+                    impl #parent_ident {
+                        // Récupère toutes les instances enfants liées à cette instance parent.
+                        pub fn get_children<C>(&self, conn: #conn_ty) -> diesel::QueryResult<Vec<#struct_name>>
+                        where C: #conn_bound, { #conn_prologue
+                            use diesel::prelude::*;
+
+                            #self_table::table.filter(#self_table::#fk_ident.eq(#parent_key)).load::<#struct_name>(conn)
+                        }
+                    }
+                }
+            }
+        }
+        "one_to_one" => {
+            // Jointure sur `child_key` (ou `fk`) côté enfant et sur `parent_key` (ou `id`) côté parent.
+            let fk_ident = Ident::new(
+                relation_attrs.child_key.as_deref().unwrap_or(fk),
+                proc_macro2::Span::call_site(),
+            );
+            let parent_key = parent_key_value(relation_attrs);
+            let exists_method = relation_attrs.exists.then(|| {
+                let exists_ident = Ident::new(
+                    &format!("has_{}", singular_name(relation_attrs)),
+                    proc_macro2::Span::call_site(),
+                );
+                let QueryRunner {
+                    connection,
+                    asyncness,
+                    use_diesel_async,
+                    await_kw,
+                } = query_runner(relation_attrs, backend, connection);
+                let ConnectionParam {
+                    bound: conn_bound,
+                    ty: conn_ty,
+                    prologue: conn_prologue,
+                    ..
+                } = connection;
+                quote! {
+                    // Indique si l'entité liée existe, sans la charger.
+                    pub #asyncness fn #exists_ident<C>(&self, conn: #conn_ty) -> diesel::QueryResult<bool>
+                    where C: #conn_bound, { #conn_prologue
+                        use #child_schema::#child_table::dsl::*;
+                        use diesel::prelude::*;
+                        #use_diesel_async
+
+                        diesel::select(diesel::dsl::exists(#child_table.filter(#fk_ident.eq(#parent_key))))
+                            .get_result::<bool>(conn)#await_kw
+                    }
+                }
+            });
+            let or_method = relation_attrs.error_type.as_ref().map(|error_type| {
+                let error_type: syn::Type =
+                    syn::parse_str(error_type).expect("Failed to parse the error_type");
+                let or_ident = Ident::new(
+                    &format!("get_{}_or", singular_name(relation_attrs)),
+                    proc_macro2::Span::call_site(),
+                );
+                quote! {
+                    // Renvoie l'entité liée, ou `not_found` si elle n'existe pas ; les autres erreurs sont converties.
+                    pub fn #or_ident<C>(&self, conn: #conn_ty, not_found: #error_type) -> Result<#child_type, #error_type>
+                    where
+                        C: #conn_bound,
+                        #error_type: From<diesel::result::Error>,
+                    { #conn_prologue
+                        use #child_schema::#child_table::dsl::*;
+                        use diesel::prelude::*;
+
+                        match #child_table.filter(#fk_ident.eq(#parent_key)) #select_child.first::<#child_type>(conn) {
+                            Ok(related) => Ok(related),
+                            Err(diesel::result::Error::NotFound) => Err(not_found),
+                            Err(error) => Err(error.into()),
+                        }
+                    }
+                }
+            });
+            let load_related = if relation_attrs.strict_one {
+                let message = format!(
+                    "more than one `{}` row is related to this `{}` in a one_to_one relation",
+                    child_table, struct_name
+                );
+                quote! {
+                    // Charge jusqu'à deux lignes pour détecter les données qui violent la relation un-à-un.
+                    let mut related = #child_table.filter(#fk_ident.eq(#parent_key)).limit(2)#select_child.load::<#child_type>(conn)?;
+                    if related.len() > 1 {
+                        return Err(diesel::result::Error::DatabaseError(
+                            diesel::result::DatabaseErrorKind::UniqueViolation,
+                            Box::new(String::from(#message)),
+                        ));
+                    }
+                    Ok(related.pop())
+                }
+            } else {
+                quote! {
+                    #child_table.filter(#fk_ident.eq(#parent_key))#select_child.first::<#child_type>(conn).optional()
+                }
+            };
+
+            // Mutateurs, absents des relations en lecture seule (`readonly`).
+            let update_method = relation_attrs.update.then(|| {
+                let update_ident = Ident::new(
+                    &format!("update_{}", singular_name(relation_attrs)),
+                    proc_macro2::Span::call_site(),
+                );
+                quote! {
+                    // Applique `changes` à l'entité liée et renvoie la ligne modifiée.
+                    pub fn #update_ident<C, Changes>(&self, conn: #conn_ty, changes: Changes) -> diesel::QueryResult<#child_type>
+                    where
+                        C: #conn_bound,
+                        Changes: diesel::query_builder::AsChangeset<Target = #child_schema::#child_table::table>,
+                        Changes::Changeset: diesel::query_builder::QueryFragment<<C as diesel::Connection>::Backend>,
+                    { #conn_prologue
+                        use #child_schema::#child_table::dsl::*;
+                        use diesel::prelude::*;
+
+                        diesel::update(#child_table.filter(#fk_ident.eq(#parent_key)))
+                            .set(changes)
+                            #returning_child.get_result::<#child_type>(conn)
+                    }
+                }
+            });
+            let mutators = (!readonly).then(|| {
+                quote! {
+                    // Définit ou met à jour l'entité liée.
+                    pub fn set_related_entity<C>(&self, conn: #conn_ty, entity: &#child_type) -> diesel::QueryResult<#child_type>
+                    where C: #conn_bound, { #conn_prologue
+                        use diesel::RunQueryDsl;
+                        use #child_schema::#child_table::dsl::*;
+
+                        diesel::insert_into(#child_schema::#child_table::table)
+                            .values(entity)
+                            .on_conflict(#fk_ident)
+                            .do_update()
+                            .set(entity)
+                            #returning_child.get_result::<#child_type>(conn)
+                    }
+
+                    #update_method
+                }
+            });
+
+            let context_method = generate_context_method(
+                struct_name,
+                relation_attrs,
+                &singular_name(relation_attrs),
+                &Ident::new("get_related_entity", proc_macro2::Span::call_site()),
+                quote! { Option<#child_type> },
+                connection,
+            );
+            let pooled_method = generate_pooled_method(
+                relation_attrs,
+                &Ident::new("get_related_entity", proc_macro2::Span::call_site()),
+                quote! { Option<#child_type> },
+                connection,
+            );
+
+            quote! {
+                impl #struct_name {
+                    // Obtient l'entité liée depuis l'entité courante.
+                    pub fn get_related_entity<C>(&self, conn: #conn_ty) -> diesel::QueryResult<Option<#child_type>>
+                    where C: #conn_bound, { #conn_prologue
+                        use #child_schema::#child_table::dsl::*;
+                        use diesel::prelude::*;
+
+                        #load_related
+                    }
+
+                    #mutators
+
+                    #or_method
+
+                    #exists_method
+
+                    #context_method
+
+                    #pooled_method
+                }
+            }
+        }
+        "many_to_many" => {
+            if let (Some(join_table), Some(fk_parent), Some(fk_child)) = (
+                &relation_attrs.join_table,
+                &relation_attrs.fk_parent,
+                &relation_attrs.fk_child,
+            ) {
+                let join_table_ident = Ident::new(join_table, proc_macro2::Span::call_site());
+                let parent_fk_ident = Ident::new(fk_parent, proc_macro2::Span::call_site());
+                let child_fk_ident = Ident::new(fk_child, proc_macro2::Span::call_site());
+                let parent_key_type = key_type(&relation_attrs.fk_parent_type);
+                let child_key_type = key_type(&relation_attrs.fk_child_type);
+                // Ordre des enfants chargés : `order_by`, ou la clé primaire par défaut.
+                let related_order = match relation_attrs.order_by {
+                    Some(_) => order_expression(relation_attrs, "id"),
+                    None => quote! { id.asc() },
+                };
+
+                let singular = singular_name(relation_attrs);
+                let add_ident = Ident::new(
+                    &format!(
+                        "{}_{}",
+                        relation_attrs.link_verb.as_deref().unwrap_or("add"),
+                        singular
+                    ),
+                    proc_macro2::Span::call_site(),
+                );
+                let remove_ident = Ident::new(
+                    &format!(
+                        "{}_{}",
+                        relation_attrs.unlink_verb.as_deref().unwrap_or("remove"),
+                        singular
+                    ),
+                    proc_macro2::Span::call_site(),
+                );
+
+                // Méthodes optionnelles, activées par les attributs de la relation.
+                let mut extra_methods = Vec::new();
+                extra_methods.extend(generate_dto_method(
+                    relation_attrs,
+                    &child_type,
+                    &Ident::new("related_entities", proc_macro2::Span::call_site()),
+                    connection,
+                ));
+                extra_methods.extend(generate_with_parent_method(
+                    relation_attrs,
+                    &child_type,
+                    &Ident::new("related_entities", proc_macro2::Span::call_site()),
+                    connection,
+                ));
+                extra_methods.extend(generate_nonempty_method(
+                    relation_attrs,
+                    &child_type,
+                    &Ident::new("related_entities", proc_macro2::Span::call_site()),
+                    connection,
+                ));
+                extra_methods.extend(generate_has_more_method(
+                    relation_attrs,
+                    &child_type,
+                    connection,
+                ));
+                extra_methods.extend(generate_paged_method(
+                    relation_attrs,
+                    &child_type,
+                    connection,
+                ));
+                extra_methods.extend(generate_context_method(
+                    struct_name,
+                    relation_attrs,
+                    &plural_name(relation_attrs),
+                    &Ident::new("related_entities", proc_macro2::Span::call_site()),
+                    quote! { Vec<#child_type> },
+                    connection,
+                ));
+                extra_methods.extend(generate_pooled_method(
+                    relation_attrs,
+                    &Ident::new("related_entities", proc_macro2::Span::call_site()),
+                    quote! { Vec<#child_type> },
+                    connection,
+                ));
+                extra_methods.extend(generate_page_with_total_method(
+                    relation_attrs,
+                    &child_type,
+                    connection,
+                ));
+                extra_methods.extend(generate_grouped_method(
+                    relation_attrs,
+                    &child_type,
+                    &Ident::new("related_entities", proc_macro2::Span::call_site()),
+                    connection,
+                ));
+                extra_methods.extend(generate_shared_method(
+                    relation_attrs,
+                    &child_type,
+                    &Ident::new("related_entities", proc_macro2::Span::call_site()),
+                    connection,
+                ));
+                let (query_type, query) = relation_query(relation_attrs);
+                let (query_method, query_fn) = generate_query_method(
+                    struct_name,
+                    relation_attrs,
+                    backend,
+                    connection,
+                    query_type,
+                    query,
+                );
+                extra_methods.push(query_method);
+                free_items.extend(query_fn);
+                extra_methods.extend(generate_count_methods(
+                    relation_attrs,
+                    &quote! {
+                        #child_schema::#child_table::table
+                            .filter(
+                                #child_schema::#child_table::id.eq_any(
+                                    #schema::#join_table_ident::table
+                                        .filter(#schema::#join_table_ident::#parent_fk_ident.eq(&self.id))
+                                        .select(#schema::#join_table_ident::#child_fk_ident),
+                                ),
+                            )#default_filter
+                    },
+                    backend,
+                    connection,
+                ));
+
+                if relation_attrs.paginate {
+                    let page_ident = Ident::new(
+                        &format!("get_{}_page", plural_name(relation_attrs)),
+                        proc_macro2::Span::call_site(),
+                    );
+                    extra_methods.push(quote! {
+                        // Charge une page d'enfants. La pagination porte sur la requête finale des enfants,
+                        // après le filtre par défaut, pour que chaque page soit complète.
+                        pub fn #page_ident<C>(&self, conn: #conn_ty, page: crate::Page) -> diesel::QueryResult<Vec<#child_type>>
+                        where
+                            C: #conn_bound,
+                        { #conn_prologue
+                            use diesel::prelude::*;
+                            use #schema::#join_table_ident::dsl as join_dsl;
+                            use #child_schema::#child_table::dsl::*;
+
+                            let parent_id: &#parent_key_type = &self.id;
+                            let related_ids = join_dsl::#join_table_ident
+                                .filter(join_dsl::#parent_fk_ident.eq(parent_id))
+                                .select(join_dsl::#child_fk_ident)
+                                .load::<#child_key_type>(conn)?;
+
+                            // Même ordre que `related_entities`, stable d'une page à l'autre.
+                            #child_table
+                                .filter(id.eq_any(related_ids))#default_filter
+                                .order(#related_order)
+                                .limit(page.limit)
+                                .offset(page.offset)
+                                #select_child.load::<#child_type>(conn)
+                        }
+                    });
+                }
+                if relation_attrs.ids {
+                    let ids_ident = Ident::new(
+                        &format!("get_{}_ids", singular),
+                        proc_macro2::Span::call_site(),
+                    );
+                    extra_methods.push(quote! {
+                        // Charge seulement les identifiants des enfants, la table de jointure étant lue en sous-requête.
+                        pub fn #ids_ident<C>(&self, conn: #conn_ty) -> diesel::QueryResult<Vec<#child_key_type>>
+                        where
+                            C: #conn_bound,
+                        { #conn_prologue
+                            use diesel::prelude::*;
+                            use #schema::#join_table_ident::dsl as join_dsl;
+                            use #child_schema::#child_table::dsl::*;
+
+                            let parent_id: &#parent_key_type = &self.id;
+                            #child_table
+                                .filter(id.eq_any(
+                                    join_dsl::#join_table_ident
+                                        .filter(join_dsl::#parent_fk_ident.eq(parent_id))
+                                        .select(join_dsl::#child_fk_ident)
+                                ))#default_filter
+                                .order(id)
+                                .select(id)
+                                .load::<#child_key_type>(conn)
+                        }
+                    });
+                }
+                if let Some(cursor_column) = &relation_attrs.cursor_column {
+                    let after_ident = Ident::new(
+                        &format!("get_{}_after", plural_name(relation_attrs)),
+                        proc_macro2::Span::call_site(),
+                    );
+                    let cursor_ident = Ident::new(cursor_column, proc_macro2::Span::call_site());
+                    let cursor_type = key_type(&relation_attrs.cursor_type);
+                    extra_methods.push(quote! {
+                        // Pagination par curseur sur la requête finale des enfants, comme la pagination par page.
+                        pub fn #after_ident<C>(&self, conn: #conn_ty, cursor: Option<#cursor_type>, limit: i64) -> diesel::QueryResult<Vec<#child_type>>
+                        where
+                            C: #conn_bound,
+                        { #conn_prologue
+                            use diesel::prelude::*;
+                            use #schema::#join_table_ident::dsl as join_dsl;
+                            use #child_schema::#child_table::dsl::*;
+
+                            let parent_id: &#parent_key_type = &self.id;
+                            let related_ids = join_dsl::#join_table_ident
+                                .filter(join_dsl::#parent_fk_ident.eq(parent_id))
+                                .select(join_dsl::#child_fk_ident)
+                                .load::<#child_key_type>(conn)?;
+
+                            let mut query = #child_table.filter(id.eq_any(related_ids))#default_filter.into_boxed();
+                            if let Some(cursor) = cursor {
+                                query = query.filter(#cursor_ident.gt(cursor));
+                            }
+                            query.order(#cursor_ident.asc()).limit(limit) #select_child.load::<#child_type>(conn)
+                        }
+                    });
+                }
+                if relation_attrs.with_usage {
+                    let usage_ident = Ident::new(
+                        &format!("get_{}_with_usage", plural_name(relation_attrs)),
+                        proc_macro2::Span::call_site(),
+                    );
+                    extra_methods.push(quote! {
+                        // Charge les enfants avec, pour chacun, son nombre total de liens dans la table de jointure.
+                        pub fn #usage_ident<C>(&self, conn: #conn_ty) -> diesel::QueryResult<Vec<(#child_type, i64)>>
+                        where
+                            C: #conn_bound,
+                        { #conn_prologue
+                            use diesel::prelude::*;
+                            use #schema::#join_table_ident::dsl as join_dsl;
+
+                            let parent_id: &#parent_key_type = &self.id;
+                            let related_ids = join_dsl::#join_table_ident
+                                .filter(join_dsl::#parent_fk_ident.eq(parent_id))
+                                .select(join_dsl::#child_fk_ident)
+                                .load::<#child_key_type>(conn)?;
+                            let usage: std::collections::HashMap<#child_key_type, i64> = join_dsl::#join_table_ident
+                                .filter(join_dsl::#child_fk_ident.eq_any(&related_ids))
+                                .group_by(join_dsl::#child_fk_ident)
+                                .select((join_dsl::#child_fk_ident, diesel::dsl::count_star()))
+                                .load::<(#child_key_type, i64)>(conn)?
+                                .into_iter()
+                                .collect();
+
+                            Ok(self
+                                .related_entities(#conn_arg)?
+                                .into_iter()
+                                .map(|child| {
+                                    let count = usage.get(&child.id).copied().unwrap_or(0);
+                                    (child, count)
+                                })
+                                .collect())
+                        }
+                    });
+                }
+
+                // Mutateurs, absents des relations en lecture seule (`readonly`).
+                let mutators = (!readonly).then(|| {
+                    quote! {
+                        // Ajoute une ligne dans la table de jointure pour lier l'enfant.
+                        // L'identifiant peut être passé brut ou sous la forme d'un newtype convertible.
+                        pub fn #add_ident<C>(&self, conn: #conn_ty, child_id: impl Into<#child_key_type>) -> Result<usize, diesel::result::Error>
+                        where
+                            C: #conn_bound,
+                        { #conn_prologue
+                            use diesel::prelude::*;
+                            use #schema::#join_table_ident::dsl as join_dsl;
+
+                            let parent_id: &#parent_key_type = &self.id;
+                            let child_id: #child_key_type = child_id.into();
+                            diesel::insert_into(join_dsl::#join_table_ident)
+                                .values((join_dsl::#parent_fk_ident.eq(parent_id), join_dsl::#child_fk_ident.eq(child_id)))
+                                .execute(conn)
+                        }
+
+                        // Supprime la ligne de la table de jointure qui lie l'enfant.
+                        pub fn #remove_ident<C>(&self, conn: #conn_ty, child_id: impl Into<#child_key_type>) -> Result<usize, diesel::result::Error>
+                        where
+                            C: #conn_bound,
+                        { #conn_prologue
+                            use diesel::prelude::*;
+                            use #schema::#join_table_ident::dsl as join_dsl;
+
+                            let parent_id: &#parent_key_type = &self.id;
+                            let child_id: #child_key_type = child_id.into();
+                            diesel::delete(
+                                join_dsl::#join_table_ident
+                                    .filter(join_dsl::#parent_fk_ident.eq(parent_id))
+                                    .filter(join_dsl::#child_fk_ident.eq(child_id)),
+                            )
+                            .execute(conn)
+                        }
+                    }
+                });
+
+                quote! {
+                    impl #struct_name {
+                        pub fn related_entities<C>(&self, conn: #conn_ty) -> diesel::QueryResult<Vec<#child_type>>
+                        where
+                            C: #conn_bound,
+                        { #conn_prologue
+                            use diesel::prelude::*;
+                            use #schema::#join_table_ident::dsl as join_dsl;
+                            use #child_schema::#child_table::dsl::*;
+
+                            // La clé est empruntée : elle peut ne pas être `Copy` (`String`, `Uuid`, ...).
+                            let parent_id: &#parent_key_type = &self.id;
+                            let related_ids = join_dsl::#join_table_ident
+                                .filter(join_dsl::#parent_fk_ident.eq(parent_id))
+                                .select(join_dsl::#child_fk_ident)
+                                .load::<#child_key_type>(conn)?;
+
+                            // Trié par `order_by` ou par clé primaire : l'ordre des enfants ne dépend pas de celui de la table de jointure.
+                            #child_table
+                                .filter(id.eq_any(related_ids))#default_filter
+                                .order(#related_order)
+                                #select_child.load::<#child_type>(conn)
+                        }
+
+                        #mutators
+
+                        #(#extra_methods)*
+                    }
+                }
+            } else {
+                quote! {
+                    compile_error!("join_table, fk_parent, and fk_child attributes are required for many_to_many relations");
+                }
+            }
+        }
+        "has_many_through" => {
+            if let (Some(through), Some(through_fk)) =
+                (&relation_attrs.through, &relation_attrs.through_fk)
+            {
+                let through_ident = Ident::new(through, proc_macro2::Span::call_site());
+                let through_fk_ident = Ident::new(through_fk, proc_macro2::Span::call_site());
+                let parent_key_type = key_type(&relation_attrs.fk_type);
+                let getter_ident = Ident::new(
+                    &format!("get_{}", plural_name(relation_attrs)),
+                    proc_macro2::Span::call_site(),
+                );
+                // Ordre des enfants chargés : `order_by`, ou la clé primaire par défaut.
+                let through_order = match relation_attrs.order_by {
+                    Some(_) => order_expression(relation_attrs, "id"),
+                    None => quote! { id.asc() },
+                };
+
+                let mut extra_methods = Vec::new();
+                let (query_type, query) = relation_query(relation_attrs);
+                let (query_method, query_fn) = generate_query_method(
+                    struct_name,
+                    relation_attrs,
+                    backend,
+                    connection,
+                    query_type,
+                    query,
+                );
+                extra_methods.push(query_method);
+                free_items.extend(query_fn);
+                extra_methods.extend(generate_pooled_method(
+                    relation_attrs,
+                    &getter_ident,
+                    quote! { Vec<#child_type> },
+                    connection,
+                ));
+                extra_methods.extend(generate_count_methods(
+                    relation_attrs,
+                    &quote! {
+                        #child_schema::#child_table::table
+                            .filter(
+                                #child_schema::#child_table::#fk_ident.eq_any(
+                                    #schema::#through_ident::table
+                                        .filter(#schema::#through_ident::#through_fk_ident.eq(&self.id))
+                                        .select(#schema::#through_ident::id),
+                                ),
+                            )#default_filter
+                    },
+                    backend,
+                    connection,
+                ));
+
+                quote! {
+                    impl #struct_name {
+                        // Charge les enfants atteints par la table intermédiaire, lue en sous-requête.
+                        pub fn #getter_ident<C>(&self, conn: #conn_ty) -> diesel::QueryResult<Vec<#child_type>>
+                        where
+                            C: #conn_bound,
+                        { #conn_prologue
+                            use diesel::prelude::*;
+                            use #schema::#through_ident::dsl as through_dsl;
+                            use #child_schema::#child_table::dsl::*;
+
+                            let parent_id: &#parent_key_type = &self.id;
+                            #child_table
+                                .filter(#fk_ident.eq_any(
+                                    through_dsl::#through_ident
+                                        .filter(through_dsl::#through_fk_ident.eq(parent_id))
+                                        .select(through_dsl::id)
+                                ))#default_filter
+                                .order(#through_order)
+                                #select_child.load::<#child_type>(conn)
+                        }
+
+                        #(#extra_methods)*
+                    }
+                }
+            } else {
+                quote! {
+                    compile_error!("through and through_fk attributes are required for has_many_through relations");
+                }
+            }
+        }
+        _ => panic!("Unsupported relation type: {}", relation_type),
+    };
+
+    let mut items = vec![relation_impl];
+    items.extend(free_items);
+    items.extend(generate_conn_type_assertion(relation_attrs, connection));
+    items.extend(generate_wrapper_impl(
+        struct_name,
+        relation_attrs,
+        connection,
+    ));
+    items
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn many_to_many_attrs(child_model: &str, singular: Option<&str>) -> RelationAttributes {
+        RelationAttributes {
+            child_model: child_model.to_string(),
+            fk: "post_id".to_string(),
+            relation_type: "many_to_many".to_string(),
+            join_table: Some("post_data".to_string()),
+            fk_parent: Some("post_id".to_string()),
+            fk_child: Some("data_id".to_string()),
+            singular: singular.map(str::to_string),
+            ..Default::default()
+        }
+    }
+
+    fn one_to_many_attrs() -> RelationAttributes {
+        RelationAttributes {
+            child_model: "Post".to_string(),
+            fk: "user_id".to_string(),
+            relation_type: "one_to_many".to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_one_to_many_cursor_method() {
+        let struct_name = Ident::new("User", Span::call_site());
+
+        let code = generate_relation_code(&struct_name, &one_to_many_attrs()).to_string();
+        assert!(code.contains("fn get_posts_cursor <"));
+        assert!(code.contains("load_iter :: < Post , M >"));
+    }
+
+    #[test]
+    fn test_one_to_many_count_map_method() {
+        let struct_name = Ident::new("User", Span::call_site());
+
+        let code = generate_relation_code(&struct_name, &one_to_many_attrs()).to_string();
+        assert!(code.contains("fn get_posts_count_map < C > (parents : & [Self]"));
+        assert!(code.contains(
+            ". group_by (user_id) . select ((user_id , diesel :: dsl :: count_star ()))"
+        ));
+        assert!(code.contains("std :: collections :: HashMap < i32 , i64 >"));
+    }
+
+    #[test]
+    fn test_loaders_deserialize_into_model_for_backend_checks() {
+        let struct_name = Ident::new("User", Span::call_site());
+        let relation_attrs = RelationAttributes {
+            backends: vec!["postgres".to_string()],
+            ..one_to_many_attrs()
+        };
+
+        // `check_for_backend` only accepts loads whose row shape matches the model:
+        // the getters load the model from its own columns.
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(code.contains("load :: < Post > (conn)"));
+        assert!(!code.contains("all_columns"));
+        assert!(code
+            .contains("diesel :: connection :: LoadConnection < Backend = diesel :: pg :: Pg >"));
+
+        let code =
+            generate_relation_code(&struct_name, &many_to_many_attrs("Tag", None)).to_string();
+        assert!(code.contains("load :: < Tag > (conn)"));
+        assert!(!code.contains("all_columns"));
+
+        // The batch loaders pair `all_columns` with the parent key: the model is the nested
+        // element of a `(K, Child)` tuple, still read from its own columns.
+        let relation_attrs = RelationAttributes {
+            eager_loading: true,
+            ..relation_attrs
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(code.contains(". select ((user_id , crate :: schema :: posts :: all_columns)) . load :: < (i32 , Post) > (conn) ?"));
+    }
+
+    #[test]
+    fn test_one_to_many_recent_method() {
+        let struct_name = Ident::new("User", Span::call_site());
+
+        let code = generate_relation_code(&struct_name, &one_to_many_attrs()).to_string();
+        assert!(!code.contains("get_recent_posts"));
+
+        let relation_attrs = RelationAttributes {
+            recent: true,
+            order_by: Some("created_at".to_string()),
+            ..one_to_many_attrs()
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(code.contains("fn get_recent_posts < C > (& self , conn : & mut C , n : i64)"));
+        assert!(code.contains(". order (created_at . desc ()) . limit (n)"));
+    }
+
+    #[test]
+    fn test_one_to_many_latest_method() {
+        let struct_name = Ident::new("User", Span::call_site());
+        let code = generate_relation_code(&struct_name, &one_to_many_attrs()).to_string();
+        assert!(!code.contains("get_latest_posts"));
+
+        let relation_attrs = RelationAttributes {
+            latest: Some("created_at".to_string()),
+            latest_limit: Some(5),
+            default_filter: vec![FilterCondition::IsNull("deleted_at".to_string())],
+            ..one_to_many_attrs()
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(code.contains(
+            "fn get_latest_posts < C > (& self , conn : & mut C) -> diesel :: QueryResult < Vec < Post >>"
+        ));
+        assert!(code.contains("posts . filter (user_id . eq (self . id)) . filter (crate :: schema :: posts :: deleted_at . is_null ()) . order (created_at . desc ()) . limit (5) . load :: < Post > (conn)"));
+    }
+
+    #[test]
+    fn test_one_to_many_stats_loader() {
+        let struct_name = Ident::new("User", Span::call_site());
+        let relation_attrs = RelationAttributes {
+            aggregates: vec![Aggregate::Count, Aggregate::Sum("views".to_string())],
+            ..one_to_many_attrs()
+        };
+
+        let items = generate_support_items(&struct_name, &relation_attrs).to_string();
+        assert!(
+            items.contains("pub struct PostStats { pub count : i64 , pub total_views : i64 , }")
+        );
+
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(code.contains("fn load_with_post_stats < C > (parents : Vec < Self > , conn : & mut C) -> diesel :: QueryResult < Vec < (Self , PostStats) >>"));
+        assert!(code.contains(
+            ". select ((user_id , diesel :: dsl :: count_star () , diesel :: dsl :: sum (views)))"
+        ));
+        assert!(code.contains("load :: < (i32 , i64 , Option < i64 >) > (conn)"));
+        assert!(code.contains("unwrap_or_default ()"));
+    }
+
+    #[test]
+    fn test_method_names_ignore_child_model_casing() {
+        let struct_name = Ident::new("User", Span::call_site());
+        let pascal_case = RelationAttributes {
+            child_model: "UserProfile".to_string(),
+            ..one_to_many_attrs()
+        };
+        let camel_case = RelationAttributes {
+            child_model: "userProfile".to_string(),
+            ..one_to_many_attrs()
+        };
+        let pathed = RelationAttributes {
+            child_model: "crate::models::UserProfile".to_string(),
+            ..one_to_many_attrs()
+        };
+
+        for relation_attrs in [&pascal_case, &camel_case, &pathed] {
+            let code = generate_relation_code(&struct_name, relation_attrs).to_string();
+            assert!(code.contains("fn get_user_profiles_cursor <"));
+            assert!(code.contains("fn get_user_profiles_count_map <"));
+            assert!(code.contains("use crate :: schema :: user_profiles :: dsl :: *"));
+        }
+
+        // The model itself is used as written, so a casing typo fails to resolve the type.
+        let code = generate_relation_code(&struct_name, &camel_case).to_string();
+        assert!(code.contains("Vec < userProfile >"));
+        let code = generate_relation_code(&struct_name, &pathed).to_string();
+        assert!(code.contains("Vec < crate :: models :: UserProfile >"));
+    }
+
+    #[test]
+    fn test_serialize_as_dto_method() {
+        let struct_name = Ident::new("User", Span::call_site());
+        let relation_attrs = RelationAttributes {
+            serialize_as: Some("crate::api::PostDto".to_string()),
+            ..one_to_many_attrs()
+        };
+
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(code.contains(
+            "fn get_post_dtos < C > (& self , conn : & mut C) -> diesel :: QueryResult < Vec < crate :: api :: PostDto >>"
+        ));
+        assert!(code.contains("crate :: api :: PostDto : From < Post > + serde :: Serialize"));
+        assert!(code.contains("self . children (conn)"));
+
+        let relation_attrs = RelationAttributes {
+            serialize_as: Some("TagDto".to_string()),
+            ..many_to_many_attrs("Tag", None)
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(code.contains("fn get_tag_dtos < C >"));
+        assert!(code.contains("self . related_entities (conn)"));
+    }
+
+    #[test]
+    fn test_with_parent_method() {
+        let struct_name = Ident::new("User", Span::call_site());
+        let code = generate_relation_code(&struct_name, &one_to_many_attrs()).to_string();
+        assert!(!code.contains("get_posts_with_parent"));
+
+        let relation_attrs = RelationAttributes {
+            with_parent: true,
+            ..one_to_many_attrs()
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(code.contains(
+            "fn get_posts_with_parent < C > (& self , conn : & mut C) -> diesel :: QueryResult < Vec < (Post , Self) >>"
+        ));
+        assert!(code.contains("Self : Clone"));
+        assert!(code.contains("self . children (conn) . map (| children | children . into_iter () . map (| child | (child , self . clone ())) . collect ())"));
+
+        let relation_attrs = RelationAttributes {
+            with_parent: true,
+            ..many_to_many_attrs("Tag", None)
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(code.contains("fn get_tags_with_parent < C >"));
+        assert!(code.contains("self . related_entities (conn)"));
+    }
+
+    #[test]
+    fn test_nonempty_method() {
+        let struct_name = Ident::new("User", Span::call_site());
+        let code = generate_relation_code(&struct_name, &one_to_many_attrs()).to_string();
+        assert!(!code.contains("get_posts_nonempty"));
+
+        let relation_attrs = RelationAttributes {
+            nonempty: true,
+            ..one_to_many_attrs()
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(code.contains(
+            "fn get_posts_nonempty < C > (& self , conn : & mut C) -> diesel :: QueryResult < Option < (Post , Vec < Post >) >>"
+        ));
+        assert!(code.contains("let mut children = self . children (conn) ? . into_iter () ;"));
+        assert!(code
+            .contains("Ok (children . next () . map (| first | (first , children . collect ())))"));
+
+        let relation_attrs = RelationAttributes {
+            nonempty: true,
+            ..many_to_many_attrs("Tag", None)
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(code.contains("fn get_tags_nonempty < C >"));
+        assert!(code.contains("self . related_entities (conn) ?"));
+    }
+
+    #[test]
+    fn test_grouped_method() {
+        let struct_name = Ident::new("User", Span::call_site());
+        let code = generate_relation_code(&struct_name, &one_to_many_attrs()).to_string();
+        assert!(!code.contains("grouped_by"));
+
+        let relation_attrs = RelationAttributes {
+            group_by: Some("category_id".to_string()),
+            ..one_to_many_attrs()
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(code.contains(
+            "fn get_posts_grouped_by_category_id < C > (& self , conn : & mut C) -> diesel :: QueryResult < std :: collections :: HashMap < i32 , Vec < Post >> >"
+        ));
+        assert!(code.contains("for child in self . children (conn) ? { groups . entry (child . category_id . clone ()) . or_default () . push (child) ; }"));
+
+        let relation_attrs = RelationAttributes {
+            group_by: Some("kind".to_string()),
+            group_by_type: Some("String".to_string()),
+            ..many_to_many_attrs("Tag", None)
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(code.contains("fn get_tags_grouped_by_kind < C >"));
+        assert!(code.contains("std :: collections :: HashMap < String , Vec < Tag >> >"));
+        assert!(code.contains("for child in self . related_entities (conn) ?"));
+    }
+
+    #[test]
+    fn test_shared_method() {
+        let struct_name = Ident::new("User", Span::call_site());
+        let code = generate_relation_code(&struct_name, &one_to_many_attrs()).to_string();
+        assert!(!code.contains("get_posts_shared"));
+
+        let relation_attrs = RelationAttributes {
+            shared_children: true,
+            ..one_to_many_attrs()
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(code.contains(
+            "fn get_posts_shared < C > (& self , conn : & mut C) -> diesel :: QueryResult < Vec < std :: sync :: Arc < Post >> >"
+        ));
+        assert!(code.contains("self . children (conn) . map (| children | children . into_iter () . map (std :: sync :: Arc :: new) . collect ())"));
+
+        let relation_attrs = RelationAttributes {
+            shared_children: true,
+            ..many_to_many_attrs("Tag", None)
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(code.contains("fn get_tags_shared < C >"));
+        assert!(code.contains("self . related_entities (conn) . map"));
+    }
+
+    #[test]
+    fn test_one_to_many_remove_child_accepts_newtype_ids() {
+        let struct_name = Ident::new("User", Span::call_site());
+        let code = generate_relation_code(&struct_name, &one_to_many_attrs()).to_string();
+        assert!(code.contains(
+            "fn remove_child < C > (& self , conn : & mut C , child_id : impl Into < i32 >)"
+        ));
+        assert!(code.contains("let child_id : i32 = child_id . into () ;"));
+    }
+
+    #[test]
+    fn test_default_filter_scopes_loaders() {
+        let struct_name = Ident::new("User", Span::call_site());
+        let code = generate_relation_code(&struct_name, &one_to_many_attrs()).to_string();
+        assert!(
+            code.contains("posts . filter (user_id . eq (self . id)) . load :: < Post > (conn)")
+        );
+
+        let relation_attrs = RelationAttributes {
+            default_filter: parse_default_filter(r#"status = "active" AND deleted_at IS NULL"#)
+                .unwrap(),
+            ..one_to_many_attrs()
+        };
+        let filter = ". filter (crate :: schema :: posts :: status . eq (\"active\") . and (crate :: schema :: posts :: deleted_at . is_null ()))";
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(code.contains(&format!(
+            "posts . filter (user_id . eq (self . id)) {} . load :: < Post > (conn)",
+            filter
+        )));
+        assert!(code.contains(&format!(
+            "posts . filter (user_id . eq (self . id)) {} . load_iter :: < Post , M > (conn)",
+            filter
+        )));
+        assert!(code.contains(&format!(
+            "filter (user_id . eq_any (parent_ids)) {} . group_by (user_id)",
+            filter
+        )));
+
+        let relation_attrs = RelationAttributes {
+            default_filter: parse_default_filter("id IN (1, 2)").unwrap(),
+            ..many_to_many_attrs("Tag", None)
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(code.contains(
+            "filter (id . eq_any (related_ids)) . filter (crate :: schema :: tags :: id . eq_any ([1 , 2]))"
+        ));
+    }
+
+    #[test]
+    fn test_count_and_exists_methods() {
+        let struct_name = Ident::new("User", Span::call_site());
+        let code = generate_relation_code(&struct_name, &one_to_many_attrs()).to_string();
+        assert!(!code.contains("fn count_posts"));
+        assert!(!code.contains("fn has_posts"));
+
+        let relation_attrs = RelationAttributes {
+            count: true,
+            exists: true,
+            ..one_to_many_attrs()
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        let children = "crate :: schema :: posts :: table . filter (crate :: schema :: posts :: user_id . eq (self . id))";
+        assert!(code.contains(
+            "fn count_posts < C > (& self , conn : & mut C) -> diesel :: QueryResult < i64 >"
+        ));
+        assert!(code.contains(&format!(
+            "{} . count () . get_result :: < i64 > (conn)",
+            children
+        )));
+        assert!(code.contains(
+            "fn has_posts < C > (& self , conn : & mut C) -> diesel :: QueryResult < bool >"
+        ));
+        assert!(code.contains(&format!(
+            "diesel :: select (diesel :: dsl :: exists ({})) . get_result :: < bool > (conn)",
+            children
+        )));
+
+        let relation_attrs = RelationAttributes {
+            count: true,
+            ..many_to_many_attrs("Tag", None)
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(code.contains("fn count_tags < C >"));
+        assert!(code.contains("crate :: schema :: post_data :: post_id . eq (& self . id)"));
+        assert!(!code.contains("fn has_tags"));
+
+        // The count has the scope of `children`, default filter included.
+        let relation_attrs = RelationAttributes {
+            count: true,
+            default_filter: vec![FilterCondition::Eq(
+                "published".to_string(),
+                "true".to_string(),
+            )],
+            ..one_to_many_attrs()
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(code.contains("user_id . eq (self . id)) . filter (crate :: schema :: posts :: published . eq (true)) . count () . get_result :: < i64 > (conn)"));
+    }
+
+    #[test]
+    fn test_full_enables_the_bundle() {
+        let struct_name = Ident::new("User", Span::call_site());
+        let parsed_attrs = ParsedAttrs {
+            relation_type: Some("one_to_many".to_string()),
+            child: Some("Post".to_string()),
+            fk: Some("user_id".to_string()),
+            full: true,
+            ..Default::default()
+        };
+        let relation_attrs = extract_relation_attrs(&parsed_attrs).unwrap();
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        let items = generate_support_items(&struct_name, &relation_attrs).to_string();
+        assert!(code.contains("fn count_posts < C >"));
+        assert!(code.contains("fn has_posts < C >"));
+        assert!(code.contains("pub fn load_with_posts < C >"));
+        assert!(!items.contains("UserWithPosts"));
+
+        let parsed_attrs = ParsedAttrs {
+            eager_loading: Some(false),
+            wrapper: Some(true),
+            ..parsed_attrs
+        };
+        let relation_attrs = extract_relation_attrs(&parsed_attrs).unwrap();
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        let items = generate_support_items(&struct_name, &relation_attrs).to_string();
+        assert!(code.contains("fn count_posts < C >"));
+        assert!(code.contains("fn has_posts < C >"));
+        assert!(!code.contains("load_with_posts"));
+        assert!(items.contains("pub struct UserWithPosts"));
+
+        // Many-to-many relations have no batch loader to enable.
+        let parsed_attrs = ParsedAttrs {
+            relation_type: Some("many_to_many".to_string()),
+            child: Some("Tag".to_string()),
+            fk: Some("post_id".to_string()),
+            join_table: Some("post_tags".to_string()),
+            fk_parent: Some("post_id".to_string()),
+            fk_child: Some("tag_id".to_string()),
+            full: true,
+            ..Default::default()
+        };
+        assert!(!extract_relation_attrs(&parsed_attrs).unwrap().eager_loading);
+    }
+
+    #[test]
+    fn test_selectable_loads_through_as_select() {
+        let struct_name = Ident::new("User", Span::call_site());
+        let code = generate_relation_code(&struct_name, &one_to_many_attrs()).to_string();
+        assert!(!code.contains("as_select"));
+
+        let relation_attrs = RelationAttributes {
+            selectable: true,
+            ..one_to_many_attrs()
+        };
+        let select = ". select (< Post as diesel :: SelectableHelper < _ >> :: as_select ())";
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(code.contains(&format!(
+            "posts . filter (user_id . eq (self . id)) {} . load :: < Post > (conn)",
+            select
+        )));
+        assert!(code.contains(&format!("{} . load_iter :: < Post , M > (conn)", select)));
+
+        let relation_attrs = RelationAttributes {
+            selectable: true,
+            ..many_to_many_attrs("Tag", None)
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(code.contains(". order (id . asc ()) . select (< Tag as diesel :: SelectableHelper < _ >> :: as_select ()) . load :: < Tag > (conn)"));
+
+        let relation_attrs = RelationAttributes {
+            child_model: "Profile".to_string(),
+            fk: "user_id".to_string(),
+            relation_type: "one_to_one".to_string(),
+            selectable: true,
+            ..Default::default()
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(code.contains(". select (< Profile as diesel :: SelectableHelper < _ >> :: as_select ()) . first :: < Profile > (conn)"));
+        assert!(code.contains(". returning (< Profile as diesel :: SelectableHelper < _ >> :: as_returning ()) . get_result :: < Profile > (conn)"));
+    }
+
+    #[test]
+    fn test_wrapper_memoizes_children() {
+        let struct_name = Ident::new("User", Span::call_site());
+        let relation_attrs = RelationAttributes {
+            wrapper: true,
+            backends: vec!["postgres".to_string()],
+            ..one_to_many_attrs()
+        };
+
+        let items = generate_support_items(&struct_name, &relation_attrs).to_string();
+        assert!(items.contains("pub struct UserWithPosts { inner : User , posts : std :: cell :: OnceCell < Vec < Post >> , }"));
+        assert!(items.contains("impl std :: ops :: Deref for UserWithPosts { type Target = User ;"));
+
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(code.contains("impl UserWithPosts"));
+        assert!(code.contains(
+            "pub fn posts < C > (& self , conn : & mut C) -> diesel :: QueryResult < & [Post] >"
+        ));
+        assert!(code
+            .contains("if let Some (children) = self . posts . get () { return Ok (children) ; }"));
+    }
+
+    #[test]
+    #[cfg(not(feature = "async"))]
+    fn test_sync_only_code_never_references_diesel_async() {
+        let struct_name = Ident::new("User", Span::call_site());
+        let relation_attrs = RelationAttributes {
+            eager_loading: true,
+            eager_loading_stream: true,
+            backends: vec!["postgres".to_string()],
+            ..one_to_many_attrs()
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(code.contains("pub fn load_with_posts < C >"));
+        // Output follows the caller's parent order.
+        assert!(code.contains("Ok (parents . into_iter () . map (| parent | { let parent_children = children . remove (& parent . id) . unwrap_or_default () ;"));
+        assert!(!code.contains("diesel_async"));
+        assert!(!code.contains("load_with_posts_stream"));
+
+        // `async` is rejected by the parser without the feature, and ignored here.
+        let relation_attrs = RelationAttributes {
+            count: true,
+            async_: true,
+            ..one_to_many_attrs()
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(code.contains("pub fn count_posts < C >"));
+        assert!(!code.contains("diesel_async"));
+    }
+
+    #[test]
+    #[cfg(feature = "async")]
+    fn test_one_to_many_eager_loading_stream() {
+        let struct_name = Ident::new("User", Span::call_site());
+        let code = generate_relation_code(&struct_name, &one_to_many_attrs()).to_string();
+        assert!(!code.contains("load_with_posts_stream"));
+
+        let relation_attrs = RelationAttributes {
+            eager_loading_stream: true,
+            backends: vec!["postgres".to_string()],
+            ..one_to_many_attrs()
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(code.contains("pub async fn load_with_posts_stream < C > (parents : Vec < Self > , conn : & mut C) -> diesel :: QueryResult < Vec < (Self , Vec < Post >) >>"));
+        assert!(code.contains(
+            "where C : diesel_async :: AsyncConnection < Backend = diesel :: pg :: Pg > ,"
+        ));
+        assert!(code.contains(". load_stream :: < (i32 , Post) > (conn) . await ?"));
+        assert!(
+            code.contains("while let Some ((parent_id , child)) = rows . try_next () . await ?")
+        );
+        assert!(code.contains("Ok (parents . into_iter () . map (| parent | { let parent_children = children . remove (& parent . id) . unwrap_or_default () ;"));
+        // The sync loaders stay behind `eager_loading`.
+        assert!(!code.contains("fn load_with_posts <"));
+
+        let relation_attrs = RelationAttributes {
+            chunk_size: Some(900),
+            ..relation_attrs
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(code.contains("for parent_ids in parent_ids . chunks (900) { let rows = posts . filter (user_id . eq_any (parent_ids))"));
+    }
+
+    #[test]
+    #[cfg(feature = "async")]
+    fn test_async_count_and_exists_methods() {
+        let struct_name = Ident::new("User", Span::call_site());
+        let relation_attrs = RelationAttributes {
+            count: true,
+            exists: true,
+            async_: true,
+            backends: vec!["postgres".to_string()],
+            ..one_to_many_attrs()
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(code.contains("pub async fn count_posts < C > (& self , conn : & mut C) -> diesel :: QueryResult < i64 > where C : diesel_async :: AsyncConnection < Backend = diesel :: pg :: Pg > ,"));
+        assert!(code.contains("use diesel :: prelude :: * ; use diesel_async :: RunQueryDsl ;"));
+        assert!(code.contains(". count () . get_result :: < i64 > (conn) . await"));
+        assert!(code.contains("pub async fn has_posts < C >"));
+        assert!(code.contains(". get_result :: < bool > (conn) . await"));
+        // The getters stay synchronous.
+        assert!(code.contains("pub fn children < C > (& self , conn : & mut C)"));
+        // Generic over the connection, so the pooled objects of deadpool, bb8 and mobc are accepted.
+        assert!(!code.contains("AsyncPgConnection"));
+
+        let relation_attrs = RelationAttributes {
+            count: true,
+            async_: true,
+            ..many_to_many_attrs("Tag", None)
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(code.contains(
+            "pub async fn count_tags < C > (& self , conn : & mut C) -> diesel :: QueryResult < i64 > where C : diesel_async :: AsyncConnection ,"
+        ));
+
+        let relation_attrs = RelationAttributes {
+            child_model: "Profile".to_string(),
+            fk: "user_id".to_string(),
+            relation_type: "one_to_one".to_string(),
+            exists: true,
+            async_: true,
+            connection_type: Some("crate::db::SharedConn".to_string()),
+            ..Default::default()
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        // The async connection is taken by `&mut` whatever `connection_type` says.
+        assert!(code.contains("pub async fn has_profile < C > (& self , conn : & mut C) -> diesel :: QueryResult < bool > where C : diesel_async :: AsyncConnection ,"));
+        assert!(code.contains("profiles . filter (user_id . eq (self . id)))) . get_result :: < bool > (conn) . await"));
+
+        let relation_attrs = RelationAttributes {
+            child_model: "User".to_string(),
+            relation_type: "many_to_one".to_string(),
+            connection_type: None,
+            ..relation_attrs
+        };
+        let code = generate_relation_code(&Ident::new("Post", Span::call_site()), &relation_attrs)
+            .to_string();
+        assert!(code.contains("pub async fn has_user < C > (& self , conn : & mut C) -> diesel :: QueryResult < bool > where C : diesel_async :: AsyncConnection ,"));
+    }
+
+    #[test]
+    #[cfg(feature = "async")]
+    fn test_streaming_chunks_method() {
+        let struct_name = Ident::new("User", Span::call_site());
+        let relation_attrs = RelationAttributes {
+            streaming: true,
+            backends: vec!["postgres".to_string()],
+            ..one_to_many_attrs()
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(!code.contains("get_posts_chunks"));
+
+        let relation_attrs = RelationAttributes {
+            async_: true,
+            ..relation_attrs
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(code.contains("pub fn get_posts_chunks < 'a , C > (& 'a self , conn : & 'a mut C , chunk_size : usize) -> impl futures_util :: Stream < Item = diesel :: QueryResult < Vec < Post >> > + Send + 'a"));
+        assert!(code.contains(
+            "where C : diesel_async :: AsyncConnection < Backend = diesel :: pg :: Pg > ,"
+        ));
+        // A single query, whose rows are grouped into chunks of up to `chunk_size` as they arrive.
+        assert!(code.contains("futures_util :: stream :: once (posts . filter (user_id . eq (self . id)) . load_stream :: < Post > (conn) ,) . try_flatten () . try_chunks (chunk_size) . map_err (| error | error . 1)"));
+    }
+
+    #[test]
+    fn test_one_to_many_custom_key_type_bounds() {
+        let struct_name = Ident::new("User", Span::call_site());
+        let relation_attrs = RelationAttributes {
+            eager_loading: true,
+            ..one_to_many_attrs()
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(code.contains("let parent_ids : Vec < i32 >"));
+        assert!(!code.contains("AsExpression"));
+
+        let relation_attrs = RelationAttributes {
+            eager_loading: true,
+            fk_type: Some("UserId".to_string()),
+            ..one_to_many_attrs()
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(!code.contains("Vec < i32 >"));
+        assert!(code.contains("skip_keys : & std :: collections :: HashSet < UserId >"));
+        assert!(code.contains(". load :: < (UserId , Post) > (conn) ?"));
+        assert!(code.contains(
+            "-> diesel :: QueryResult < std :: collections :: HashMap < UserId , i64 >>"
+        ));
+        assert!(
+            code.contains("diesel :: dsl :: Eq < crate :: schema :: posts :: user_id , UserId >")
+        );
+        let bounds = "UserId : diesel :: expression :: AsExpression < diesel :: dsl :: SqlTypeOf < crate :: schema :: posts :: user_id > > \
+            + diesel :: serialize :: ToSql < diesel :: dsl :: SqlTypeOf < crate :: schema :: posts :: user_id > , < C as diesel :: Connection > :: Backend > \
+            + diesel :: deserialize :: FromSql < diesel :: dsl :: SqlTypeOf < crate :: schema :: posts :: user_id > , < C as diesel :: Connection > :: Backend > ,";
+        // load_with_<plural>, load_with_<plural>_except, load_<plural>_map and get_<plural>_count_map.
+        assert_eq!(code.matches(bounds).count(), 4);
+    }
+
+    #[test]
+    fn test_many_to_many_independent_key_types() {
+        let struct_name = Ident::new("Post", Span::call_site());
+
+        let code =
+            generate_relation_code(&struct_name, &many_to_many_attrs("Tag", None)).to_string();
+        assert!(code.contains("let parent_id : & i32 = & self . id ;"));
+        assert!(code.contains("load :: < i32 > (conn) ?"));
+
+        let relation_attrs = RelationAttributes {
+            fk_parent_type: Some("i64".to_string()),
+            fk_child_type: Some("uuid::Uuid".to_string()),
+            ..many_to_many_attrs("Tag", None)
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(code.contains("let parent_id : & i64 = & self . id ;"));
+        assert!(code.contains("load :: < uuid :: Uuid > (conn) ?"));
+        assert!(code.contains(
+            "fn add_tag < C > (& self , conn : & mut C , child_id : impl Into < uuid :: Uuid >)"
+        ));
+        assert!(code.contains(
+            "fn remove_tag < C > (& self , conn : & mut C , child_id : impl Into < uuid :: Uuid >)"
+        ));
+        assert!(code.contains("let child_id : uuid :: Uuid = child_id . into () ;"));
+    }
+
+    #[test]
+    fn test_many_to_many_mutators_borrow_non_copy_parent_key() {
+        let struct_name = Ident::new("Post", Span::call_site());
+        let relation_attrs = RelationAttributes {
+            fk_parent_type: Some("uuid::Uuid".to_string()),
+            count: true,
+            exists: true,
+            paginate: true,
+            ..many_to_many_attrs("Tag", None)
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert_eq!(
+            code.matches("let parent_id : & uuid :: Uuid = & self . id ;")
+                .count(),
+            4
+        );
+        assert!(!code.contains("= self . id ;"));
+        // No method, `query_<plural>` included, moves the key out of `&self`.
+        assert!(!code.contains("eq (self . id)"));
+        assert!(code.contains(
+            ". values ((join_dsl :: post_id . eq (parent_id) , join_dsl :: data_id . eq (child_id)))"
+        ));
+    }
+
+    #[test]
+    fn test_relation_scoped_boxed_query() {
+        let struct_name = Ident::new("User", Span::call_site());
+
+        let code = generate_relation_code(&struct_name, &one_to_many_attrs()).to_string();
+        assert!(code.contains("pub fn query_posts < DB > (& self) -> crate :: schema :: posts :: BoxedQuery < 'static , DB >"));
+        assert!(code.contains("diesel :: dsl :: Filter < crate :: schema :: posts :: table , diesel :: dsl :: Eq < crate :: schema :: posts :: user_id , i32 > , > : diesel :: query_dsl :: methods :: BoxedDsl"));
+        assert!(code.contains(
+            ". filter (crate :: schema :: posts :: user_id . eq (self . id)) . into_boxed ()"
+        ));
+
+        let relation_attrs = RelationAttributes {
+            backends: vec!["sqlite".to_string()],
+            ..many_to_many_attrs("Tag", None)
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(code.contains("pub fn query_tags (& self) -> crate :: schema :: tags :: BoxedQuery < 'static , diesel :: sqlite :: Sqlite >"));
+        assert!(code.contains("crate :: schema :: tags :: id . eq_any (crate :: schema :: post_data :: table . filter (crate :: schema :: post_data :: post_id . eq (self . id . clone ())) . select (crate :: schema :: post_data :: data_id) ,)"));
+
+        // The boxed query is `'static`, so a non-`Copy` parent key is cloned rather than moved out of `self`.
+        let relation_attrs = RelationAttributes {
+            fk_parent_type: Some("uuid::Uuid".to_string()),
+            ..many_to_many_attrs("Tag", None)
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(code.contains(
+            "diesel :: dsl :: Eq < crate :: schema :: post_data :: post_id , uuid :: Uuid >"
+        ));
+        assert!(code.contains(
+            ". filter (crate :: schema :: post_data :: post_id . eq (self . id . clone ()))"
+        ));
+    }
+
+    #[test]
+    fn test_query_fn() {
+        let struct_name = Ident::new("User", Span::call_site());
+        let code = generate_relation_code(&struct_name, &one_to_many_attrs()).to_string();
+        assert!(!code.contains("fn posts_of"));
+
+        let relation_attrs = RelationAttributes {
+            query_fn: true,
+            ..one_to_many_attrs()
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(code.contains("pub fn posts_of < DB > (parent : & User) -> crate :: schema :: posts :: BoxedQuery < 'static , DB >"));
+        assert!(code.contains("{ parent . query_posts () }"));
+
+        let relation_attrs = RelationAttributes {
+            query_fn: true,
+            backends: vec!["sqlite".to_string()],
+            ..many_to_many_attrs("Tag", None)
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(code.contains("pub fn tags_of (parent : & User) -> crate :: schema :: tags :: BoxedQuery < 'static , diesel :: sqlite :: Sqlite >"));
+    }
+
+    #[test]
+    fn test_filtered_method() {
+        let struct_name = Ident::new("User", Span::call_site());
+        let code = generate_relation_code(&struct_name, &one_to_many_attrs()).to_string();
+        assert!(!code.contains("fn get_posts_filtered"));
+
+        // Called as `user.get_posts_filtered(conn, posts::published.eq(true))`.
+        let relation_attrs = RelationAttributes {
+            filtered: true,
+            ..one_to_many_attrs()
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(code.contains("pub fn get_posts_filtered < C , P > (& self , conn : & mut C , predicate : P) -> diesel :: QueryResult < Vec < Post >>"));
+        assert!(code.contains("crate :: schema :: posts :: BoxedQuery < 'static , < C as diesel :: Connection > :: Backend > : diesel :: query_dsl :: methods :: FilterDsl < P , Output = crate :: schema :: posts :: BoxedQuery < 'static , < C as diesel :: Connection > :: Backend > > ,"));
+        assert!(code.contains("self . query_posts :: << C as diesel :: Connection > :: Backend > () . filter (predicate) . load :: < Post > (conn)"));
+
+        let relation_attrs = RelationAttributes {
+            filtered: true,
+            backends: vec!["postgres".to_string()],
+            ..many_to_many_attrs("Tag", None)
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(code.contains("self . query_tags () . filter (predicate) . load :: < Tag > (conn)"));
+    }
+
+    #[test]
+    fn test_where_closure_method() {
+        let struct_name = Ident::new("User", Span::call_site());
+        let code = generate_relation_code(&struct_name, &one_to_many_attrs()).to_string();
+        assert!(!code.contains("fn get_posts_where"));
+
+        // Called as `user.get_posts_where(conn, |query| query.filter(posts::published.eq(true)).limit(10))`.
+        let relation_attrs = RelationAttributes {
+            where_closure: true,
+            ..one_to_many_attrs()
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(code.contains("pub fn get_posts_where < C , F > (& self , conn : & mut C , refine : F) -> diesel :: QueryResult < Vec < Post >>"));
+        assert!(code.contains("F : FnOnce (crate :: schema :: posts :: BoxedQuery < 'static , < C as diesel :: Connection > :: Backend >) -> crate :: schema :: posts :: BoxedQuery < 'static , < C as diesel :: Connection > :: Backend > ,"));
+        assert!(code.contains("refine (self . query_posts :: << C as diesel :: Connection > :: Backend > ()) . load :: < Post > (conn)"));
+
+        let relation_attrs = RelationAttributes {
+            where_closure: true,
+            selectable: true,
+            backends: vec!["postgres".to_string()],
+            ..many_to_many_attrs("Tag", None)
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(code.contains("refine (self . query_tags ()) . select (< Tag as diesel :: SelectableHelper < diesel :: pg :: Pg >> :: as_select ()) . load :: < Tag > (conn)"));
+    }
+
+    #[test]
+    fn test_debug_sql_method() {
+        let struct_name = Ident::new("User", Span::call_site());
+        let code = generate_relation_code(&struct_name, &one_to_many_attrs()).to_string();
+        assert!(!code.contains("posts_query_debug"));
+
+        let relation_attrs = RelationAttributes {
+            debug_sql: true,
+            backends: vec!["postgres".to_string()],
+            default_filter: vec![FilterCondition::In(
+                "status".to_string(),
+                vec!["\"draft\"".to_string(), "\"published\"".to_string()],
+            )],
+            ..one_to_many_attrs()
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(code.contains("pub fn posts_query_debug (& self) -> (String , Vec < String >)"));
+        assert!(code.contains("let query = posts . filter (user_id . eq (self . id)) . filter (crate :: schema :: posts :: status . eq_any ([\"draft\" , \"published\"]))"));
+        assert!(code.contains("let rendered = diesel :: debug_query :: < diesel :: pg :: Pg , _ > (& query) . to_string () ; (rendered , vec !"));
+        assert!(!code.contains("rsplit_once"));
+        assert!(code.contains("vec ! [format ! (\"{:?}\" , self . id) , format ! (\"{:?}\" , \"draft\") , format ! (\"{:?}\" , \"published\")]"));
+
+        // The debug query is the one `children` runs, ordering included.
+        let relation_attrs = RelationAttributes {
+            order_by: Some("created_at".to_string()),
+            ..relation_attrs
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        let query = "posts . filter (user_id . eq (self . id)) . filter (crate :: schema :: posts :: status . eq_any ([\"draft\" , \"published\"])) . order (created_at)";
+        assert!(code.contains(&format!("{} . load :: < Post > (conn)", query)));
+        assert!(code.contains(&format!("let query = {} ;", query)));
+    }
+
+    #[test]
+    fn test_debug_sql_binds_the_polymorphic_discriminator() {
+        let struct_name = Ident::new("Post", Span::call_site());
+        let relation_attrs = RelationAttributes {
+            debug_sql: true,
+            backends: vec!["postgres".to_string()],
+            type_column: Some("commentable_type".to_string()),
+            type_value: Some("Post".to_string()),
+            default_filter: vec![FilterCondition::Eq(
+                "approved".to_string(),
+                "true".to_string(),
+            )],
+            ..one_to_many_attrs()
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        let debug = &code[code.find("pub fn posts_query_debug").unwrap()..];
+        let query = &debug[debug.find("let query =").unwrap()..debug.find("let rendered").unwrap()];
+        let binds = &debug[debug.find("vec ! [").unwrap()..];
+        let binds = &binds[..binds.find(']').unwrap()];
+        // One rendered value per placeholder: the parent key, `approved` and the discriminator.
+        assert_eq!(query.matches(". eq (").count(), 3);
+        assert_eq!(binds.matches("format !").count(), 3);
+        assert!(binds.contains("format ! (\"{:?}\" , \"Post\")"));
+    }
+
+    #[test]
+    fn test_related_table_in_another_schema() {
+        let struct_name = Ident::new("User", Span::call_site());
+        let relation_attrs = RelationAttributes {
+            child_model: "Event".to_string(),
+            related_table: Some("analytics::events".to_string()),
+            ..one_to_many_attrs()
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(code.contains("use crate :: schema :: analytics :: events :: dsl :: * ;"));
+        assert!(code.contains("events . filter (user_id . eq (self . id))"));
+        assert!(code.contains("crate :: schema :: analytics :: events :: BoxedQuery"));
+
+        let relation_attrs = RelationAttributes {
+            related_table: Some("analytics::tags".to_string()),
+            ..many_to_many_attrs("Tag", None)
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(code.contains("use crate :: schema :: analytics :: tags :: dsl :: * ;"));
+        assert!(code.contains("use crate :: schema :: post_data :: dsl as join_dsl ;"));
+    }
+
+    #[test]
+    fn test_schema_path() {
+        let struct_name = Ident::new("User", Span::call_site());
+        let relation_attrs = RelationAttributes {
+            schema_path: Some("crate::db::schema".to_string()),
+            query_fn: true,
+            ..one_to_many_attrs()
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(!code.contains("crate :: schema"));
+        assert!(code.contains("use crate :: db :: schema :: posts :: dsl :: *"));
+        assert!(code.contains("crate :: db :: schema :: posts :: BoxedQuery < 'static , DB >"));
+
+        let relation_attrs = RelationAttributes {
+            schema_path: Some("crate::db::schema".to_string()),
+            ..many_to_many_attrs("Tag", None)
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        let items = generate_support_items(&struct_name, &relation_attrs).to_string();
+        assert!(!code.contains("crate :: schema"));
+        assert!(code.contains("use crate :: db :: schema :: post_data :: dsl as join_dsl"));
+        assert!(items.contains(
+            "crate :: db :: schema :: post_data :: table , crate :: db :: schema :: tags :: table"
+        ));
+
+        let relation_attrs = RelationAttributes {
+            child_model: "User".to_string(),
+            fk: "user_id".to_string(),
+            relation_type: "many_to_one".to_string(),
+            schema_path: Some("crate::db::schema".to_string()),
+            ..Default::default()
+        };
+        let code = generate_relation_code(&Ident::new("Post", Span::call_site()), &relation_attrs)
+            .to_string();
+        assert!(code.contains("crate :: db :: schema :: posts :: table . filter"));
+    }
+
+    #[test]
+    fn test_no_inflection_uses_explicit_names() {
+        let struct_name = Ident::new("Post", Span::call_site());
+        let relation_attrs = RelationAttributes {
+            no_inflection: true,
+            singular: Some("status".to_string()),
+            plural: Some("status_list".to_string()),
+            related_table: Some("status".to_string()),
+            ..many_to_many_attrs("Status", None)
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(!code.contains("statuses"));
+        assert!(code.contains("use crate :: schema :: status :: dsl :: *"));
+        assert!(code.contains("fn add_status < C >"));
+        assert!(code.contains("fn query_status_list < DB >"));
+
+        let relation_attrs = RelationAttributes {
+            no_inflection: true,
+            wrapper: true,
+            singular: Some("post".to_string()),
+            plural: Some("blog_posts".to_string()),
+            related_table: Some("posts".to_string()),
+            ..one_to_many_attrs()
+        };
+        let items = generate_support_items(&Ident::new("User", Span::call_site()), &relation_attrs)
+            .to_string();
+        assert!(items.contains("pub struct UserWithBlogPosts"));
+
+        // A projection tuple is named after `singular` as written.
+        let relation_attrs = RelationAttributes {
+            child_model: "(i32, String)".to_string(),
+            columns: Some("id, title".to_string()),
+            aggregates: vec![Aggregate::Count],
+            ..relation_attrs
+        };
+        assert_eq!(child_type_name(&relation_attrs), "post");
+        let items = generate_support_items(&Ident::new("User", Span::call_site()), &relation_attrs)
+            .to_string();
+        assert!(items.contains("# [allow (non_camel_case_types)] pub struct postStats"));
+
+        let relation_attrs = RelationAttributes {
+            no_inflection: false,
+            ..relation_attrs
+        };
+        assert_eq!(child_type_name(&relation_attrs), "Post");
+    }
+
+    #[test]
+    fn test_no_inflection_requires_declared_self_table() {
+        let struct_name = Ident::new("Post", Span::call_site());
+        let relation_attrs = RelationAttributes {
+            child_model: "User".to_string(),
+            fk: "user_id".to_string(),
+            relation_type: "many_to_one".to_string(),
+            no_inflection: true,
+            ..Default::default()
+        };
+        let error = check_self_table(&struct_name, &relation_attrs).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "no_inflection requires the table of Post to be declared with table_name"
+        );
+
+        let relation_attrs = RelationAttributes {
+            table_name: Some("posts".to_string()),
+            ..relation_attrs
+        };
+        assert!(check_self_table(&struct_name, &relation_attrs).is_ok());
+    }
+
+    #[test]
+    fn test_readonly_suppresses_mutators() {
+        let struct_name = Ident::new("User", Span::call_site());
+        let relation_attrs = RelationAttributes {
+            readonly: true,
+            related_table: Some("active_posts".to_string()),
+            ..one_to_many_attrs()
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(code.contains("active_posts . filter (user_id . eq (self . id))"));
+        assert!(!code.contains("fn add_child"));
+        assert!(!code.contains("fn remove_child"));
+        assert!(!code.contains("insert_into"));
+
+        let relation_attrs = RelationAttributes {
+            readonly: true,
+            ..many_to_many_attrs("Tag", None)
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(code.contains("fn related_entities < C >"));
+        assert!(!code.contains("fn add_tag"));
+        assert!(!code.contains("fn remove_tag"));
+
+        let relation_attrs = RelationAttributes {
+            child_model: "Profile".to_string(),
+            fk: "user_id".to_string(),
+            relation_type: "one_to_one".to_string(),
+            readonly: true,
+            ..Default::default()
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(code.contains("fn get_related_entity < C >"));
+        assert!(!code.contains("fn set_related_entity"));
+    }
+
+    #[test]
+    fn test_declared_table_name() {
+        let item_struct: ItemStruct = syn::parse_quote! {
+            #[derive(Queryable)]
+            #[diesel(belongs_to(User), table_name = some::module::posts, check_for_backend(diesel::pg::Pg))]
+            pub struct Post {
+                pub id: i32,
+            }
+        };
+        assert_eq!(
+            declared_table_name(&item_struct).as_deref(),
+            Some("some::module::posts")
+        );
+
+        let item_struct: ItemStruct = syn::parse_quote! {
+            #[table_name = "posts"]
+            pub struct Post {
+                pub id: i32,
+            }
+        };
+        assert_eq!(declared_table_name(&item_struct).as_deref(), Some("posts"));
+
+        let item_struct: ItemStruct = syn::parse_quote! {
+            pub struct Post {
+                pub id: i32,
+            }
+        };
+        assert_eq!(declared_table_name(&item_struct), None);
+    }
+
+    #[test]
+    fn test_renamed_fk_field_and_column() {
+        // `Post::author` is mapped to the `user_fk` column with `#[diesel(column_name = user_fk)]`.
+        let struct_name = Ident::new("User", Span::call_site());
+        let relation_attrs = RelationAttributes {
+            fk: "author".to_string(),
+            fk_column: Some("user_fk".to_string()),
+            eager_loading: true,
+            ..one_to_many_attrs()
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(!code.contains("author"));
+        assert!(
+            code.contains("posts . filter (user_fk . eq (self . id)) . load :: < Post > (conn)")
+        );
+        assert!(code.contains(". filter (user_fk . eq_any (parent_ids))"));
+
+        let struct_name = Ident::new("Post", Span::call_site());
+        let relation_attrs = RelationAttributes {
+            child_model: "User".to_string(),
+            fk: "user_fk".to_string(),
+            fk_field: Some("author".to_string()),
+            relation_type: "many_to_one".to_string(),
+            eager_loading: true,
+            ..Default::default()
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(!code.contains("user_fk"));
+        assert!(code.contains(". filter (id . eq (self . author))"));
+        assert!(code.contains("groups . entry (child . author . clone ())"));
+    }
+
+    #[test]
+    fn test_many_to_one_load_parents_with_children() {
+        let struct_name = Ident::new("Post", Span::call_site());
+        let relation_attrs = RelationAttributes {
+            child_model: "User".to_string(),
+            fk: "user_id".to_string(),
+            relation_type: "many_to_one".to_string(),
+            ..Default::default()
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(!code.contains("load_parents_with_children"));
+
+        // A flat post list is grouped under its users, each user being loaded once.
+        let relation_attrs = RelationAttributes {
+            eager_loading: true,
+            ..relation_attrs
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(code.contains("pub fn load_parents_with_children < C > (children : Vec < Self > , conn : & mut C) -> diesel :: QueryResult < Vec < (User , Vec < Self >) >>"));
+        assert!(code.contains("for child in children { groups . entry (child . user_id . clone ()) . or_default () . push (child) ; }"));
+        // The users come back in the order they first appear in the posts, not in key order.
+        assert!(code.contains("let parent_keys : Vec < i32 > = children . iter () . map (| child | child . user_id . clone ()) . filter (| key | seen . insert (key . clone ())) . collect () ;"));
+        assert!(code.contains("crate :: schema :: users :: table . filter (crate :: schema :: users :: id . eq_any (& parent_keys [..])) . load :: < User > (conn) ? . into_iter () . map (| parent | (parent . id . clone () , parent)) . collect () ;"));
+        assert!(!code.contains(". order (crate :: schema :: users :: id)"));
+        assert!(code.contains("parent_keys . into_iter () . filter_map (| key | { let parent = parents . remove (& key) ? ; let children = groups . remove (& key) . unwrap_or_default () ; Some ((parent , children)) })"));
+        // Children left in `groups` once every parent took its own are orphans.
+        assert!(code.contains("debug_assert ! (groups . is_empty () , \"load_parents_with_children: {} `Post` row(s) reference a missing `User`\" , groups . values () . map (Vec :: len) . sum :: < usize > ()) ; Ok (loaded)"));
+        // The orphan panic points at the caller of the loader.
+        assert!(code.contains("# [track_caller] pub fn load_parents_with_children < C >"));
+
+        let relation_attrs = RelationAttributes {
+            optional: true,
+            ..relation_attrs
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(!code.contains("debug_assert !"));
+        assert!(!code.contains("track_caller"));
+    }
+
+    #[test]
+    fn test_load_parents_with_children_nullable_fk() {
+        let item_struct: ItemStruct = syn::parse_quote! {
+            pub struct Post {
+                pub id: i32,
+                pub user_id: Option<i32>,
+                pub editor_id: i32,
+            }
+        };
+        assert!(is_option_field(&item_struct, "user_id"));
+        assert!(!is_option_field(&item_struct, "editor_id"));
+
+        let struct_name = Ident::new("Post", Span::call_site());
+        let relation_attrs = RelationAttributes {
+            child_model: "User".to_string(),
+            fk: "user_id".to_string(),
+            relation_type: "many_to_one".to_string(),
+            eager_loading: true,
+            nullable_fk: true,
+            ..Default::default()
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        // Posts without user are returned under a `None` parent.
+        assert!(code.contains("diesel :: QueryResult < Vec < (Option < User > , Vec < Self >) >>"));
+        assert!(code.contains("let parent_keys : Vec < Option < i32 > > = children"));
+        assert!(code.contains("let loaded_keys : Vec < i32 > = parent_keys . iter () . filter_map (| key | key . clone ()) . collect () ;"));
+        assert!(code.contains(
+            "let mut groups : std :: collections :: HashMap < Option < i32 > , Vec < Self >>"
+        ));
+        assert!(code.contains(". eq_any (& loaded_keys [..])"));
+        assert!(code.contains("let parent = match & key { Some (parent_key) => Some (parents . remove (parent_key) ?) , None => None , } ;"));
+    }
+
+    #[test]
+    fn test_internal_helpers_are_doc_hidden() {
+        let struct_name = Ident::new("User", Span::call_site());
+        let relation_attrs = RelationAttributes {
+            debug_sql: true,
+            eager_loading: true,
+            backends: vec!["postgres".to_string()],
+            ..one_to_many_attrs()
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(code.contains("# [doc (hidden)] pub fn posts_query_debug (& self)"));
+        assert!(code.contains("# [doc (hidden)] pub fn load_with_posts_except < C >"));
+        // The primary getters and loaders stay documented, the batch counter included.
+        assert_eq!(code.matches("# [doc (hidden)]").count(), 2);
+        assert!(code.contains("pub fn get_posts_count_map < C >"));
+        assert!(!code.contains("# [doc (hidden)] pub fn get_posts_count_map"));
+        assert!(code.contains("pub fn children < C >"));
+        assert!(code.contains("pub fn load_with_posts < C >"));
+    }
+
+    #[test]
+    fn test_self_table_from_table_name() {
+        let struct_name = Ident::new("Post", Span::call_site());
+        let relation_attrs = RelationAttributes {
+            child_model: "User".to_string(),
+            fk: "user_id".to_string(),
+            relation_type: "many_to_one".to_string(),
+            ..Default::default()
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(code.contains(
+            "crate :: schema :: posts :: table . filter (crate :: schema :: posts :: user_id"
+        ));
+
+        let relation_attrs = RelationAttributes {
+            table_name: Some("some::module::posts".to_string()),
+            ..relation_attrs
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(code.contains(
+            "some :: module :: posts :: table . filter (some :: module :: posts :: user_id"
+        ));
+    }
+
+    #[test]
+    fn test_one_to_many_page_method() {
+        let struct_name = Ident::new("User", Span::call_site());
+        let code = generate_relation_code(&struct_name, &one_to_many_attrs()).to_string();
+        assert!(!code.contains("get_posts_page"));
+
+        let relation_attrs = RelationAttributes {
+            paginate: true,
+            ..one_to_many_attrs()
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(code
+            .contains("fn get_posts_page < C > (& self , conn : & mut C , page : crate :: Page)"));
+        assert!(code.contains(". order (id) . limit (page . limit) . offset (page . offset)"));
+
+        let relation_attrs = RelationAttributes {
+            paginate: true,
+            order_by: Some("created_at".to_string()),
+            ..one_to_many_attrs()
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(code.contains(". order (created_at) . limit (page . limit)"));
+    }
+
+    #[test]
+    fn test_many_to_many_page_method() {
+        let struct_name = Ident::new("Post", Span::call_site());
+        let code =
+            generate_relation_code(&struct_name, &many_to_many_attrs("Tag", None)).to_string();
+        assert!(!code.contains("get_tags_page"));
+
+        let relation_attrs = RelationAttributes {
+            paginate: true,
+            ..many_to_many_attrs("Tag", None)
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(code
+            .contains("fn get_tags_page < C > (& self , conn : & mut C , page : crate :: Page)"));
+        assert!(code.contains(
+            "tags . filter (id . eq_any (related_ids)) . order (id . asc ()) . limit (page . limit) . offset (page . offset) . load :: < Tag > (conn)"
+        ));
+    }
+
+    #[test]
+    fn test_page_has_more_method() {
+        let struct_name = Ident::new("User", Span::call_site());
+        let code = generate_relation_code(&struct_name, &one_to_many_attrs()).to_string();
+        assert!(!code.contains("get_posts_page_has_more"));
+
+        let relation_attrs = RelationAttributes {
+            paginate: true,
+            ..one_to_many_attrs()
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(code.contains("pub fn get_posts_page_has_more < C > (& self , conn : & mut C , page : crate :: Page) -> diesel :: QueryResult < (Vec < Post > , bool) >"));
+        assert!(code.contains("let limit = page . limit . max (0) ; let mut children = self . get_posts_page (conn , crate :: Page { limit : limit . saturating_add (1) , .. page }) ? ; let limit = usize :: try_from (limit) . unwrap_or (usize :: MAX) ;"));
+        assert!(code
+            .contains("let has_more = children . len () > limit ; children . truncate (limit) ;"));
+
+        let relation_attrs = RelationAttributes {
+            paginate: true,
+            ..many_to_many_attrs("Tag", None)
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(code.contains("self . get_tags_page (conn , crate :: Page"));
+
+        let relation_attrs = RelationAttributes {
+            paginate: true,
+            connection_type: Some("crate::db::SharedConn".to_string()),
+            ..one_to_many_attrs()
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(code.contains("self . get_posts_page (* conn , crate :: Page { limit : limit . saturating_add (1) , .. page })"));
+    }
+
+    #[test]
+    fn test_cursor_methods() {
+        let struct_name = Ident::new("User", Span::call_site());
+        let code = generate_relation_code(&struct_name, &one_to_many_attrs()).to_string();
+        assert!(!code.contains("get_posts_after"));
+
+        let relation_attrs = RelationAttributes {
+            cursor_column: Some("id".to_string()),
+            cursor_type: Some("i64".to_string()),
+            ..one_to_many_attrs()
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(code.contains("pub fn get_posts_after < C > (& self , conn : & mut C , cursor : Option < i64 > , limit : i64) -> diesel :: QueryResult < Vec < Post >>"));
+        assert!(code.contains("let mut query = posts . filter (user_id . eq (self . id)) . into_boxed () ; if let Some (cursor) = cursor { query = query . filter (id . gt (cursor)) ; } query . order (id . asc ()) . limit (limit) . load :: < Post > (conn)"));
+
+        let relation_attrs = RelationAttributes {
+            cursor_column: Some("id".to_string()),
+            ..many_to_many_attrs("Tag", None)
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(code.contains("pub fn get_tags_after < C > (& self , conn : & mut C , cursor : Option < i32 > , limit : i64)"));
+        assert!(code.contains(
+            "let mut query = tags . filter (id . eq_any (related_ids)) . into_boxed () ;"
+        ));
+    }
+
+    #[test]
+    fn test_ids_methods() {
+        let struct_name = Ident::new("User", Span::call_site());
+        let code = generate_relation_code(&struct_name, &one_to_many_attrs()).to_string();
+        assert!(!code.contains("get_post_ids"));
+
+        let relation_attrs = RelationAttributes {
+            ids: true,
+            ids_type: Some("i64".to_string()),
+            ..one_to_many_attrs()
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(code.contains("pub fn get_post_ids < C > (& self , conn : & mut C) -> diesel :: QueryResult < Vec < i64 >>"));
+        assert!(code.contains("posts . filter (user_id . eq (self . id)) . order (id) . select (id) . load :: < i64 > (conn)"));
+
+        let relation_attrs = RelationAttributes {
+            ids: true,
+            fk_child_type: Some("uuid::Uuid".to_string()),
+            ..many_to_many_attrs("Tag", None)
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(code.contains("pub fn get_tag_ids < C > (& self , conn : & mut C) -> diesel :: QueryResult < Vec < uuid :: Uuid >>"));
+        assert!(code.contains(". select (join_dsl :: data_id))) . order (id) . select (id) . load :: < uuid :: Uuid > (conn)"));
+    }
+
+    #[test]
+    fn test_nested_eager_loading() {
+        let struct_name = Ident::new("User", Span::call_site());
+        let relation_attrs = RelationAttributes {
+            eager_loading: true,
+            ..one_to_many_attrs()
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(!code.contains("load_with_posts_and_"));
+
+        let relation_attrs = RelationAttributes {
+            nested: Some("comments".to_string()),
+            nested_child: Some("Comment".to_string()),
+            ..relation_attrs
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(code.contains("pub fn load_with_posts_and_comments < C > (parents : Vec < Self > , conn : & mut C) -> diesel :: QueryResult < Vec < (Self , Vec < (Post , Vec < Comment >) >) >>"));
+        assert!(code.contains("let loaded = Self :: load_with_posts (parents , conn) ? ;"));
+        assert!(code.contains("let mut grandchildren = < Post > :: load_with_comments (all_children , conn) ? . into_iter () ;"));
+        assert!(code.contains(". map (| (parent , count) | (parent , grandchildren . by_ref () . take (count) . collect ()))"));
+    }
+
+    #[test]
+    fn test_load_map_method() {
+        let struct_name = Ident::new("User", Span::call_site());
+        let code = generate_relation_code(&struct_name, &one_to_many_attrs()).to_string();
+        assert!(!code.contains("load_posts_map"));
+
+        let relation_attrs = RelationAttributes {
+            eager_loading: true,
+            ..one_to_many_attrs()
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(code.contains("pub fn load_posts_map < C > (parents : & [Self] , conn : & mut C) -> diesel :: QueryResult < std :: collections :: HashMap < i32 , Vec < Post >> >"));
+        assert!(code.contains("let parent_ids : Vec < i32 > = parents . iter () . map (| parent | parent . id) . collect () ; let mut children : std :: collections :: HashMap < i32 , Vec < Post >> = std :: collections :: HashMap :: new () ; for (parent_id , child) in posts . filter (user_id . eq_any (parent_ids)) . order (id)"));
+        assert!(code.contains(
+            "children . entry (parent_id) . or_default () . push (child) ; } Ok (children) }"
+        ));
+    }
+
+    #[test]
+    fn test_chunked_batch_loaders() {
+        let struct_name = Ident::new("User", Span::call_site());
+        let relation_attrs = RelationAttributes {
+            eager_loading: true,
+            aggregates: vec![Aggregate::Count],
+            ..one_to_many_attrs()
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(!code.contains(". chunks ("));
+
+        let relation_attrs = RelationAttributes {
+            chunk_size: Some(900),
+            ..relation_attrs
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        // load_with_posts_except, load_posts_map, load_with_post_stats and get_posts_count_map.
+        assert_eq!(
+            code.matches("let mut rows = Vec :: new () ; for parent_ids in parent_ids . chunks (900) { rows . extend (posts . filter (user_id . eq_any (parent_ids))").count(),
+            4
+        );
+        assert!(code.contains(". load :: < (i32 , Post) > (conn) ?) ; } rows } { children . entry (parent_id) . or_default () . push (child) ; }"));
+
+        let relation_attrs = RelationAttributes {
+            child_model: "User".to_string(),
+            fk: "user_id".to_string(),
+            relation_type: "many_to_one".to_string(),
+            eager_loading: true,
+            chunk_size: Some(900),
+            ..Default::default()
+        };
+        let code = generate_relation_code(&Ident::new("Post", Span::call_site()), &relation_attrs)
+            .to_string();
+        assert!(code.contains("for parent_keys in parent_keys . chunks (900) { rows . extend (crate :: schema :: users :: table . filter (crate :: schema :: users :: id . eq_any (& parent_keys [..])) . load :: < User > (conn) ?) ; }"));
+    }
+
+    #[test]
+    fn test_latest_by_methods() {
+        let struct_name = Ident::new("User", Span::call_site());
+        let code = generate_relation_code(&struct_name, &one_to_many_attrs()).to_string();
+        assert!(!code.contains("get_latest_post"));
+        assert!(!code.contains("get_first_post"));
+
+        let relation_attrs = RelationAttributes {
+            latest_by: Some("created_at".to_string()),
+            ..one_to_many_attrs()
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(code.contains("pub fn get_latest_post < C > (& self , conn : & mut C) -> diesel :: QueryResult < Option < Post >>"));
+        assert!(code
+            .contains(". order (created_at . desc ()) . first :: < Post > (conn) . optional ()"));
+        assert!(code.contains("pub fn get_first_post < C > (& self , conn : & mut C) -> diesel :: QueryResult < Option < Post >>"));
+        assert!(
+            code.contains(". order (created_at . asc ()) . first :: < Post > (conn) . optional ()")
+        );
+    }
+
+    #[test]
+    fn test_paged_methods() {
+        let struct_name = Ident::new("User", Span::call_site());
+        let code = generate_relation_code(&struct_name, &one_to_many_attrs()).to_string();
+        assert!(!code.contains("get_posts_paged"));
+
+        let relation_attrs = RelationAttributes {
+            paginate: true,
+            ..one_to_many_attrs()
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(code.contains("pub fn get_posts_paged < C > (& self , conn : & mut C , page : i64 , per_page : i64) -> diesel :: QueryResult < Vec < Post >>"));
+        assert!(code.contains("let offset = (page . max (1) - 1) . saturating_mul (per_page) ; self . get_posts_page (conn , crate :: Page { limit : per_page , offset })"));
+
+        let relation_attrs = RelationAttributes {
+            paginate: true,
+            ..many_to_many_attrs("Tag", None)
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(code.contains("pub fn get_tags_paged < C >"));
+
+        // With `connection_type`, the page is loaded with the connection taken by value.
+        let relation_attrs = RelationAttributes {
+            paginate: true,
+            connection_type: Some("crate::db::SharedConn".to_string()),
+            ..one_to_many_attrs()
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(code.contains(
+            "self . get_posts_page (* conn , crate :: Page { limit : per_page , offset })"
+        ));
+    }
+
+    #[test]
+    fn test_context_errors_methods() {
+        let struct_name = Ident::new("User", Span::call_site());
+        let code = generate_relation_code(&struct_name, &one_to_many_attrs()).to_string();
+        assert!(!code.contains("RelationError"));
+
+        let relation_attrs = RelationAttributes {
+            context_errors: true,
+            ..one_to_many_attrs()
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(code.contains("pub fn children_with_context < C > (& self , conn : & mut C) -> Result < Vec < Post > , crate :: RelationError >"));
+        // The error carries the relation and the model it was loaded for.
+        assert!(code.contains("self . children (conn) . map_err (| source | crate :: RelationError { relation : \"posts\" , model : \"User\" , source , })"));
+
+        let relation_attrs = RelationAttributes {
+            context_errors: true,
+            ..many_to_many_attrs("Tag", None)
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(code.contains("self . related_entities (conn) . map_err (| source | crate :: RelationError { relation : \"tags\" , model : \"User\" , source , })"));
+
+        let relation_attrs = RelationAttributes {
+            child_model: "UserProfile".to_string(),
+            fk: "user_id".to_string(),
+            relation_type: "one_to_one".to_string(),
+            context_errors: true,
+            ..Default::default()
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(code.contains("pub fn get_related_entity_with_context < C > (& self , conn : & mut C) -> Result < Option < UserProfile > , crate :: RelationError >"));
+        assert!(code.contains("relation : \"user_profile\" , model : \"User\""));
+    }
+
+    #[test]
+    fn test_page_with_total_method() {
+        let struct_name = Ident::new("User", Span::call_site());
+        let relation_attrs = RelationAttributes {
+            paginate: true,
+            ..one_to_many_attrs()
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(!code.contains("get_posts_page_with_total"));
+
+        let relation_attrs = RelationAttributes {
+            paginate: true,
+            count: true,
+            ..one_to_many_attrs()
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(code.contains("pub fn get_posts_page_with_total < C > (& self , conn : & mut C , page : crate :: Page) -> diesel :: QueryResult < (Vec < Post > , i64) >"));
+        // The page and the total are read in the same transaction.
+        assert!(code.contains("conn . transaction :: < _ , diesel :: result :: Error , _ > (| conn | { let children = self . get_posts_page (conn , page) ? ; let total = self . count_posts (conn) ? ; Ok ((children , total)) })"));
+
+        let relation_attrs = RelationAttributes {
+            paginate: true,
+            count: true,
+            connection_type: Some("crate::db::SharedConn".to_string()),
+            ..many_to_many_attrs("Tag", None)
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(code.contains("let children = self . get_tags_page (* conn , page) ? ; let total = self . count_tags (* conn) ? ;"));
+    }
+
+    #[test]
+    fn test_embedded_child_loaders_select_through_selectable() {
+        // `Post` embeds a `#[diesel(embed)]` struct: its row isn't the flat column list of the
+        // table, so every loader has to go through `Selectable` rather than `all_columns`.
+        let struct_name = Ident::new("User", Span::call_site());
+        let relation_attrs = RelationAttributes {
+            selectable: true,
+            eager_loading: true,
+            filtered: true,
+            paginate: true,
+            ..one_to_many_attrs()
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(!code.contains("all_columns"));
+        assert!(code.contains(". select ((user_id , < Post as diesel :: SelectableHelper < _ >> :: as_select ())) . load :: < (i32 , Post) > (conn) ?"));
+        assert!(code.contains(". filter (predicate) . select (< Post as diesel :: SelectableHelper < < C as diesel :: Connection > :: Backend >> :: as_select ()) . load :: < Post > (conn)"));
+        assert!(code.contains("diesel :: dsl :: Select < crate :: schema :: posts :: BoxedQuery < 'static , < C as diesel :: Connection > :: Backend > , diesel :: dsl :: AsSelect < Post , < C as diesel :: Connection > :: Backend > > : diesel :: query_dsl :: LoadQuery < 'static , C , Post > ,"));
+        assert_eq!(
+            code.matches(". load :: < Post > (conn)").count(),
+            code.matches("as_select ()) . load :: < Post > (conn)")
+                .count()
+        );
+    }
+
+    #[test]
+    fn test_load_into_method() {
+        let struct_name = Ident::new("User", Span::call_site());
+        let code = generate_relation_code(&struct_name, &one_to_many_attrs()).to_string();
+        assert!(!code.contains("fn get_posts_into"));
+
+        let relation_attrs = RelationAttributes {
+            load_into: true,
+            ..one_to_many_attrs()
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(code.contains("pub fn get_posts_into < C > (& self , conn : & mut C , buf : & mut Vec < Post >) -> diesel :: QueryResult < () >"));
+        assert!(code.contains("where C : diesel :: connection :: LoadConnection ,"));
+        // The buffer is cleared first, so reusing it across parents never mixes their children.
+        assert!(code.contains("buf . clear () ; let rows = posts . filter (user_id . eq (self . id)) . load_iter :: < Post , diesel :: connection :: DefaultLoadingMode > (conn) ? ; for row in rows { buf . push (row ?) ; } Ok (())"));
+
+        // The buffer is filled in the order of `children`.
+        let relation_attrs = RelationAttributes {
+            order_by: Some("created_at".to_string()),
+            order_desc: true,
+            ..relation_attrs
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(code.contains("let rows = posts . filter (user_id . eq (self . id)) . order (created_at . desc ()) . load_iter :: < Post , diesel :: connection :: DefaultLoadingMode > (conn) ? ;"));
+    }
+
+    #[test]
+    fn test_collect_into_method() {
+        let struct_name = Ident::new("User", Span::call_site());
+        let code = generate_relation_code(&struct_name, &one_to_many_attrs()).to_string();
+        assert!(!code.contains("fn get_posts_collected"));
+
+        let relation_attrs = RelationAttributes {
+            collect_into: Some("smallvec::SmallVec<[Post; 8]>".to_string()),
+            ..one_to_many_attrs()
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(code.contains("pub fn get_posts_collected < C > (& self , conn : & mut C) -> diesel :: QueryResult < smallvec :: SmallVec < [Post ; 8] > >"));
+        assert!(code.contains("posts . filter (user_id . eq (self . id)) . load_iter :: < Post , diesel :: connection :: DefaultLoadingMode > (conn) ? . collect ()"));
+        // The main getter keeps returning a `Vec`.
+        assert!(code.contains("pub fn children < C > (& self , conn : & mut C) -> diesel :: QueryResult < Vec < Post >>"));
+
+        // The collection is filled in the order of `children`.
+        let relation_attrs = RelationAttributes {
+            order_by: Some("created_at".to_string()),
+            ..relation_attrs
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(code.contains("posts . filter (user_id . eq (self . id)) . order (created_at) . load_iter :: < Post , diesel :: connection :: DefaultLoadingMode > (conn) ? . collect ()"));
+    }
+
+    #[test]
+    fn test_join_condition_nested_set_descendants() {
+        // A nested-set tree: the descendants of a category lie within its `lft`/`rgt` bounds.
+        let struct_name = Ident::new("Category", Span::call_site());
+        let relation_attrs = RelationAttributes {
+            child_model: "Category".to_string(),
+            fk: "parent_id".to_string(),
+            relation_type: "one_to_many".to_string(),
+            plural: Some("descendants".to_string()),
+            join_condition: Some(JoinCondition::Between(
+                "lft".to_string(),
+                "lft".to_string(),
+                "rgt".to_string(),
+            )),
+            ..Default::default()
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(code.contains("pub fn get_descendants_matching < C > (& self , conn : & mut C) -> diesel :: QueryResult < Vec < Category >>"));
+        assert!(code.contains("categories . filter (lft . between (& self . lft , & self . rgt)) . load :: < Category > (conn)"));
+        // The `fk` getter still loads the direct children.
+        assert!(code.contains(
+            "categories . filter (parent_id . eq (self . id)) . load :: < Category > (conn)"
+        ));
+
+        let relation_attrs = RelationAttributes {
+            join_condition: Some(JoinCondition::Gt("lft".to_string(), "rgt".to_string())),
+            default_filter: vec![FilterCondition::IsNull("deleted_at".to_string())],
+            ..relation_attrs
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(code.contains("categories . filter (lft . gt (& self . rgt)) . filter (crate :: schema :: categories :: deleted_at . is_null ())"));
+    }
+
+    #[test]
+    fn test_soft_delete_methods() {
+        let struct_name = Ident::new("User", Span::call_site());
+        let relation_attrs = RelationAttributes {
+            soft_delete_column: Some("deleted_at".to_string()),
+            eager_loading: true,
+            ..one_to_many_attrs()
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        // Soft-deleted children are excluded from the later loads.
+        assert!(code.contains("posts . filter (user_id . eq (self . id)) . filter (crate :: schema :: posts :: deleted_at . is_null ()) . load :: < Post > (conn)"));
+        assert!(code.contains(". filter (user_id . eq_any (parent_ids)) . filter (crate :: schema :: posts :: deleted_at . is_null ())"));
+        assert!(code.contains("pub fn soft_delete_posts < C > (& self , conn : & mut C) -> Result < usize , diesel :: result :: Error >"));
+        assert!(code.contains("diesel :: update (posts . filter (user_id . eq (self . id)) . filter (deleted_at . is_null ())) . set (deleted_at . eq (diesel :: dsl :: now)) . execute (conn)"));
+        assert!(code.contains(
+            "diesel :: delete (posts . filter (user_id . eq (self . id))) . execute (conn)"
+        ));
+
+        // An explicit `deleted_at IS NULL` in `default_filter` isn't repeated.
+        let relation_attrs = RelationAttributes {
+            default_filter: vec![FilterCondition::IsNull("deleted_at".to_string())],
+            ..relation_attrs
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(code.contains("posts . filter (user_id . eq (self . id)) . filter (crate :: schema :: posts :: deleted_at . is_null ()) . load"));
+
+        let relation_attrs = RelationAttributes {
+            readonly: true,
+            ..relation_attrs
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(!code.contains("soft_delete_posts"));
+        assert!(!code.contains("hard_delete_posts"));
+    }
+
+    #[test]
+    fn test_sorted_method() {
+        let struct_name = Ident::new("User", Span::call_site());
+        let code = generate_relation_code(&struct_name, &one_to_many_attrs()).to_string();
+        assert!(!code.contains("fn get_posts_sorted"));
+
+        let relation_attrs = RelationAttributes {
+            sorted: true,
+            order_by: Some("created_at".to_string()),
+            ..one_to_many_attrs()
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(code.contains("pub fn get_posts_sorted < C > (& self , conn : & mut C , dir : crate :: SortDir) -> diesel :: QueryResult < Vec < Post >>"));
+        assert!(code
+            .contains("let query = posts . filter (user_id . eq (self . id)) . into_boxed () ;"));
+        // Both directions order the same column, one reversed from the other.
+        assert!(code.contains("crate :: SortDir :: Asc => query . order (created_at . asc ()) , crate :: SortDir :: Desc => query . order (created_at . desc ()) ,"));
+        assert!(code.contains("query . load :: < Post > (conn)"));
+    }
+
+    #[test]
+    fn test_ranked_method() {
+        let struct_name = Ident::new("User", Span::call_site());
+        let code = generate_relation_code(&struct_name, &one_to_many_attrs()).to_string();
+        assert!(!code.contains("fn get_posts_ranked"));
+
+        let relation_attrs = RelationAttributes {
+            ranked: true,
+            order_by: Some("created_at".to_string()),
+            backends: vec!["postgres".to_string(), "sqlite".to_string()],
+            ..one_to_many_attrs()
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert_eq!(code.matches("pub fn get_posts_ranked < C > (& self , conn : & mut C) -> diesel :: QueryResult < Vec < (i64 , Post) >>").count(), 2);
+        // Postgres ranks the rows with ROW_NUMBER over the same order as the query.
+        assert!(code.contains(". order (created_at) . select ((diesel :: dsl :: sql :: < diesel :: sql_types :: BigInt > (\"ROW_NUMBER() OVER (ORDER BY created_at)\") , crate :: schema :: posts :: all_columns)) . load :: < (i64 , Post) > (conn)"));
+        // SQLite numbers the ordered rows from 1 without gaps.
+        assert!(code.contains(". order (created_at) . load :: < Post > (conn) . map (| children | (1 ..) . zip (children) . collect ())"));
+    }
+
+    #[test]
+    fn test_order_by_direction() {
+        let struct_name = Ident::new("User", Span::call_site());
+        let code = generate_relation_code(&struct_name, &one_to_many_attrs()).to_string();
+        assert!(
+            code.contains("posts . filter (user_id . eq (self . id)) . load :: < Post > (conn)")
+        );
+
+        let relation_attrs = RelationAttributes {
+            order_by: Some("created_at".to_string()),
+            order_desc: true,
+            eager_loading: true,
+            paginate: true,
+            ranked: true,
+            backends: vec!["postgres".to_string()],
+            ..one_to_many_attrs()
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(code.contains("posts . filter (user_id . eq (self . id)) . order (created_at . desc ()) . load :: < Post > (conn)"));
+        assert!(code.contains(". order (created_at . desc ()) . load_iter :: < Post , M > (conn)"));
+        assert!(code.contains(
+            ". order (created_at . desc ()) . limit (page . limit) . offset (page . offset)"
+        ));
+        assert!(code.contains("\"ROW_NUMBER() OVER (ORDER BY created_at DESC)\""));
+        assert!(code
+            .contains(". filter (user_id . eq_any (parent_ids)) . order (created_at . desc ())"));
+
+        let code =
+            generate_relation_code(&struct_name, &many_to_many_attrs("Tag", None)).to_string();
+        assert!(code.contains(". filter (id . eq_any (related_ids)) . order (id . asc ())"));
+        let relation_attrs = RelationAttributes {
+            order_by: Some("name".to_string()),
+            ..many_to_many_attrs("Tag", None)
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(code.contains(
+            ". filter (id . eq_any (related_ids)) . order (name) . load :: < Tag > (conn)"
+        ));
+    }
+
+    #[test]
+    fn test_tuple_projection_child() {
+        let struct_name = Ident::new("User", Span::call_site());
+        let relation_attrs = RelationAttributes {
+            child_model: "(i32, String)".to_string(),
+            related_table: Some("posts".to_string()),
+            singular: Some("post_title".to_string()),
+            plural: Some("post_titles".to_string()),
+            columns: Some("id, title".to_string()),
+            eager_loading: true,
+            ..one_to_many_attrs()
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(code.contains("pub fn children < C > (& self , conn : & mut C) -> diesel :: QueryResult < Vec < (i32 , String) >>"));
+        assert!(code.contains(". select ((crate :: schema :: posts :: id , crate :: schema :: posts :: title ,)) . load :: < (i32 , String) > (conn)"));
+        assert!(code.contains("fn load_with_post_titles < C > (parents : Vec < Self > , conn : & mut C) -> diesel :: QueryResult < Vec < (Self , Vec < (i32 , String) >) >>"));
+        assert!(code.contains(". select ((user_id , (crate :: schema :: posts :: id , crate :: schema :: posts :: title ,))) . load :: < (i32 , (i32 , String)) > (conn) ?"));
+        // A projection can't be inserted or updated.
+        assert!(!code.contains("fn add_child"));
+        assert!(!code.contains("fn remove_child"));
+
+        let relation_attrs = RelationAttributes {
+            columns: Some("id, title".to_string()),
+            ..many_to_many_attrs("Tag", None)
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(code.contains(". select ((crate :: schema :: tags :: id , crate :: schema :: tags :: title ,)) . load :: < Tag > (conn)"));
+    }
+
+    #[test]
+    fn test_one_to_many_eager_loading() {
+        let struct_name = Ident::new("User", Span::call_site());
+        let code = generate_relation_code(&struct_name, &one_to_many_attrs()).to_string();
+        assert!(!code.contains("load_with_posts"));
+
+        let relation_attrs = RelationAttributes {
+            eager_loading: true,
+            ..one_to_many_attrs()
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(code.contains("fn load_with_posts < C > (parents : Vec < Self > , conn : & mut C) -> diesel :: QueryResult < Vec < (Self , Vec < Post >) >>"));
+        assert!(code.contains(
+            "Self :: load_with_posts_except (parents , & std :: collections :: HashSet :: new () , conn)"
+        ));
+        assert!(code.contains("fn load_with_posts_except < C > (parents : Vec < Self > , skip_keys : & std :: collections :: HashSet < i32 > , conn : & mut C ,)"));
+        assert!(code.contains(". filter (| parent_id | ! skip_keys . contains (parent_id))"));
+        assert!(code.contains(". select ((user_id , crate :: schema :: posts :: all_columns)) . load :: < (i32 , Post) > (conn) ?"));
+        assert!(code.contains("children . remove (& parent . id) . unwrap_or_default ()"));
+
+        let relation_attrs = RelationAttributes {
+            eager_loading: true,
+            selectable: true,
+            ..one_to_many_attrs()
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(code.contains(
+            ". select ((user_id , < Post as diesel :: SelectableHelper < _ >> :: as_select ()))"
+        ));
+    }
+
+    #[test]
+    fn test_one_to_many_cache_method() {
+        let struct_name = Ident::new("User", Span::call_site());
+        let code = generate_relation_code(&struct_name, &one_to_many_attrs()).to_string();
+        assert!(!code.contains("get_cached_posts"));
+
+        let attrs = RelationAttributes {
+            cache: true,
+            ..one_to_many_attrs()
+        };
+        let code = generate_relation_code(&struct_name, &attrs).to_string();
+        assert!(
+            code.contains("fn get_cached_posts < R , C > (& self , cache : & R , conn : & mut C)")
+        );
+        assert!(code.contains("R : crate :: RelationCache < i32 , Post >"));
+        assert!(code.contains("let parent_key : & i32 = & self . id ;"));
+        assert!(code.contains("cache . get (parent_key , \"User::posts\")"));
+        assert!(code.contains("cache . put (parent_key , \"User::posts\" , & loaded)"));
+
+        // The cache is keyed by the type of the parent key.
+        let attrs = RelationAttributes {
+            cache: true,
+            fk_type: Some("uuid::Uuid".to_string()),
+            ..one_to_many_attrs()
+        };
+        let code = generate_relation_code(&struct_name, &attrs).to_string();
+        assert!(code.contains("R : crate :: RelationCache < uuid :: Uuid , Post >"));
+        assert!(code.contains("let parent_key : & uuid :: Uuid = & self . id ;"));
+    }
+
+    #[test]
+    fn test_many_to_many_related_entities_ordered_by_child_key() {
+        let struct_name = Ident::new("Post", Span::call_site());
+        let code =
+            generate_relation_code(&struct_name, &many_to_many_attrs("Tag", None)).to_string();
+        assert!(code.contains(
+            "tags . filter (id . eq_any (related_ids)) . order (id . asc ()) . load :: < Tag > (conn)"
+        ));
+    }
+
+    #[test]
+    fn test_natural_key_joins() {
+        let struct_name = Ident::new("User", Span::call_site());
+        let relation_attrs = RelationAttributes {
+            child_model: "Profile".to_string(),
+            fk: "user_id".to_string(),
+            relation_type: "one_to_one".to_string(),
+            parent_key: Some("external_id".to_string()),
+            child_key: Some("external_ref".to_string()),
+            ..Default::default()
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(code.contains(
+            "profiles . filter (external_ref . eq (& self . external_id)) . first :: < Profile > (conn) . optional ()"
+        ));
+        assert!(code.contains(". on_conflict (external_ref)"));
+        assert!(!code.contains("user_id"));
+        assert!(!code.contains("self . id"));
+
+        let struct_name = Ident::new("Post", Span::call_site());
+        let relation_attrs = RelationAttributes {
+            child_model: "User".to_string(),
+            fk: "user_id".to_string(),
+            relation_type: "many_to_one".to_string(),
+            parent_key: Some("external_id".to_string()),
+            child_key: Some("author_ref".to_string()),
+            ..Default::default()
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(code.contains(". filter (external_id . eq (& self . author_ref))"));
+        assert!(code.contains(
+            ". filter (crate :: schema :: posts :: author_ref . eq (& self . external_id))"
+        ));
+    }
+
+    #[test]
+    fn test_one_to_many_eager_loading_on_parent_key() {
+        let struct_name = Ident::new("User", Span::call_site());
+        let relation_attrs = RelationAttributes {
+            fk: "user_external_id".to_string(),
+            parent_key: Some("external_id".to_string()),
+            fk_type: Some("String".to_string()),
+            eager_loading: true,
+            ..one_to_many_attrs()
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(code.contains(
+            "posts . filter (user_external_id . eq (& self . external_id)) . load :: < Post > (conn)"
+        ));
+        assert!(code.contains(". map (| parent | parent . external_id . clone ())"));
+        assert!(code.contains("children . remove (& parent . external_id) . unwrap_or_default ()"));
+        assert!(code.contains(". load :: < (String , Post) > (conn) ?"));
+        assert!(code.contains(
+            ". filter (crate :: schema :: posts :: user_external_id . eq (self . external_id . clone ())) . into_boxed ()"
+        ));
+        assert!(!code.contains("parent . id"));
+    }
+
+    #[test]
+    fn test_update_methods() {
+        let struct_name = Ident::new("User", Span::call_site());
+        let code = generate_relation_code(&struct_name, &one_to_many_attrs()).to_string();
+        assert!(!code.contains("fn update_post"));
+
+        let relation_attrs = RelationAttributes {
+            update: true,
+            ..one_to_many_attrs()
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(code.contains("pub fn update_post < C , Changes > (& self , conn : & mut C , child_id : impl Into < i32 > , changes : Changes) -> diesel :: QueryResult < Post >"));
+        assert!(code.contains("Changes : diesel :: query_builder :: AsChangeset < Target = crate :: schema :: posts :: table > ,"));
+        assert!(code.contains("Changes :: Changeset : diesel :: query_builder :: QueryFragment << C as diesel :: Connection > :: Backend > ,"));
+        assert!(code.contains("diesel :: update (posts . filter (id . eq (child_id)) . filter (user_id . eq (self . id))) . set (changes) . get_result :: < Post > (conn)"));
+
+        let relation_attrs = RelationAttributes {
+            readonly: true,
+            ..relation_attrs
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(!code.contains("fn update_post"));
+
+        let relation_attrs = RelationAttributes {
+            child_model: "Profile".to_string(),
+            fk: "user_id".to_string(),
+            relation_type: "one_to_one".to_string(),
+            update: true,
+            ..Default::default()
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(code.contains(
+            "pub fn update_profile < C , Changes > (& self , conn : & mut C , changes : Changes)"
+        ));
+        assert!(code.contains(
+            "diesel :: update (profiles . filter (user_id . eq (self . id))) . set (changes)"
+        ));
+    }
+
+    #[test]
+    fn test_shared_connection_type() {
+        let struct_name = Ident::new("User", Span::call_site());
+        let code = generate_relation_code(&struct_name, &one_to_many_attrs()).to_string();
+        assert!(code.contains("pub fn children < C > (& self , conn : & mut C)"));
+
+        // A mock pool wrapper whose `&self` API relies on interior mutability.
+        let relation_attrs = RelationAttributes {
+            connection_type: Some("crate::db::SharedConn".to_string()),
+            with_parent: true,
+            ..one_to_many_attrs()
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(code.contains("pub fn children < C > (& self , conn : C)"));
+        assert!(code.contains("C : diesel :: connection :: LoadConnection + Copy + std :: ops :: Deref < Target = crate :: db :: SharedConn > ,"));
+        assert!(code.contains("{ let mut conn = conn ; let conn = & mut conn ; use crate :: schema :: posts :: dsl :: * ;"));
+        assert!(code.contains("self . children (* conn)"));
+        assert!(code.contains("(& self , conn : & 'conn mut C)"));
+    }
+
+    #[test]
+    fn test_loaders_filter_without_associations() {
+        // `PostSummary` stands for a view-backed struct deriving only `Queryable`.
+        let struct_name = Ident::new("User", Span::call_site());
+        let relation_attrs = RelationAttributes {
+            child_model: "PostSummary".to_string(),
+            related_table: Some("post_summaries".to_string()),
+            ..one_to_many_attrs()
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(!code.contains("belonging_to"));
+        assert!(code.contains(
+            "post_summaries . filter (user_id . eq (self . id)) . load :: < PostSummary > (conn)"
+        ));
+
+        let relation_attrs = RelationAttributes {
+            relation_type: "one_to_one".to_string(),
+            ..relation_attrs
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(!code.contains("belonging_to"));
+        assert!(code.contains("post_summaries . filter (user_id . eq (self . id)) . first :: < PostSummary > (conn) . optional ()"));
+    }
+
+    #[test]
+    fn test_exists_methods_on_each_backend() {
+        let struct_name = Ident::new("Post", Span::call_site());
+        let backends = vec![
+            "postgres".to_string(),
+            "sqlite".to_string(),
+            "mysql".to_string(),
+        ];
+        let relation_attrs = RelationAttributes {
+            exists: true,
+            backends: backends.clone(),
+            ..one_to_many_attrs()
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        let relation_attrs = RelationAttributes {
+            exists: true,
+            backends,
+            ..many_to_many_attrs("Tag", None)
+        };
+        let many_to_many_code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        for backend in [
+            "diesel :: pg :: Pg",
+            "diesel :: sqlite :: Sqlite",
+            "diesel :: mysql :: Mysql",
+        ] {
+            // Each backend gets the same `SELECT EXISTS (subquery)`, which needs no alias on MySQL.
+            let has_posts = format!("pub fn has_posts < C > (& self , conn : & mut C) -> diesel :: QueryResult < bool > where C : diesel :: connection :: LoadConnection < Backend = {} > , {{ use diesel :: prelude :: * ; diesel :: select (diesel :: dsl :: exists (crate :: schema :: posts :: table . filter (crate :: schema :: posts :: user_id . eq (self . id)))) . get_result :: < bool > (conn) }}", backend);
+            assert!(code.contains(&has_posts), "{}", backend);
+            let has_tags = format!("pub fn has_tags < C > (& self , conn : & mut C) -> diesel :: QueryResult < bool > where C : diesel :: connection :: LoadConnection < Backend = {} > ,", backend);
+            assert!(many_to_many_code.contains(&has_tags), "{}", backend);
+        }
+        assert_eq!(code.matches("pub fn has_posts").count(), 3);
+    }
+
+    #[test]
+    fn test_one_to_one_exists_method() {
+        let struct_name = Ident::new("User", Span::call_site());
+        let relation_attrs = RelationAttributes {
+            child_model: "UserProfile".to_string(),
+            fk: "user_id".to_string(),
+            relation_type: "one_to_one".to_string(),
+            ..Default::default()
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(!code.contains("has_user_profile"));
+
+        let relation_attrs = RelationAttributes {
+            exists: true,
+            ..relation_attrs
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(code.contains(
+            "pub fn has_user_profile < C > (& self , conn : & mut C) -> diesel :: QueryResult < bool >"
+        ));
+        assert!(code.contains("diesel :: select (diesel :: dsl :: exists (user_profiles . filter (user_id . eq (self . id)))) . get_result :: < bool > (conn)"));
+    }
+
+    #[test]
+    fn test_many_to_one_exists_method() {
+        let struct_name = Ident::new("Post", Span::call_site());
+        let relation_attrs = RelationAttributes {
+            child_model: "User".to_string(),
+            fk: "user_id".to_string(),
+            relation_type: "many_to_one".to_string(),
+            ..Default::default()
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(!code.contains("fn has_user"));
+
+        let relation_attrs = RelationAttributes {
+            exists: true,
+            ..relation_attrs
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(code.contains(
+            "pub fn has_user < C > (& self , conn : & mut C) -> diesel :: QueryResult < bool >"
+        ));
+        assert!(code.contains("diesel :: select (diesel :: dsl :: exists (crate :: schema :: users :: table . filter (crate :: schema :: users :: id . eq (self . user_id)) ,)) . get_result :: < bool > (conn)"));
+    }
+
+    #[test]
+    fn test_one_to_one_strict_one() {
+        let struct_name = Ident::new("User", Span::call_site());
+        let relation_attrs = RelationAttributes {
+            child_model: "Profile".to_string(),
+            fk: "user_id".to_string(),
+            relation_type: "one_to_one".to_string(),
+            ..Default::default()
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(code.contains("first :: < Profile > (conn) . optional ()"));
+        assert!(!code.contains("UniqueViolation"));
+
+        let relation_attrs = RelationAttributes {
+            strict_one: true,
+            ..relation_attrs
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(code.contains("limit (2) . load :: < Profile > (conn) ?"));
+        assert!(code.contains("diesel :: result :: DatabaseErrorKind :: UniqueViolation"));
+        assert!(code.contains(
+            "\"more than one `profiles` row is related to this `User` in a one_to_one relation\""
+        ));
+        assert!(!code.contains("first :: < Profile >"));
+    }
+
+    #[test]
+    fn test_many_to_many_related_table_override() {
+        let struct_name = Ident::new("Post", Span::call_site());
+        let relation_attrs = many_to_many_attrs("Status", None);
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(code.contains("use crate :: schema :: statuses :: dsl :: *"));
+
+        let relation_attrs = RelationAttributes {
+            related_table: Some("status".to_string()),
+            ..many_to_many_attrs("Status", None)
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(!code.contains("schema :: statuses"));
+        assert!(code.contains("use crate :: schema :: status :: dsl :: *"));
+        assert!(code.contains("status . filter (id . eq_any (related_ids))"));
+        assert!(code.contains("crate :: schema :: status :: BoxedQuery"));
+        assert!(code.contains("fn add_status < C >"));
+
+        let items = generate_support_items(&struct_name, &relation_attrs).to_string();
+        assert!(items.contains("crate :: schema :: status :: table"));
+    }
+
+    #[test]
+    fn test_one_to_one_error_type_method() {
+        let struct_name = Ident::new("User", Span::call_site());
+        let relation_attrs = RelationAttributes {
+            child_model: "UserProfile".to_string(),
+            fk: "user_id".to_string(),
+            relation_type: "one_to_one".to_string(),
+            ..Default::default()
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(!code.contains("get_user_profile_or"));
+
+        let relation_attrs = RelationAttributes {
+            error_type: Some("crate::error::AppError".to_string()),
+            ..relation_attrs
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(code.contains("fn get_user_profile_or < C > (& self , conn : & mut C , not_found : crate :: error :: AppError) -> Result < UserProfile , crate :: error :: AppError >"));
+        assert!(code.contains("crate :: error :: AppError : From < diesel :: result :: Error >"));
+        assert!(code.contains("Err (diesel :: result :: Error :: NotFound) => Err (not_found) ,"));
+        assert!(code.contains("Err (error) => Err (error . into ()) ,"));
+    }
+
+    #[test]
+    fn test_many_to_many_with_usage() {
+        let struct_name = Ident::new("Post", Span::call_site());
+        let code =
+            generate_relation_code(&struct_name, &many_to_many_attrs("Tag", None)).to_string();
+        assert!(!code.contains("get_tags_with_usage"));
+
+        let relation_attrs = RelationAttributes {
+            with_usage: true,
+            ..many_to_many_attrs("Tag", None)
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(code.contains("fn get_tags_with_usage < C > (& self , conn : & mut C) -> diesel :: QueryResult < Vec < (Tag , i64) >>"));
+        assert!(code.contains(". filter (join_dsl :: data_id . eq_any (& related_ids)) . group_by (join_dsl :: data_id) . select ((join_dsl :: data_id , diesel :: dsl :: count_star ())) . load :: < (i32 , i64) > (conn) ?"));
+        assert!(code.contains("usage . get (& child . id) . copied () . unwrap_or (0)"));
+    }
+
+    #[test]
+    fn test_many_to_many_same_query_assertion() {
+        let struct_name = Ident::new("Post", Span::call_site());
+
+        let items =
+            generate_support_items(&struct_name, &many_to_many_attrs("Tag", None)).to_string();
+        assert!(
+            items.contains("message = \"`tags` and `post_data` can't appear in the same query\"")
+        );
+        assert!(
+            items.contains("add `diesel::allow_tables_to_appear_in_same_query!(tags, post_data);`")
+        );
+        assert!(items.contains("assert_allowed_in_same_query :: < crate :: schema :: post_data :: table , crate :: schema :: tags :: table >"));
+
+        let items = generate_support_items(&struct_name, &one_to_many_attrs()).to_string();
+        assert!(!items.contains("assert_allowed_in_same_query"));
+    }
+
+    #[test]
+    fn test_generic_connection_bounds() {
+        let struct_name = Ident::new("User", Span::call_site());
+        let relation_attrs = RelationAttributes {
+            backends: vec!["sqlite".to_string()],
+            ..one_to_many_attrs()
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        // Loading needs `LoadConnection`, which any connection of the backend implements.
+        assert!(code.contains("pub fn children < C > (& self , conn : & mut C) -> diesel :: QueryResult < Vec < Post >> where C : diesel :: connection :: LoadConnection < Backend = diesel :: sqlite :: Sqlite > ,"));
+        assert!(!code.contains("diesel :: Connection <"));
+        // No concrete connection type is named, so pooled connections are accepted as is.
+        assert!(!code.contains("PgConnection"));
+        assert!(!code.contains("SqliteConnection"));
+        // The row cursor borrows the connection for `'conn`, so the loading mode must outlive it.
+        assert!(code.contains("C : diesel :: connection :: LoadConnection < M > + diesel :: connection :: LoadConnection < Backend = diesel :: sqlite :: Sqlite > , M : 'conn ,"));
+        assert!(code.contains("use crate :: schema :: posts :: dsl :: * ; use diesel :: prelude :: * ; let child_id : i32 = child_id . into () ;"));
+    }
+
+    #[test]
+    fn test_pooled_methods() {
+        let struct_name = Ident::new("User", Span::call_site());
+        let code = generate_relation_code(&struct_name, &one_to_many_attrs()).to_string();
+        assert!(!code.contains("_pooled"));
+
+        let relation_attrs = RelationAttributes {
+            pool: true,
+            backends: vec!["postgres".to_string()],
+            ..one_to_many_attrs()
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(code.contains("pub fn children_pooled < M > (& self , pool : & diesel :: r2d2 :: Pool < M >) -> Result < Vec < Post > , Box < dyn std :: error :: Error + Send + Sync > > where M : diesel :: r2d2 :: ManageConnection , M :: Connection : diesel :: connection :: LoadConnection < Backend = diesel :: pg :: Pg > ,"));
+        // The connection goes back to the pool when `conn` is dropped.
+        assert!(
+            code.contains("let mut conn = pool . get () ? ; Ok (self . children (& mut * conn) ?)")
+        );
+
+        let relation_attrs = RelationAttributes {
+            pool: true,
+            pool_error: Some("crate::error::AppError".to_string()),
+            ..many_to_many_attrs("Tag", None)
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(code.contains("pub fn related_entities_pooled < M > (& self , pool : & diesel :: r2d2 :: Pool < M >) -> Result < Vec < Tag > , crate :: error :: AppError >"));
+        assert!(code.contains("crate :: error :: AppError : From < diesel :: r2d2 :: PoolError > + From < diesel :: result :: Error > ,"));
+
+        let relation_attrs = RelationAttributes {
+            child_model: "UserProfile".to_string(),
+            fk: "user_id".to_string(),
+            relation_type: "one_to_one".to_string(),
+            pool: true,
+            ..Default::default()
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(code.contains("pub fn get_related_entity_pooled < M > (& self , pool : & diesel :: r2d2 :: Pool < M >) -> Result < Option < UserProfile > ,"));
+    }
+
+    #[test]
+    fn test_conn_type_assertion() {
+        let struct_name = Ident::new("User", Span::call_site());
+        let relation_attrs = RelationAttributes {
+            backends: vec!["postgres".to_string()],
+            conn_type: Some("crate::db::LoggingConnection".to_string()),
+            ..one_to_many_attrs()
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(code.contains("message = \"`crate::db::LoggingConnection` can't be used as the connection of this relation\" , note = \"the generated methods require `C: diesel::connection::LoadConnection<Backend = diesel::pg::Pg>`\""));
+        assert!(code.contains("impl < C : diesel :: connection :: LoadConnection < Backend = diesel :: pg :: Pg > > RelationConnection for C { }"));
+        assert!(code.contains("assert_relation_connection :: < crate :: db :: LoggingConnection >"));
+        // The signatures stay generic over the connection.
+        assert!(code.contains("pub fn children < C > (& self , conn : & mut C)"));
+
+        let code = generate_relation_code(&struct_name, &one_to_many_attrs()).to_string();
+        assert!(!code.contains("RelationConnection"));
+    }
+
+    #[test]
+    fn test_any_backend_relation() {
+        let struct_name = Ident::new("User", Span::call_site());
+        let relation_attrs = RelationAttributes {
+            backends: vec!["any".to_string()],
+            count: true,
+            ..one_to_many_attrs()
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        // The boxed query is built for the backend of the connection.
+        assert!(code.contains("pub fn children < C > (& self , conn : & mut C) -> diesel :: QueryResult < Vec < Post >> where C : diesel :: connection :: LoadConnection ,"));
+        assert!(code.contains(": diesel :: query_dsl :: methods :: BoxedDsl < 'static , < C as diesel :: Connection > :: Backend , Output = crate :: schema :: posts :: BoxedQuery < 'static , < C as diesel :: Connection > :: Backend > > ,"));
+        assert!(code.contains("self . query_posts :: < < C as diesel :: Connection > :: Backend > () . load :: < Post > (conn)"));
+        assert!(code.contains("self . query_posts :: < < C as diesel :: Connection > :: Backend > () . count () . get_result :: < i64 > (conn)"));
+        assert!(code.contains("pub fn query_posts < DB > (& self)"));
+        assert!(!code.contains("diesel :: sqlite"));
+        assert!(!code.contains("load_with_posts"));
+
+        // Many-to-many relations are ordered by the child primary key, as with a concrete backend.
+        let relation_attrs = RelationAttributes {
+            backends: vec!["any".to_string()],
+            ..many_to_many_attrs("Group", None)
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(code.contains("pub fn related_entities < C >"));
+        assert!(code.contains("crate :: schema :: groups :: BoxedQuery < 'static , < C as diesel :: Connection > :: Backend > : diesel :: query_dsl :: methods :: OrderDsl < crate :: schema :: groups :: id , Output = crate :: schema :: groups :: BoxedQuery < 'static , < C as diesel :: Connection > :: Backend > > ,"));
+        assert!(
+            code.contains(". order (crate :: schema :: groups :: id) . load :: < Group > (conn)")
+        );
+    }
+
+    #[test]
+    fn test_self_referential_relation() {
+        let struct_name = Ident::new("Employee", Span::call_site());
+        let relation_attrs = RelationAttributes {
+            child_model: "Employee".to_string(),
+            fk: "manager_id".to_string(),
+            relation_type: "many_to_one".to_string(),
+            inverse: Some("reports".to_string()),
+            ..Default::default()
+        };
+
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(code.contains("pub fn get_manager < C > (& self , conn : & mut C) -> diesel :: QueryResult < Option < Self >>"));
+        assert!(code.contains(
+            "let parent_key : Option < i32 > = self . manager_id . clone () . into () ;"
+        ));
+        assert!(code.contains("Some (parent_key) => crate :: schema :: employees :: table . filter (crate :: schema :: employees :: id . eq (parent_key)) . first :: < Self > (conn) . optional () , None => Ok (None) ,"));
+        assert!(code.contains("pub fn get_reports < C > (& self , conn : & mut C) -> diesel :: QueryResult < Vec < Self >>"));
+        assert!(code.contains("crate :: schema :: employees :: table . filter (crate :: schema :: employees :: manager_id . eq (self . id)) . order (crate :: schema :: employees :: id) . load :: < Self > (conn)"));
+        assert!(!code.contains("ParentModel"));
+
+        // Without `inverse`, only the parent getter is generated.
+        let relation_attrs = RelationAttributes {
+            inverse: None,
+            singular: Some("boss".to_string()),
+            ..relation_attrs
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(code.contains("pub fn get_boss < C >"));
+        assert!(!code.contains("get_reports"));
+    }
+
+    #[test]
+    fn test_self_referential_rejects_parent_helpers() {
+        let struct_name = Ident::new("Employee", Span::call_site());
+        let relation_attrs = RelationAttributes {
+            child_model: "Employee".to_string(),
+            fk: "manager_id".to_string(),
+            relation_type: "many_to_one".to_string(),
+            ..Default::default()
+        };
+        assert!(check_self_referential(&struct_name, &relation_attrs).is_ok());
+
+        for (name, relation_attrs) in [
+            (
+                "eager_loading",
+                RelationAttributes {
+                    eager_loading: true,
+                    ..Default::default()
+                },
+            ),
+            (
+                "exists",
+                RelationAttributes {
+                    exists: true,
+                    ..Default::default()
+                },
+            ),
+            (
+                "context_errors",
+                RelationAttributes {
+                    context_errors: true,
+                    ..Default::default()
+                },
+            ),
+            (
+                "pool",
+                RelationAttributes {
+                    pool: true,
+                    ..Default::default()
+                },
+            ),
+        ] {
+            let relation_attrs = RelationAttributes {
+                child_model: "Employee".to_string(),
+                fk: "manager_id".to_string(),
+                relation_type: "many_to_one".to_string(),
+                ..relation_attrs
+            };
+            let error = check_self_referential(&struct_name, &relation_attrs).unwrap_err();
+            assert_eq!(
+                error.to_string(),
+                format!(
+                    "Attribute '{}' is not supported by self-referential relations",
+                    name
+                )
+            );
+        }
+
+        // `exists` enabled by `full` is reported on `full`.
+        let relation_attrs = RelationAttributes {
+            exists: true,
+            spans: vec![("full".to_string(), Span::call_site())],
+            ..relation_attrs
+        };
+        let error = check_self_referential(&struct_name, &relation_attrs).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Attribute 'full' is not supported by self-referential relations"
+        );
+
+        // Relations to another model keep them.
+        let relation_attrs = RelationAttributes {
+            child_model: "Department".to_string(),
+            fk: "department_id".to_string(),
+            ..relation_attrs
+        };
+        assert!(check_self_referential(&struct_name, &relation_attrs).is_ok());
+    }
+
+    #[test]
+    fn test_polymorphic_relation() {
+        let struct_name = Ident::new("Post", Span::call_site());
+        let relation_attrs = RelationAttributes {
+            child_model: "Comment".to_string(),
+            fk: "commentable_id".to_string(),
+            relation_type: "one_to_many".to_string(),
+            type_column: Some("commentable_type".to_string()),
+            type_value: Some("Post".to_string()),
+            eager_loading: true,
+            ..Default::default()
+        };
+
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        let discriminator =
+            ". filter (crate :: schema :: comments :: commentable_type . eq (\"Post\"))";
+        assert!(code.contains(&format!(
+            "comments . filter (commentable_id . eq (self . id)) {} . load :: < Comment > (conn)",
+            discriminator
+        )));
+        assert!(code.contains(&format!(
+            ". filter (commentable_id . eq_any (parent_ids)) {}",
+            discriminator
+        )));
+        assert!(code.contains(&format!(
+            "diesel :: delete (comments . filter (id . eq (child_id) . and (commentable_id . eq (self . id))) {}) . execute (conn)",
+            discriminator
+        )));
+        assert!(code.contains("diesel :: dsl :: Eq < crate :: schema :: comments :: commentable_type , & 'static str > ,"));
+        assert!(code.contains(&format!(
+            ". filter (crate :: schema :: comments :: commentable_id . eq (self . id)) {} . into_boxed ()",
+            discriminator
+        )));
+
+        let code = generate_relation_code(&struct_name, &one_to_many_attrs()).to_string();
+        assert!(!code.contains("commentable_type"));
+    }
+
+    #[test]
+    fn test_has_many_through_methods() {
+        let struct_name = Ident::new("User", Span::call_site());
+        let relation_attrs = RelationAttributes {
+            child_model: "Comment".to_string(),
+            fk: "post_id".to_string(),
+            relation_type: "has_many_through".to_string(),
+            through: Some("posts".to_string()),
+            through_fk: Some("user_id".to_string()),
+            count: true,
+            ..Default::default()
+        };
+
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(code.contains("pub fn get_comments < C > (& self , conn : & mut C) -> diesel :: QueryResult < Vec < Comment >>"));
+        assert!(code.contains("comments . filter (post_id . eq_any (through_dsl :: posts . filter (through_dsl :: user_id . eq (parent_id)) . select (through_dsl :: id))) . order (id . asc ()) . load :: < Comment > (conn)"));
+        assert!(code.contains("pub fn query_comments < DB > (& self) -> crate :: schema :: comments :: BoxedQuery < 'static , DB >"));
+        assert!(code.contains(". filter (crate :: schema :: posts :: user_id . eq (self . id . clone ())) . select (crate :: schema :: posts :: id) ,) ,) . into_boxed ()"));
+        assert!(code.contains("crate :: schema :: comments :: post_id . eq_any (crate :: schema :: posts :: table . filter (crate :: schema :: posts :: user_id . eq (& self . id)) . select (crate :: schema :: posts :: id) ,) ,) . count ()"));
+
+        let items = generate_support_items(&struct_name, &relation_attrs).to_string();
+        assert!(items.contains("assert_allowed_in_same_query :: < crate :: schema :: posts :: table , crate :: schema :: comments :: table >"));
+        assert!(
+            items.contains("assert_join_column :: < crate :: schema :: posts :: user_id > () ;")
+        );
+    }
+
+    #[test]
+    fn test_many_to_many_mutator_verbs() {
+        let struct_name = Ident::new("Post", Span::call_site());
+        let relation_attrs = RelationAttributes {
+            link_verb: Some("attach".to_string()),
+            unlink_verb: Some("detach".to_string()),
+            ..many_to_many_attrs("Tag", None)
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(code.contains("pub fn attach_tag < C >"));
+        assert!(code.contains("pub fn detach_tag < C >"));
+        assert!(!code.contains("fn add_tag"));
+        assert!(!code.contains("fn remove_tag"));
+    }
+
+    #[test]
+    fn test_many_to_many_join_columns_assertion() {
+        let struct_name = Ident::new("Post", Span::call_site());
+
+        let items =
+            generate_support_items(&struct_name, &many_to_many_attrs("Tag", None)).to_string();
+        assert!(items.contains("fn assert_join_column < T : diesel :: Column < Table = crate :: schema :: post_data :: table >> () { }"));
+        assert!(items.contains("assert_join_column :: < crate :: schema :: post_data :: post_id > () ; assert_join_column :: < crate :: schema :: post_data :: data_id > () ;"));
+
+        let items = generate_support_items(&struct_name, &one_to_many_attrs()).to_string();
+        assert!(!items.contains("assert_join_column"));
+    }
+
+    #[test]
+    fn test_backend_list_generates_cfg_gated_impls() {
+        let struct_name = Ident::new("User", Span::call_site());
+        let relation_attrs = RelationAttributes {
+            backends: vec!["postgres".to_string(), "sqlite".to_string()],
+            ..one_to_many_attrs()
+        };
+
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(code.contains("# [cfg (all (feature = \"postgres\" , not (any ())))] impl User"));
+        assert!(code.contains(
+            "# [cfg (all (feature = \"sqlite\" , not (any (feature = \"postgres\"))))] impl User"
+        ));
+        assert!(code
+            .contains("diesel :: connection :: LoadConnection < Backend = diesel :: pg :: Pg >"));
+        assert!(code.contains(
+            "diesel :: connection :: LoadConnection < Backend = diesel :: sqlite :: Sqlite >"
+        ));
+
+        // A single backend doesn't depend on a feature of the model crate.
+        let relation_attrs = RelationAttributes {
+            backends: vec!["postgres".to_string()],
+            ..one_to_many_attrs()
+        };
+        let code = generate_relation_code(&struct_name, &relation_attrs).to_string();
+        assert!(!code.contains("cfg"));
+        assert!(code
+            .contains("diesel :: connection :: LoadConnection < Backend = diesel :: pg :: Pg >"));
+    }
+
+    #[test]
+    fn test_many_to_many_singular_method_names() {
+        let struct_name = Ident::new("Post", Span::call_site());
+
+        let code =
+            generate_relation_code(&struct_name, &many_to_many_attrs("Data", None)).to_string();
+        assert!(code.contains("fn add_data <"));
+        assert!(code.contains("fn remove_data <"));
+        assert!(!code.contains("datum"));
+
+        let code = generate_relation_code(
+            &struct_name,
+            &many_to_many_attrs("Data", Some("data_point")),
+        )
+        .to_string();
+        assert!(code.contains("fn add_data_point <"));
+        assert!(code.contains("fn remove_data_point <"));
+    }
+
+    #[test]
+    fn test_private_struct_restricts_visibility() {
+        let item_struct: ItemStruct = syn::parse_quote! { struct User { id: i32 } };
+        let struct_name = &item_struct.ident;
+        let relation_attrs = RelationAttributes {
+            visibility: struct_visibility(&item_struct),
+            wrapper: true,
+            query_fn: true,
+            ..one_to_many_attrs()
+        };
+        let code = generate_relation_code(struct_name, &relation_attrs).to_string();
+        assert!(!code.contains("pub "));
+        assert!(code.contains("fn children < C > (& self , conn : & mut C)"));
+        let items = generate_support_items(struct_name, &relation_attrs).to_string();
+        assert!(items.contains("struct UserWithPosts"));
+        assert!(!items.contains("pub "));
+        // Trait methods keep the trait's visibility.
+        assert!(items.contains("fn deref (& self) -> & Self :: Target"));
+
+        let item_struct: ItemStruct = syn::parse_quote! { pub(crate) struct User { id: i32 } };
+        assert_eq!(
+            struct_visibility(&item_struct).as_deref(),
+            Some("pub (crate)")
+        );
+        let relation_attrs = RelationAttributes {
+            visibility: struct_visibility(&item_struct),
+            ..one_to_many_attrs()
+        };
+        let code = generate_relation_code(struct_name, &relation_attrs).to_string();
+        assert!(code.contains("pub (crate) fn children < C >"));
+
+        let item_struct: ItemStruct = syn::parse_quote! { pub struct User { id: i32 } };
+        assert_eq!(struct_visibility(&item_struct), None);
     }
 }
@@ -0,0 +1,71 @@
+// Path: src/utils/grouping.rs
+
+use proc_macro2::{Ident, TokenStream};
+use quote::{quote, ToTokens};
+
+// Emits the grouping shared by the generated loaders: declares `groups`, a
+// `HashMap<key_type, Vec<child_type>>`, then, for every item of `children` bound by the pattern
+// `binding`, pushes `value` under the key computed by `key`. A proc-macro crate can only export
+// macros, so the helper lives at the codegen level; lib.rs documents its runtime counterpart.
+pub fn group_children_by(
+    groups: &Ident,
+    key_type: &impl ToTokens,
+    child_type: &impl ToTokens,
+    binding: TokenStream,
+    children: TokenStream,
+    key: TokenStream,
+    value: TokenStream,
+) -> TokenStream {
+    quote! {
+        let mut #groups: std::collections::HashMap<#key_type, Vec<#child_type>> =
+            std::collections::HashMap::new();
+        for #binding in #children {
+            #groups.entry(#key).or_default().push(#value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proc_macro2::Span;
+
+    #[test]
+    fn test_group_children_by_custom_key() {
+        let grouping = group_children_by(
+            &Ident::new("by_author", Span::call_site()),
+            &quote! { String },
+            &quote! { Post },
+            quote! { child },
+            quote! { posts },
+            quote! { child.author.to_lowercase() },
+            quote! { child },
+        );
+        assert_eq!(
+            grouping.to_string(),
+            quote! {
+                let mut by_author: std::collections::HashMap<String, Vec<Post>> =
+                    std::collections::HashMap::new();
+                for child in posts {
+                    by_author.entry(child.author.to_lowercase()).or_default().push(child);
+                }
+            }
+            .to_string()
+        );
+    }
+    #[test]
+    fn test_group_children_by_destructured_rows() {
+        let grouping = group_children_by(
+            &Ident::new("children", Span::call_site()),
+            &quote! { i32 },
+            &quote! { Post },
+            quote! { (parent_id, post) },
+            quote! { rows },
+            quote! { parent_id },
+            quote! { post },
+        );
+        assert!(grouping
+            .to_string()
+            .contains("for (parent_id , post) in rows { children . entry (parent_id) . or_default () . push (post) ; }"));
+    }
+}
@@ -1,2 +1,3 @@
 // Importer tous les modules ici.
+pub mod grouping; // Importation du module grouping
 pub mod parser; // Importation du module parser
@@ -1,21 +1,357 @@
 // Path: src/utils/parser.rs
 
-use proc_macro2::Span;
+use proc_macro2::{Delimiter, Span, TokenStream, TokenTree};
+use syn::spanned::Spanned;
 use syn::{AttributeArgs, Error, Lit, Meta, NestedMeta, Result};
 
 #[derive(Debug, Default)]
 pub struct ParsedAttrs {
     pub relation_type: Option<String>,
     pub child: Option<String>,
-    pub fk: Option<String>,         // Used for one_to_many et one_to_one
-    pub join_table: Option<String>, // Used for many_to_many
-    pub fk_parent: Option<String>,  // Foreign key for the parent in the join table for many_to_many
-    pub fk_child: Option<String>,   // Foreign key for the child in the join table for many_to_many
+    pub fk: Option<String>,              // Used for one_to_many et one_to_one
+    pub join_table: Option<String>,      // Used for many_to_many
+    pub fk_parent: Option<String>, // Foreign key for the parent in the join table for many_to_many
+    pub fk_child: Option<String>,  // Foreign key for the child in the join table for many_to_many
+    pub singular: Option<String>,  // Singular name used by the many_to_many add_/remove_ methods
+    pub plural: Option<String>,    // Plural name used by the collection getters (get_<plural>_...)
+    pub backend: Option<String>,   // Backends, one impl each, cfg-gated if several
+    pub order_by: Option<String>, // Column used to order the generated queries, optionally followed by asc/desc
+    pub recent: bool,             // Generates get_recent_<plural>, requires order_by
+    pub aggregates: Option<String>, // Aggregates computed by load_with_<singular>_stats, e.g. "count, sum(views)"
+    pub serialize_as: Option<String>, // DTO type loaded by get_<dto plural>, must implement From<child>
+    pub wrapper: Option<bool>, // Generates a <Parent>With<Plural> wrapper memoizing the children
+    pub fk_parent_type: Option<String>, // Rust type of fk_parent in the join table, defaults to i32
+    pub fk_child_type: Option<String>, // Rust type of fk_child in the join table, defaults to i32
+    pub cache: bool, // Generates get_cached_<plural>, backed by the user's crate::RelationCache
+    pub strict_one: bool, // get_related_entity fails when several rows are related
+    pub with_parent: bool, // Generates get_<plural>_with_parent, pairing each child with a clone of the parent
+    pub default_filter: Option<String>, // Conditions scoping the loaders, e.g. status = "active" AND deleted_at IS NULL
+    pub paginate: bool, // Generates get_<plural>_page (one_to_many, many_to_many), taking the crate::Page defined by the user
+    pub query_fn: bool, // Generates the free function <plural>_of(parent) returning query_<plural>()
+    pub count: Option<bool>, // Generates count_<plural>, enabled by full unless set
+    pub exists: Option<bool>, // Generates has_<plural>, enabled by full unless set
+    pub full: bool,     // Enables count, exists and eager_loading unless they are set
+    pub related_table: Option<String>, // Table of the child model, when it is not its pluralized snake_case name
+    pub selectable: bool, // Loads children through Child::as_select(), requires #[derive(Selectable)]
+    pub error_type: Option<String>, // Error type returned by get_<singular>_or, must implement From<diesel::result::Error>
+    pub schema_path: Option<String>, // Module holding the diesel table! definitions, defaults to crate::schema
+    pub with_usage: bool,            // Generates get_<plural>_with_usage for many_to_many
+    pub eager_loading: Option<bool>, // Generates the batch loaders load_with_<plural> and load_with_<plural>_except, enabled by full unless set
+    pub no_inflection: bool, // Disables the inflected names, requires singular, plural and related_table
+    pub readonly: bool, // Suppresses the generated mutators, e.g. for a child table that is a view
+    pub debug_sql: bool, // Generates <plural>_query_debug returning the SQL and binds of the children query
+    pub nonempty: bool,  // Generates get_<plural>_nonempty returning the first child and the rest
+    pub fk_type: Option<String>, // Rust type of fk in one_to_many relations, defaults to i32
+    pub group_by: Option<String>, // Child column generating get_<plural>_grouped_by_<column>
+    pub group_by_type: Option<String>, // Rust type of the group_by field, defaults to i32
+    pub parent_key: Option<String>, // Parent column of one_to_one, many_to_one and one_to_many joins, defaults to id
+    pub child_key: Option<String>, // Child column of one_to_one and many_to_one joins, defaults to fk
+    pub eager_loading_stream: bool, // Generates the async batch loader load_with_<plural>_stream
+    pub shared_children: bool,     // Generates get_<plural>_shared returning Arc-wrapped children
+    pub link_verb: Option<String>, // Prefix of the many_to_many link mutator, defaults to add
+    pub unlink_verb: Option<String>, // Prefix of the many_to_many unlink mutator, defaults to remove
+    pub latest: Option<String>,      // Child column ordering get_latest_<plural>, newest first
+    pub latest_limit: Option<i64>,   // Number of children loaded by get_latest_<plural>
+    pub update: bool, // Generates update_<singular> applying an AsChangeset to a related row
+    pub connection_type: Option<String>, // Optional custom connection type, taken by shared reference
+    pub filtered: bool, // Generates get_<plural>_filtered taking a diesel predicate
+    pub columns: Option<String>, // Comma separated child columns selected by the loaders, e.g. "id, title"
+    pub ranked: bool,            // Generates get_<plural>_ranked, requires order_by
+    pub sorted: bool, // Generates get_<plural>_sorted taking the crate::SortDir defined by the user, requires order_by
+    pub load_into: bool, // Generates get_<plural>_into filling a caller-provided Vec
+    pub visibility: Option<String>, // Visibility of the generated items, defaults to the struct visibility
+    pub optional: bool, // Children may reference missing parents, skips the orphan debug_assert of load_parents_with_children
+    pub collect_into: Option<String>, // Collection type loaded by get_<plural>_collected, must implement FromIterator<child>
+    pub soft_delete_column: Option<String>, // Nullable timestamp column marking soft-deleted children, one_to_many only
+    pub fk_column: Option<String>, // SQL column of the foreign key when the child field is renamed, defaults to fk
+    pub fk_field: Option<String>, // Rust field of the foreign key on a many_to_one child, defaults to fk
+    pub async_: bool, // Generates the count_/has_ methods as async fns over diesel_async, requires the async feature
+    pub join_condition: Option<String>, // Comparison between a child column and parent fields, generates get_<plural>_matching
+    pub streaming: bool, // Generates the async get_<plural>_chunks stream, requires async
+    pub context_errors: bool, // Generates <getter>_with_context returning the user-defined crate::RelationError
+    pub cursor_column: Option<String>, // Child column generating get_<plural>_after keyset pagination
+    pub cursor_type: Option<String>,   // Rust type of the cursor_column field, defaults to i32
+    pub where_closure: bool, // Generates get_<plural>_where taking a closure refining the boxed query
+    pub ids: bool,           // Generates get_<singular>_ids selecting only the child ids
+    pub ids_type: Option<String>, // Rust type of the child id loaded by get_<singular>_ids in one_to_many relations, defaults to i32
+    pub latest_by: Option<String>, // Child column generating get_latest_<singular> and get_first_<singular>, one_to_many only
+    pub chunk_size: Option<usize>, // Number of parent keys per batch loader query, splitting large batches
+    pub nested: Option<String>, // Plural of a one_to_many relation of the child, loaded one level further
+    pub nested_child: Option<String>, // Grandchild type loaded by nested
+    pub through: Option<String>, // Intermediate table of has_many_through relations
+    pub through_fk: Option<String>, // Column of the through table referencing the parent
+    pub polymorphic: bool, // Scopes a one_to_many relation to the rows whose type_column names the parent
+    pub type_column: Option<String>, // Discriminator column naming the parent model of polymorphic relations
+    pub id_column: Option<String>,   // Child column holding the parent key of polymorphic relations
+    pub type_value: Option<String>, // Discriminator value of the parent, defaults to the struct name
+    pub inverse: Option<String>, // Name of the children getter of self-referential many_to_one relations
+    pub conn_type: Option<String>, // Optional connection type the relation is used with, checked against the generated bound
+    pub pool: bool, // Generate pooled variants of the main getter, checking out an r2d2 connection
+    pub pool_error: Option<String>, // Optional error type of the pooled variants
+    pub spans: Vec<(String, Span)>, // Names and spans of the attributes set, for the checks made after parsing
+}
+
+// An aggregate computed over the children of a one_to_many relation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Aggregate {
+    Count,
+    Sum(String),
+}
+
+impl Aggregate {
+    // Name of the field holding this aggregate in the generated stats struct.
+    pub fn field_name(&self) -> String {
+        match self {
+            Aggregate::Count => "count".to_string(),
+            Aggregate::Sum(column) => format!("total_{}", column),
+        }
+    }
+}
+
+// Parses an `aggregates` list such as `count, sum(views)`.
+pub fn parse_aggregates(aggregates: &str) -> Result<Vec<Aggregate>> {
+    aggregates
+        .split(',')
+        .map(str::trim)
+        .map(|aggregate| {
+            if aggregate == "count" {
+                return Ok(Aggregate::Count);
+            }
+            match aggregate
+                .strip_prefix("sum(")
+                .and_then(|rest| rest.strip_suffix(')'))
+                .map(str::trim)
+            {
+                Some(column) if !column.is_empty() => Ok(Aggregate::Sum(column.to_string())),
+                _ => Err(Error::new(
+                    Span::call_site(),
+                    format!(
+                        "Unsupported aggregate '{}', expected 'count' or 'sum(<column>)'",
+                        aggregate
+                    ),
+                )),
+            }
+        })
+        .collect()
+}
+
+// A condition of a `default_filter` on a child column. Values keep the source text of their literal.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FilterCondition {
+    Eq(String, String),
+    NotEq(String, String),
+    Lt(String, String),
+    Gt(String, String),
+    IsNull(String),
+    IsNotNull(String),
+    In(String, Vec<String>),
+}
+
+// Parses a `default_filter` such as `status = "active" AND deleted_at IS NULL`.
+pub fn parse_default_filter(filter: &str) -> Result<Vec<FilterCondition>> {
+    let tokens: TokenStream = filter.parse().map_err(|_| {
+        Error::new(
+            Span::call_site(),
+            format!(
+                "Failed to parse default_filter '{}', string literals must use double quotes",
+                filter
+            ),
+        )
+    })?;
+
+    let mut conditions = Vec::new();
+    let mut condition = Vec::new();
+    for token in tokens {
+        match &token {
+            TokenTree::Ident(ident) if ident.to_string().eq_ignore_ascii_case("and") => {
+                conditions.push(parse_filter_condition(&condition)?);
+                condition.clear();
+            }
+            _ => condition.push(token),
+        }
+    }
+    conditions.push(parse_filter_condition(&condition)?);
+    Ok(conditions)
+}
+
+// Parses a single `default_filter` condition, i.e. the tokens between two `AND`.
+fn parse_filter_condition(tokens: &[TokenTree]) -> Result<FilterCondition> {
+    let text = tokens
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(" ");
+    let unsupported = || {
+        Error::new(
+            Span::call_site(),
+            format!(
+                "Unsupported default_filter condition '{}', expected `<column> <op> <literal>` with `=`, `!=`, `<` or `>`, \
+                 `<column> IS [NOT] NULL` or `<column> IN (<literal>, ...)`",
+                text
+            ),
+        )
+    };
+
+    let (column, rest) = match tokens.split_first() {
+        Some((TokenTree::Ident(column), rest)) => (column.to_string(), rest),
+        _ => return Err(unsupported()),
+    };
+    let keyword = |token: &TokenTree, expected: &str| matches!(token, TokenTree::Ident(ident) if ident.to_string().eq_ignore_ascii_case(expected));
+    let punct = |token: &TokenTree, expected: char| matches!(token, TokenTree::Punct(punct) if punct.as_char() == expected);
+
+    match rest {
+        [is, null] if keyword(is, "is") && keyword(null, "null") => {
+            Ok(FilterCondition::IsNull(column))
+        }
+        [is, not, null] if keyword(is, "is") && keyword(not, "not") && keyword(null, "null") => {
+            Ok(FilterCondition::IsNotNull(column))
+        }
+        [in_, TokenTree::Group(group)]
+            if keyword(in_, "in") && group.delimiter() == Delimiter::Parenthesis =>
+        {
+            let values = group.stream().into_iter().collect::<Vec<_>>();
+            let values = values
+                .split(|token| punct(token, ','))
+                .filter(|value| !value.is_empty())
+                .map(|value| filter_value(value).ok_or_else(unsupported))
+                .collect::<Result<Vec<_>>>()?;
+            if values.is_empty() {
+                return Err(unsupported());
+            }
+            Ok(FilterCondition::In(column, values))
+        }
+        [bang, eq, value @ ..] if punct(bang, '!') && punct(eq, '=') => Ok(FilterCondition::NotEq(
+            column,
+            filter_value(value).ok_or_else(unsupported)?,
+        )),
+        [op, value @ ..] if punct(op, '=') => Ok(FilterCondition::Eq(
+            column,
+            filter_value(value).ok_or_else(unsupported)?,
+        )),
+        [op, value @ ..] if punct(op, '<') => Ok(FilterCondition::Lt(
+            column,
+            filter_value(value).ok_or_else(unsupported)?,
+        )),
+        [op, value @ ..] if punct(op, '>') => Ok(FilterCondition::Gt(
+            column,
+            filter_value(value).ok_or_else(unsupported)?,
+        )),
+        _ => Err(unsupported()),
+    }
+}
+
+// Returns the source text of a `default_filter` value: a literal, optionally negated, or a boolean.
+fn filter_value(tokens: &[TokenTree]) -> Option<String> {
+    match tokens {
+        [TokenTree::Literal(literal)] => Some(literal.to_string()),
+        [TokenTree::Punct(minus), TokenTree::Literal(literal)] if minus.as_char() == '-' => {
+            Some(format!("-{}", literal))
+        }
+        [TokenTree::Ident(ident)] if ident == "true" || ident == "false" => Some(ident.to_string()),
+        _ => None,
+    }
+}
+
+// A `join_condition` comparing a child column with fields of the parent, e.g. the nested-set
+// `child.lft BETWEEN self.lft AND self.rgt`. The first string is the child column.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum JoinCondition {
+    Lt(String, String),
+    Le(String, String),
+    Gt(String, String),
+    Ge(String, String),
+    Between(String, String, String),
+}
+
+// Parses an `order_by` such as `created_at` or `created_at desc` into the column and whether the
+// order is descending.
+pub fn parse_order_by(order_by: &str) -> Result<(String, bool)> {
+    let (column, descending) = match order_by.split_whitespace().collect::<Vec<_>>()[..] {
+        [column] => (column, false),
+        [column, direction] if direction.eq_ignore_ascii_case("asc") => (column, false),
+        [column, direction] if direction.eq_ignore_ascii_case("desc") => (column, true),
+        _ => ("", false),
+    };
+    if syn::parse_str::<syn::Ident>(column).is_err() {
+        return Err(Error::new(
+            Span::call_site(),
+            format!(
+                "Unsupported order_by '{}', expected `<column>`, `<column> asc` or `<column> desc`",
+                order_by
+            ),
+        ));
+    }
+    Ok((column.to_string(), descending))
+}
+
+// Parses a `join_condition` such as `child.starts_at < self.ends_at`.
+pub fn parse_join_condition(condition: &str) -> Result<JoinCondition> {
+    let unsupported = || {
+        Error::new(
+            Span::call_site(),
+            format!(
+                "Unsupported join_condition '{}', expected `child.<column> <op> self.<field>` with `<`, `<=`, `>` or `>=`, \
+                 or `child.<column> BETWEEN self.<field> AND self.<field>`",
+                condition
+            ),
+        )
+    };
+    let tokens: Vec<TokenTree> = condition
+        .parse::<TokenStream>()
+        .map_err(|_| unsupported())?
+        .into_iter()
+        .collect();
+
+    // `<side>.<name>`, where `side` is `child` or `self`.
+    let field = |tokens: &[TokenTree], side: &str| match tokens {
+        [TokenTree::Ident(prefix), TokenTree::Punct(dot), TokenTree::Ident(name)]
+            if prefix == side && dot.as_char() == '.' =>
+        {
+            Some(name.to_string())
+        }
+        _ => None,
+    };
+    let keyword = |token: &TokenTree, expected: &str| matches!(token, TokenTree::Ident(ident) if ident.to_string().eq_ignore_ascii_case(expected));
+    let punct = |token: &TokenTree, expected: char| matches!(token, TokenTree::Punct(punct) if punct.as_char() == expected);
+
+    if tokens.len() < 3 {
+        return Err(unsupported());
+    }
+    let column = field(&tokens[..3], "child").ok_or_else(unsupported)?;
+    match &tokens[3..] {
+        [between, rest @ ..]
+            if keyword(between, "between") && rest.len() == 7 && keyword(&rest[3], "and") =>
+        {
+            let low = field(&rest[..3], "self").ok_or_else(unsupported)?;
+            let high = field(&rest[4..], "self").ok_or_else(unsupported)?;
+            Ok(JoinCondition::Between(column, low, high))
+        }
+        [op, eq, rest @ ..] if punct(op, '<') && punct(eq, '=') => Ok(JoinCondition::Le(
+            column,
+            field(rest, "self").ok_or_else(unsupported)?,
+        )),
+        [op, eq, rest @ ..] if punct(op, '>') && punct(eq, '=') => Ok(JoinCondition::Ge(
+            column,
+            field(rest, "self").ok_or_else(unsupported)?,
+        )),
+        [op, rest @ ..] if punct(op, '<') => Ok(JoinCondition::Lt(
+            column,
+            field(rest, "self").ok_or_else(unsupported)?,
+        )),
+        [op, rest @ ..] if punct(op, '>') => Ok(JoinCondition::Gt(
+            column,
+            field(rest, "self").ok_or_else(unsupported)?,
+        )),
+        _ => Err(unsupported()),
+    }
 }
 
 // Parses the attributes passed to the `relation` attribute macro.
 pub fn parse_attributes(attrs: AttributeArgs) -> Result<ParsedAttrs> {
     let mut parsed_attrs = ParsedAttrs::default();
+    // Names and spans of the attributes set, for the checks depending on which ones are present
+    // and to point their errors at the offending attribute.
+    let mut names: Vec<(String, Span)> = Vec::new();
 
     for attr in attrs {
         match attr {
@@ -25,6 +361,7 @@ pub fn parse_attributes(attrs: AttributeArgs) -> Result<ParsedAttrs> {
                     .get_ident()
                     .ok_or_else(|| Error::new(Span::call_site(), "Expected named value"))?
                     .to_string();
+                names.push((ident.clone(), nv.span()));
                 match ident.as_str() {
                     "relation_type" => {
                         if let Lit::Str(s) = &nv.lit {
@@ -56,10 +393,390 @@ pub fn parse_attributes(attrs: AttributeArgs) -> Result<ParsedAttrs> {
                             parsed_attrs.fk_child = Some(s.value())
                         }
                     }
+                    "singular" => {
+                        if let Lit::Str(s) = &nv.lit {
+                            parsed_attrs.singular = Some(s.value())
+                        }
+                    }
+                    "plural" => {
+                        if let Lit::Str(s) = &nv.lit {
+                            parsed_attrs.plural = Some(s.value())
+                        }
+                    }
+                    "backend" => {
+                        if let Lit::Str(s) = &nv.lit {
+                            parsed_attrs.backend = Some(s.value())
+                        }
+                    }
+                    "order_by" => {
+                        if let Lit::Str(s) = &nv.lit {
+                            parsed_attrs.order_by = Some(s.value())
+                        }
+                    }
+                    "recent" => {
+                        if let Lit::Bool(b) = &nv.lit {
+                            parsed_attrs.recent = b.value
+                        }
+                    }
+                    "aggregates" => {
+                        if let Lit::Str(s) = &nv.lit {
+                            parsed_attrs.aggregates = Some(s.value())
+                        }
+                    }
+                    "serialize_as" => {
+                        if let Lit::Str(s) = &nv.lit {
+                            parsed_attrs.serialize_as = Some(s.value())
+                        }
+                    }
+                    "wrapper" => {
+                        if let Lit::Bool(b) = &nv.lit {
+                            parsed_attrs.wrapper = Some(b.value)
+                        }
+                    }
+                    "fk_parent_type" => {
+                        if let Lit::Str(s) = &nv.lit {
+                            parsed_attrs.fk_parent_type = Some(s.value())
+                        }
+                    }
+                    "fk_child_type" => {
+                        if let Lit::Str(s) = &nv.lit {
+                            parsed_attrs.fk_child_type = Some(s.value())
+                        }
+                    }
+                    "cache" => {
+                        if let Lit::Bool(b) = &nv.lit {
+                            parsed_attrs.cache = b.value
+                        }
+                    }
+                    "strict_one" => {
+                        if let Lit::Bool(b) = &nv.lit {
+                            parsed_attrs.strict_one = b.value
+                        }
+                    }
+                    "with_parent" => {
+                        if let Lit::Bool(b) = &nv.lit {
+                            parsed_attrs.with_parent = b.value
+                        }
+                    }
+                    "default_filter" => {
+                        if let Lit::Str(s) = &nv.lit {
+                            parsed_attrs.default_filter = Some(s.value())
+                        }
+                    }
+                    "paginate" => {
+                        if let Lit::Bool(b) = &nv.lit {
+                            parsed_attrs.paginate = b.value
+                        }
+                    }
+                    "query_fn" => {
+                        if let Lit::Bool(b) = &nv.lit {
+                            parsed_attrs.query_fn = b.value
+                        }
+                    }
+                    "count" => {
+                        if let Lit::Bool(b) = &nv.lit {
+                            parsed_attrs.count = Some(b.value)
+                        }
+                    }
+                    "exists" => {
+                        if let Lit::Bool(b) = &nv.lit {
+                            parsed_attrs.exists = Some(b.value)
+                        }
+                    }
+                    "full" => {
+                        if let Lit::Bool(b) = &nv.lit {
+                            parsed_attrs.full = b.value
+                        }
+                    }
+                    "related_table" => {
+                        if let Lit::Str(s) = &nv.lit {
+                            parsed_attrs.related_table = Some(s.value())
+                        }
+                    }
+                    "selectable" => {
+                        if let Lit::Bool(b) = &nv.lit {
+                            parsed_attrs.selectable = b.value
+                        }
+                    }
+                    "error_type" => {
+                        if let Lit::Str(s) = &nv.lit {
+                            parsed_attrs.error_type = Some(s.value())
+                        }
+                    }
+                    "schema_path" => {
+                        if let Lit::Str(s) = &nv.lit {
+                            parsed_attrs.schema_path = Some(s.value())
+                        }
+                    }
+                    "with_usage" => {
+                        if let Lit::Bool(b) = &nv.lit {
+                            parsed_attrs.with_usage = b.value
+                        }
+                    }
+                    "eager_loading" => {
+                        if let Lit::Bool(b) = &nv.lit {
+                            parsed_attrs.eager_loading = Some(b.value)
+                        }
+                    }
+                    "no_inflection" => {
+                        if let Lit::Bool(b) = &nv.lit {
+                            parsed_attrs.no_inflection = b.value
+                        }
+                    }
+                    "readonly" => {
+                        if let Lit::Bool(b) = &nv.lit {
+                            parsed_attrs.readonly = b.value
+                        }
+                    }
+                    "debug_sql" => {
+                        if let Lit::Bool(b) = &nv.lit {
+                            parsed_attrs.debug_sql = b.value
+                        }
+                    }
+                    "nonempty" => {
+                        if let Lit::Bool(b) = &nv.lit {
+                            parsed_attrs.nonempty = b.value
+                        }
+                    }
+                    "fk_type" => {
+                        if let Lit::Str(s) = &nv.lit {
+                            parsed_attrs.fk_type = Some(s.value())
+                        }
+                    }
+                    "group_by" => {
+                        if let Lit::Str(s) = &nv.lit {
+                            parsed_attrs.group_by = Some(s.value())
+                        }
+                    }
+                    "group_by_type" => {
+                        if let Lit::Str(s) = &nv.lit {
+                            parsed_attrs.group_by_type = Some(s.value())
+                        }
+                    }
+                    "parent_key" => {
+                        if let Lit::Str(s) = &nv.lit {
+                            parsed_attrs.parent_key = Some(s.value())
+                        }
+                    }
+                    "child_key" => {
+                        if let Lit::Str(s) = &nv.lit {
+                            parsed_attrs.child_key = Some(s.value())
+                        }
+                    }
+                    "eager_loading_stream" => {
+                        if let Lit::Bool(b) = &nv.lit {
+                            parsed_attrs.eager_loading_stream = b.value
+                        }
+                    }
+                    "shared_children" => {
+                        if let Lit::Bool(b) = &nv.lit {
+                            parsed_attrs.shared_children = b.value
+                        }
+                    }
+                    "link_verb" => {
+                        if let Lit::Str(s) = &nv.lit {
+                            parsed_attrs.link_verb = Some(s.value())
+                        }
+                    }
+                    "unlink_verb" => {
+                        if let Lit::Str(s) = &nv.lit {
+                            parsed_attrs.unlink_verb = Some(s.value())
+                        }
+                    }
+                    "latest" => {
+                        if let Lit::Str(s) = &nv.lit {
+                            parsed_attrs.latest = Some(s.value())
+                        }
+                    }
+                    "latest_limit" => {
+                        if let Lit::Int(i) = &nv.lit {
+                            parsed_attrs.latest_limit = Some(i.base10_parse()?)
+                        }
+                    }
+                    "update" => {
+                        if let Lit::Bool(b) = &nv.lit {
+                            parsed_attrs.update = b.value
+                        }
+                    }
+                    "connection_type" => {
+                        if let Lit::Str(s) = &nv.lit {
+                            parsed_attrs.connection_type = Some(s.value())
+                        }
+                    }
+                    "filtered" => {
+                        if let Lit::Bool(b) = &nv.lit {
+                            parsed_attrs.filtered = b.value
+                        }
+                    }
+                    "columns" => {
+                        if let Lit::Str(s) = &nv.lit {
+                            parsed_attrs.columns = Some(s.value())
+                        }
+                    }
+                    "ranked" => {
+                        if let Lit::Bool(b) = &nv.lit {
+                            parsed_attrs.ranked = b.value
+                        }
+                    }
+                    "sorted" => {
+                        if let Lit::Bool(b) = &nv.lit {
+                            parsed_attrs.sorted = b.value
+                        }
+                    }
+                    "load_into" => {
+                        if let Lit::Bool(b) = &nv.lit {
+                            parsed_attrs.load_into = b.value
+                        }
+                    }
+                    "visibility" => {
+                        if let Lit::Str(s) = &nv.lit {
+                            parsed_attrs.visibility = Some(s.value())
+                        }
+                    }
+                    "optional" => {
+                        if let Lit::Bool(b) = &nv.lit {
+                            parsed_attrs.optional = b.value
+                        }
+                    }
+                    "collect_into" => {
+                        if let Lit::Str(s) = &nv.lit {
+                            parsed_attrs.collect_into = Some(s.value())
+                        }
+                    }
+                    "soft_delete_column" => {
+                        if let Lit::Str(s) = &nv.lit {
+                            parsed_attrs.soft_delete_column = Some(s.value())
+                        }
+                    }
+                    "fk_column" => {
+                        if let Lit::Str(s) = &nv.lit {
+                            parsed_attrs.fk_column = Some(s.value())
+                        }
+                    }
+                    "fk_field" => {
+                        if let Lit::Str(s) = &nv.lit {
+                            parsed_attrs.fk_field = Some(s.value())
+                        }
+                    }
+                    "async" => {
+                        if let Lit::Bool(b) = &nv.lit {
+                            parsed_attrs.async_ = b.value
+                        }
+                    }
+                    "join_condition" => {
+                        if let Lit::Str(s) = &nv.lit {
+                            parsed_attrs.join_condition = Some(s.value())
+                        }
+                    }
+                    "streaming" => {
+                        if let Lit::Bool(b) = &nv.lit {
+                            parsed_attrs.streaming = b.value
+                        }
+                    }
+                    "context_errors" => {
+                        if let Lit::Bool(b) = &nv.lit {
+                            parsed_attrs.context_errors = b.value
+                        }
+                    }
+                    "cursor_column" => {
+                        if let Lit::Str(s) = &nv.lit {
+                            parsed_attrs.cursor_column = Some(s.value())
+                        }
+                    }
+                    "cursor_type" => {
+                        if let Lit::Str(s) = &nv.lit {
+                            parsed_attrs.cursor_type = Some(s.value())
+                        }
+                    }
+                    "where_closure" => {
+                        if let Lit::Bool(b) = &nv.lit {
+                            parsed_attrs.where_closure = b.value
+                        }
+                    }
+                    "ids" => {
+                        if let Lit::Bool(b) = &nv.lit {
+                            parsed_attrs.ids = b.value
+                        }
+                    }
+                    "ids_type" => {
+                        if let Lit::Str(s) = &nv.lit {
+                            parsed_attrs.ids_type = Some(s.value())
+                        }
+                    }
+                    "latest_by" => {
+                        if let Lit::Str(s) = &nv.lit {
+                            parsed_attrs.latest_by = Some(s.value())
+                        }
+                    }
+                    "chunk_size" => {
+                        if let Lit::Int(i) = &nv.lit {
+                            parsed_attrs.chunk_size = Some(i.base10_parse()?)
+                        }
+                    }
+                    "nested" => {
+                        if let Lit::Str(s) = &nv.lit {
+                            parsed_attrs.nested = Some(s.value())
+                        }
+                    }
+                    "nested_child" => {
+                        if let Lit::Str(s) = &nv.lit {
+                            parsed_attrs.nested_child = Some(s.value())
+                        }
+                    }
+                    "through" => {
+                        if let Lit::Str(s) = &nv.lit {
+                            parsed_attrs.through = Some(s.value())
+                        }
+                    }
+                    "through_fk" => {
+                        if let Lit::Str(s) = &nv.lit {
+                            parsed_attrs.through_fk = Some(s.value())
+                        }
+                    }
+                    "polymorphic" => {
+                        if let Lit::Bool(b) = &nv.lit {
+                            parsed_attrs.polymorphic = b.value
+                        }
+                    }
+                    "type_column" => {
+                        if let Lit::Str(s) = &nv.lit {
+                            parsed_attrs.type_column = Some(s.value())
+                        }
+                    }
+                    "id_column" => {
+                        if let Lit::Str(s) = &nv.lit {
+                            parsed_attrs.id_column = Some(s.value())
+                        }
+                    }
+                    "type_value" => {
+                        if let Lit::Str(s) = &nv.lit {
+                            parsed_attrs.type_value = Some(s.value())
+                        }
+                    }
+                    "inverse" => {
+                        if let Lit::Str(s) = &nv.lit {
+                            parsed_attrs.inverse = Some(s.value())
+                        }
+                    }
+                    "conn_type" => {
+                        if let Lit::Str(s) = &nv.lit {
+                            parsed_attrs.conn_type = Some(s.value())
+                        }
+                    }
+                    "pool" => {
+                        if let Lit::Bool(b) = &nv.lit {
+                            parsed_attrs.pool = b.value
+                        }
+                    }
+                    "pool_error" => {
+                        if let Lit::Str(s) = &nv.lit {
+                            parsed_attrs.pool_error = Some(s.value())
+                        }
+                    }
                     _ => {
                         return Err(Error::new(
                             Span::call_site(),
-                            &format!("Unknown attribute '{}'", ident),
+                            format!("Unknown attribute '{}'", ident),
                         ))
                     }
                 }
@@ -75,16 +792,670 @@ pub fn parse_attributes(attrs: AttributeArgs) -> Result<ParsedAttrs> {
         ));
     }
 
-    match parsed_attrs.relation_type.as_deref() {
-        Some("one_to_many") | Some("one_to_one") => {
-            if parsed_attrs.child.is_none() || parsed_attrs.fk.is_none() {
-                return Err(Error::new(Span::call_site(), "Attributes 'child' and 'fk' are required for 'one_to_many' and 'one_to_one' relations"));
-            }
+    // `any` replaces the backend list: the generated methods are generic over the backend, which only
+    // the boxed relation query supports, so the attributes generating other queries are rejected.
+    if parsed_attrs.backend.as_deref().map(str::trim) == Some("any") {
+        if !matches!(
+            parsed_attrs.relation_type.as_deref(),
+            Some("one_to_many" | "many_to_many" | "has_many_through")
+        ) {
+            return Err(Error::new(
+                Span::call_site(),
+                "Backend 'any' is only supported by one_to_many, many_to_many and has_many_through relations",
+            ));
         }
-        Some("many_to_one") => {
-            if parsed_attrs.child.is_none() {
-                return Err(Error::new(
-                    Span::call_site(),
+        const ANY_BACKEND_ATTRIBUTES: &[&str] = &[
+            "relation_type",
+            "child",
+            "fk",
+            "fk_column",
+            "fk_type",
+            "parent_key",
+            "join_table",
+            "fk_parent",
+            "fk_child",
+            "fk_parent_type",
+            "fk_child_type",
+            "through",
+            "through_fk",
+            "singular",
+            "plural",
+            "related_table",
+            "schema_path",
+            "no_inflection",
+            "visibility",
+            "connection_type",
+            "conn_type",
+            "backend",
+            "order_by",
+            "count",
+            "query_fn",
+            "filtered",
+            "where_closure",
+        ];
+        if let Some((name, span)) = names
+            .iter()
+            .find(|(name, _)| !ANY_BACKEND_ATTRIBUTES.contains(&name.as_str()))
+        {
+            return Err(Error::new(
+                *span,
+                format!("Attribute '{}' is not supported with backend 'any'", name),
+            ));
+        }
+    } else if let Some(backend) = &parsed_attrs.backend {
+        let backends: Vec<&str> = backend.split(',').map(str::trim).collect();
+        for (index, name) in backends.iter().enumerate() {
+            if !matches!(*name, "postgres" | "sqlite" | "mysql") {
+                return Err(Error::new(
+                    Span::call_site(),
+                    format!(
+                        "Unsupported backend '{}', expected 'postgres', 'sqlite', 'mysql' or 'any'",
+                        name
+                    ),
+                ));
+            }
+            // The implementation of a repeated backend would be gated out by its first occurrence.
+            if backends[..index].contains(name) {
+                return Err(Error::new(
+                    Span::call_site(),
+                    format!("Backend '{}' is listed more than once", name),
+                ));
+            }
+        }
+    }
+
+    if let Some(child) = &parsed_attrs.child {
+        match syn::parse_str::<syn::Type>(child) {
+            Ok(syn::Type::Path(_)) => {}
+            // A projection tuple has no name to infer the table and method names from.
+            Ok(syn::Type::Tuple(_)) => {
+                if parsed_attrs.columns.is_none()
+                    || parsed_attrs.related_table.is_none()
+                    || parsed_attrs.singular.is_none()
+                    || parsed_attrs.plural.is_none()
+                {
+                    return Err(Error::new(
+                        Span::call_site(),
+                        "Attributes 'columns', 'related_table', 'singular' and 'plural' are required when 'child' is a tuple",
+                    ));
+                }
+            }
+            _ => {
+                return Err(Error::new(
+                    Span::call_site(),
+                    format!(
+                        "Attribute 'child' must be a type path or a tuple, got '{}'",
+                        child
+                    ),
+                ))
+            }
+        }
+    }
+
+    if let Some(schema_path) = &parsed_attrs.schema_path {
+        syn::parse_str::<syn::Path>(schema_path).map_err(|_| {
+            Error::new(
+                Span::call_site(),
+                format!(
+                    "Attribute 'schema_path' must be a module path, got '{}'",
+                    schema_path
+                ),
+            )
+        })?;
+    }
+
+    // The pooled variants call the getter with `&mut` the connection checked out of the pool.
+    if parsed_attrs.pool && parsed_attrs.connection_type.is_some() {
+        return Err(Error::new(
+            Span::call_site(),
+            "Attribute 'pool' is not supported with 'connection_type'",
+        ));
+    }
+
+    if let Some(pool_error) = &parsed_attrs.pool_error {
+        if !parsed_attrs.pool {
+            return Err(Error::new(
+                Span::call_site(),
+                "Attribute 'pool_error' requires 'pool = true'",
+            ));
+        }
+        syn::parse_str::<syn::Type>(pool_error).map_err(|_| {
+            Error::new(
+                Span::call_site(),
+                format!(
+                    "Attribute 'pool_error' must be a type, got '{}'",
+                    pool_error
+                ),
+            )
+        })?;
+    }
+
+    if let Some(conn_type) = &parsed_attrs.conn_type {
+        syn::parse_str::<syn::Type>(conn_type).map_err(|_| {
+            Error::new(
+                Span::call_site(),
+                format!("Attribute 'conn_type' must be a type, got '{}'", conn_type),
+            )
+        })?;
+        if parsed_attrs.connection_type.is_some() {
+            return Err(Error::new(
+                Span::call_site(),
+                "Attributes 'conn_type' and 'connection_type' are mutually exclusive",
+            ));
+        }
+    }
+
+    if let Some(collect_into) = &parsed_attrs.collect_into {
+        syn::parse_str::<syn::Type>(collect_into).map_err(|_| {
+            Error::new(
+                Span::call_site(),
+                format!(
+                    "Attribute 'collect_into' must be a type, got '{}'",
+                    collect_into
+                ),
+            )
+        })?;
+    }
+
+    if let Some(visibility) = &parsed_attrs.visibility {
+        syn::parse_str::<syn::Visibility>(visibility).map_err(|_| {
+            Error::new(
+                Span::call_site(),
+                format!(
+                    "Attribute 'visibility' must be a visibility such as 'pub(crate)', got '{}'",
+                    visibility
+                ),
+            )
+        })?;
+    }
+
+    // `related_table` is appended to `schema_path`, so it must be a relative path of identifiers.
+    if let Some(related_table) = &parsed_attrs.related_table {
+        if related_table
+            .split("::")
+            .any(|segment| syn::parse_str::<syn::Ident>(segment).is_err())
+        {
+            return Err(Error::new(
+                Span::call_site(),
+                format!(
+                    "Attribute 'related_table' must be a table name or a relative module path, got '{}'",
+                    related_table
+                ),
+            ));
+        }
+    }
+
+    if let Some(serialize_as) = &parsed_attrs.serialize_as {
+        syn::parse_str::<syn::Path>(serialize_as).map_err(|_| {
+            Error::new(
+                Span::call_site(),
+                format!(
+                    "Attribute 'serialize_as' must be a type path, got '{}'",
+                    serialize_as
+                ),
+            )
+        })?;
+    }
+
+    if let Some(error_type) = &parsed_attrs.error_type {
+        syn::parse_str::<syn::Type>(error_type).map_err(|_| {
+            Error::new(
+                Span::call_site(),
+                format!(
+                    "Attribute 'error_type' must be a type, got '{}'",
+                    error_type
+                ),
+            )
+        })?;
+    }
+
+    for key_type in [
+        &parsed_attrs.fk_type,
+        &parsed_attrs.fk_parent_type,
+        &parsed_attrs.fk_child_type,
+        &parsed_attrs.group_by_type,
+        &parsed_attrs.cursor_type,
+        &parsed_attrs.ids_type,
+    ]
+    .into_iter()
+    .flatten()
+    {
+        syn::parse_str::<syn::Type>(key_type).map_err(|_| {
+            Error::new(
+                Span::call_site(),
+                format!("Invalid key type '{}'", key_type),
+            )
+        })?;
+    }
+
+    if let Some(aggregates) = &parsed_attrs.aggregates {
+        parse_aggregates(aggregates)?;
+    }
+    if let Some(default_filter) = &parsed_attrs.default_filter {
+        parse_default_filter(default_filter)?;
+    }
+    if let Some(order_by) = &parsed_attrs.order_by {
+        parse_order_by(order_by)?;
+    }
+
+    // Only these relation types have batch loaders, other relation types would get misleading code.
+    for (name, enabled, relation_types) in [
+        (
+            "eager_loading",
+            parsed_attrs.eager_loading == Some(true),
+            &["one_to_many", "many_to_one"][..],
+        ),
+        (
+            "eager_loading_stream",
+            parsed_attrs.eager_loading_stream,
+            &["one_to_many"][..],
+        ),
+        (
+            "chunk_size",
+            parsed_attrs.chunk_size.is_some(),
+            &["one_to_many", "many_to_one"][..],
+        ),
+    ] {
+        let supported = parsed_attrs
+            .relation_type
+            .as_deref()
+            .is_some_and(|relation_type| relation_types.contains(&relation_type));
+        if enabled && !supported {
+            let span = names
+                .iter()
+                .find(|(attribute, _)| attribute == name)
+                .map_or_else(Span::call_site, |(_, span)| *span);
+            return Err(Error::new(
+                span,
+                format!(
+                    "Attribute '{}' is only supported by {} relations, not {}",
+                    name,
+                    relation_types.join(" and "),
+                    parsed_attrs
+                        .relation_type
+                        .as_deref()
+                        .unwrap_or("<missing relation_type>")
+                ),
+            ));
+        }
+    }
+
+    if parsed_attrs.chunk_size == Some(0) {
+        return Err(Error::new(
+            Span::call_site(),
+            "Attribute 'chunk_size' must be greater than 0",
+        ));
+    }
+
+    // Without the `async` feature the stream loader isn't generated, so asking for it must fail loudly.
+    if parsed_attrs.eager_loading_stream && !cfg!(feature = "async") {
+        return Err(Error::new(
+            Span::call_site(),
+            "Attribute 'eager_loading_stream' requires the 'async' feature of diesel_linker",
+        ));
+    }
+    if parsed_attrs.async_ && !cfg!(feature = "async") {
+        return Err(Error::new(
+            Span::call_site(),
+            "Attribute 'async' requires the 'async' feature of diesel_linker",
+        ));
+    }
+    if parsed_attrs.streaming
+        && (!parsed_attrs.async_ || parsed_attrs.relation_type.as_deref() != Some("one_to_many"))
+    {
+        return Err(Error::new(
+            Span::call_site(),
+            "Attribute 'streaming' requires 'async' and is only supported by one_to_many relations",
+        ));
+    }
+
+    // The debug method renders the children query, which needs a concrete backend to compile.
+    if parsed_attrs.debug_sql {
+        if parsed_attrs.relation_type.as_deref() != Some("one_to_many") {
+            return Err(Error::new(
+                Span::call_site(),
+                "Attribute 'debug_sql' is only supported by one_to_many relations",
+            ));
+        }
+        if parsed_attrs.backend.is_none() {
+            return Err(Error::new(
+                Span::call_site(),
+                "Attribute 'backend' is required when 'debug_sql' is enabled",
+            ));
+        }
+    }
+
+    // The verbs become identifier prefixes, and only many_to_many relations have named mutators.
+    for verb in [&parsed_attrs.link_verb, &parsed_attrs.unlink_verb]
+        .into_iter()
+        .flatten()
+    {
+        if parsed_attrs.relation_type.as_deref() != Some("many_to_many") {
+            return Err(Error::new(
+                Span::call_site(),
+                "Attributes 'link_verb' and 'unlink_verb' are only supported by many_to_many relations",
+            ));
+        }
+        syn::parse_str::<syn::Ident>(verb).map_err(|_| {
+            Error::new(
+                Span::call_site(),
+                format!("Invalid mutator verb '{}'", verb),
+            )
+        })?;
+    }
+
+    // Many-to-many relations join through a join table, and one_to_many relations name their child column with `fk`.
+    if parsed_attrs.parent_key.is_some()
+        && !matches!(
+            parsed_attrs.relation_type.as_deref(),
+            Some("one_to_one" | "many_to_one" | "one_to_many")
+        )
+    {
+        return Err(Error::new(
+            Span::call_site(),
+            "Attribute 'parent_key' is only supported by one_to_one, many_to_one and one_to_many relations",
+        ));
+    }
+    if parsed_attrs.child_key.is_some()
+        && !matches!(
+            parsed_attrs.relation_type.as_deref(),
+            Some("one_to_one" | "many_to_one")
+        )
+    {
+        return Err(Error::new(
+            Span::call_site(),
+            "Attribute 'child_key' is only supported by one_to_one and many_to_one relations",
+        ));
+    }
+    if parsed_attrs.fk_column.is_some()
+        && !matches!(
+            parsed_attrs.relation_type.as_deref(),
+            Some("one_to_many" | "one_to_one")
+        )
+    {
+        return Err(Error::new(
+            Span::call_site(),
+            "Attribute 'fk_column' is only supported by one_to_many and one_to_one relations",
+        ));
+    }
+    if parsed_attrs.fk_field.is_some()
+        && parsed_attrs.relation_type.as_deref() != Some("many_to_one")
+    {
+        return Err(Error::new(
+            Span::call_site(),
+            "Attribute 'fk_field' is only supported by many_to_one relations",
+        ));
+    }
+    if let Some(join_condition) = &parsed_attrs.join_condition {
+        if parsed_attrs.relation_type.as_deref() != Some("one_to_many") {
+            return Err(Error::new(
+                Span::call_site(),
+                "Attribute 'join_condition' is only supported by one_to_many relations",
+            ));
+        }
+        parse_join_condition(join_condition)?;
+    }
+    if let Some(soft_delete_column) = &parsed_attrs.soft_delete_column {
+        if parsed_attrs.relation_type.as_deref() != Some("one_to_many") {
+            return Err(Error::new(
+                Span::call_site(),
+                "Attribute 'soft_delete_column' is only supported by one_to_many relations",
+            ));
+        }
+        if syn::parse_str::<syn::Ident>(soft_delete_column).is_err() {
+            return Err(Error::new(
+                Span::call_site(),
+                format!(
+                    "Attribute 'soft_delete_column' must be a column name, got '{}'",
+                    soft_delete_column
+                ),
+            ));
+        }
+    }
+    if parsed_attrs.ids
+        && !matches!(
+            parsed_attrs.relation_type.as_deref(),
+            Some("one_to_many" | "many_to_many")
+        )
+    {
+        return Err(Error::new(
+            Span::call_site(),
+            "Attribute 'ids' is only supported by one_to_many and many_to_many relations",
+        ));
+    }
+    if let Some(latest_by) = &parsed_attrs.latest_by {
+        if parsed_attrs.relation_type.as_deref() != Some("one_to_many") {
+            return Err(Error::new(
+                Span::call_site(),
+                "Attribute 'latest_by' is only supported by one_to_many relations",
+            ));
+        }
+        if syn::parse_str::<syn::Ident>(latest_by).is_err() {
+            return Err(Error::new(
+                Span::call_site(),
+                format!(
+                    "Attribute 'latest_by' must be a column name, got '{}'",
+                    latest_by
+                ),
+            ));
+        }
+    }
+    if let Some(inverse) = &parsed_attrs.inverse {
+        if parsed_attrs.relation_type.as_deref() != Some("many_to_one") {
+            return Err(Error::new(
+                Span::call_site(),
+                "Attribute 'inverse' is only supported by many_to_one relations",
+            ));
+        }
+        if syn::parse_str::<syn::Ident>(inverse).is_err() {
+            return Err(Error::new(
+                Span::call_site(),
+                format!(
+                    "Attribute 'inverse' must be a plural name such as 'reports', got '{}'",
+                    inverse
+                ),
+            ));
+        }
+    }
+    // A polymorphic relation is a one_to_many relation scoped by a discriminator, keyed by `id_column`.
+    if parsed_attrs.polymorphic {
+        if parsed_attrs.relation_type.as_deref() != Some("one_to_many") {
+            return Err(Error::new(
+                Span::call_site(),
+                "Attribute 'polymorphic' is only supported by one_to_many relations",
+            ));
+        }
+        let (Some(type_column), Some(id_column)) =
+            (&parsed_attrs.type_column, &parsed_attrs.id_column)
+        else {
+            return Err(Error::new(
+                Span::call_site(),
+                "Attributes 'type_column' and 'id_column' are required for polymorphic relations",
+            ));
+        };
+        if parsed_attrs.fk.is_some() {
+            return Err(Error::new(
+                Span::call_site(),
+                "Attribute 'fk' can't be combined with 'polymorphic', use 'id_column' instead",
+            ));
+        }
+        for column in [type_column, id_column] {
+            if syn::parse_str::<syn::Ident>(column).is_err() {
+                return Err(Error::new(
+                    Span::call_site(),
+                    format!("Invalid column name '{}'", column),
+                ));
+            }
+        }
+    } else if parsed_attrs.type_column.is_some()
+        || parsed_attrs.id_column.is_some()
+        || parsed_attrs.type_value.is_some()
+    {
+        return Err(Error::new(
+            Span::call_site(),
+            "Attributes 'type_column', 'id_column' and 'type_value' require 'polymorphic'",
+        ));
+    }
+    if (parsed_attrs.through.is_some() || parsed_attrs.through_fk.is_some())
+        && parsed_attrs.relation_type.as_deref() != Some("has_many_through")
+    {
+        return Err(Error::new(
+            Span::call_site(),
+            "Attributes 'through' and 'through_fk' are only supported by has_many_through relations",
+        ));
+    }
+    // The nested loader calls `load_with_<nested>` on the children, so both levels must be batch loaders.
+    if let Some(nested) = &parsed_attrs.nested {
+        if parsed_attrs.relation_type.as_deref() != Some("one_to_many")
+            || !parsed_attrs.eager_loading.unwrap_or(parsed_attrs.full)
+        {
+            return Err(Error::new(
+                Span::call_site(),
+                "Attribute 'nested' requires 'eager_loading' and is only supported by one_to_many relations",
+            ));
+        }
+        if syn::parse_str::<syn::Ident>(nested).is_err() {
+            return Err(Error::new(
+                Span::call_site(),
+                format!(
+                    "Attribute 'nested' must be the plural name of a relation, got '{}'",
+                    nested
+                ),
+            ));
+        }
+        let nested_child = parsed_attrs.nested_child.as_deref().ok_or_else(|| {
+            Error::new(
+                Span::call_site(),
+                "Attribute 'nested' requires 'nested_child'",
+            )
+        })?;
+        syn::parse_str::<syn::Type>(nested_child).map_err(|_| {
+            Error::new(
+                Span::call_site(),
+                format!(
+                    "Attribute 'nested_child' must be a type, got '{}'",
+                    nested_child
+                ),
+            )
+        })?;
+    } else if parsed_attrs.nested_child.is_some() {
+        return Err(Error::new(
+            Span::call_site(),
+            "Attribute 'nested_child' requires 'nested'",
+        ));
+    }
+    if let Some(cursor_column) = &parsed_attrs.cursor_column {
+        if !matches!(
+            parsed_attrs.relation_type.as_deref(),
+            Some("one_to_many" | "many_to_many")
+        ) {
+            return Err(Error::new(
+                Span::call_site(),
+                "Attribute 'cursor_column' is only supported by one_to_many and many_to_many relations",
+            ));
+        }
+        if syn::parse_str::<syn::Ident>(cursor_column).is_err() {
+            return Err(Error::new(
+                Span::call_site(),
+                format!(
+                    "Attribute 'cursor_column' must be a column name, got '{}'",
+                    cursor_column
+                ),
+            ));
+        }
+    }
+
+    if parsed_attrs.no_inflection {
+        if parsed_attrs.singular.is_none()
+            || parsed_attrs.plural.is_none()
+            || parsed_attrs.related_table.is_none()
+        {
+            return Err(Error::new(
+                Span::call_site(),
+                "Attributes 'singular', 'plural' and 'related_table' are required when 'no_inflection' is enabled",
+            ));
+        }
+        if parsed_attrs.serialize_as.is_some() {
+            return Err(Error::new(
+                Span::call_site(),
+                "Attribute 'serialize_as' can't be used with 'no_inflection', its getter name is inflected from the DTO type",
+            ));
+        }
+    }
+
+    // The listed columns replace the whole row in the child selects, which only the collection loaders have.
+    if let Some(columns) = &parsed_attrs.columns {
+        if !matches!(
+            parsed_attrs.relation_type.as_deref(),
+            Some("one_to_many" | "many_to_many")
+        ) {
+            return Err(Error::new(
+                Span::call_site(),
+                "Attribute 'columns' is only supported by one_to_many and many_to_many relations",
+            ));
+        }
+        if parsed_attrs.selectable {
+            return Err(Error::new(
+                Span::call_site(),
+                "Attributes 'columns' and 'selectable' can't be used together",
+            ));
+        }
+        for column in columns.split(',') {
+            syn::parse_str::<syn::Ident>(column.trim()).map_err(|_| {
+                Error::new(
+                    Span::call_site(),
+                    format!("Invalid column '{}' in 'columns'", column.trim()),
+                )
+            })?;
+        }
+    }
+    if parsed_attrs.latest.is_some() != parsed_attrs.latest_limit.is_some() {
+        return Err(Error::new(
+            Span::call_site(),
+            "Attributes 'latest' and 'latest_limit' must be set together",
+        ));
+    }
+
+    if parsed_attrs.recent && parsed_attrs.order_by.is_none() {
+        return Err(Error::new(
+            Span::call_site(),
+            "Attribute 'order_by' is required when 'recent' is enabled",
+        ));
+    }
+    if parsed_attrs.sorted && parsed_attrs.order_by.is_none() {
+        return Err(Error::new(
+            Span::call_site(),
+            "Attribute 'order_by' is required when 'sorted' is enabled",
+        ));
+    }
+    if parsed_attrs.optional
+        && (parsed_attrs.relation_type.as_deref() != Some("many_to_one")
+            || !parsed_attrs.eager_loading.unwrap_or(parsed_attrs.full))
+    {
+        return Err(Error::new(
+            Span::call_site(),
+            "Attribute 'optional' is only supported by many_to_one relations with 'eager_loading'",
+        ));
+    }
+    if parsed_attrs.ranked && parsed_attrs.order_by.is_none() {
+        return Err(Error::new(
+            Span::call_site(),
+            "Attribute 'order_by' is required when 'ranked' is enabled",
+        ));
+    }
+
+    match parsed_attrs.relation_type.as_deref() {
+        Some("one_to_many") | Some("one_to_one") => {
+            if parsed_attrs.child.is_none()
+                || (parsed_attrs.fk.is_none() && parsed_attrs.id_column.is_none())
+            {
+                return Err(Error::new(Span::call_site(), "Attributes 'child' and 'fk' are required for 'one_to_many' and 'one_to_one' relations"));
+            }
+        }
+        Some("many_to_one") => {
+            if parsed_attrs.child.is_none() {
+                return Err(Error::new(
+                    Span::call_site(),
                     "Attribute 'child' is required for 'many_to_one' relations",
                 ));
             }
@@ -97,6 +1468,15 @@ pub fn parse_attributes(attrs: AttributeArgs) -> Result<ParsedAttrs> {
                 return Err(Error::new(Span::call_site(), "Attributes 'join_table', 'fk_parent', and 'fk_child' are required for 'many_to_many' relations"));
             }
         }
+        Some("has_many_through") => {
+            if parsed_attrs.child.is_none()
+                || parsed_attrs.fk.is_none()
+                || parsed_attrs.through.is_none()
+                || parsed_attrs.through_fk.is_none()
+            {
+                return Err(Error::new(Span::call_site(), "Attributes 'child', 'fk', 'through', and 'through_fk' are required for 'has_many_through' relations"));
+            }
+        }
         _ => {
             return Err(Error::new(
                 Span::call_site(),
@@ -105,6 +1485,7 @@ pub fn parse_attributes(attrs: AttributeArgs) -> Result<ParsedAttrs> {
         }
     }
 
+    parsed_attrs.spans = names;
     Ok(parsed_attrs)
 }
 
@@ -184,4 +1565,1073 @@ mod tests {
         assert_eq!(parsed.fk_parent.unwrap(), "user_id");
         assert_eq!(parsed.fk_child.unwrap(), "post_id");
     }
+
+    #[test]
+    fn test_many_to_many_singular_attribute() {
+        let attrs = vec![
+            NestedMeta::Meta(parse_quote! { relation_type = "many_to_many" }),
+            NestedMeta::Meta(parse_quote! { child = "Data" }),
+            NestedMeta::Meta(parse_quote! { join_table = "post_data" }),
+            NestedMeta::Meta(parse_quote! { fk_parent = "post_id" }),
+            NestedMeta::Meta(parse_quote! { fk_child = "data_id" }),
+            NestedMeta::Meta(parse_quote! { singular = "data_point" }),
+        ];
+
+        let parsed = parse_attributes(attrs).unwrap();
+        assert_eq!(parsed.singular.unwrap(), "data_point");
+    }
+
+    #[test]
+    fn test_one_to_many_plural_attribute() {
+        let attrs = vec![
+            NestedMeta::Meta(parse_quote! { relation_type = "one_to_many" }),
+            NestedMeta::Meta(parse_quote! { child = "Person" }),
+            NestedMeta::Meta(parse_quote! { fk = "team_id" }),
+            NestedMeta::Meta(parse_quote! { plural = "members" }),
+        ];
+
+        let parsed = parse_attributes(attrs).unwrap();
+        assert_eq!(parsed.plural.unwrap(), "members");
+    }
+
+    #[test]
+    fn test_backend_list_attribute() {
+        let attrs = vec![
+            NestedMeta::Meta(parse_quote! { relation_type = "one_to_many" }),
+            NestedMeta::Meta(parse_quote! { child = "Post" }),
+            NestedMeta::Meta(parse_quote! { fk = "user_id" }),
+            NestedMeta::Meta(parse_quote! { backend = "postgres, sqlite" }),
+        ];
+
+        let parsed = parse_attributes(attrs).unwrap();
+        assert_eq!(parsed.backend.unwrap(), "postgres, sqlite");
+    }
+
+    #[test]
+    fn test_unsupported_backend_attribute() {
+        let attrs = vec![
+            NestedMeta::Meta(parse_quote! { relation_type = "one_to_many" }),
+            NestedMeta::Meta(parse_quote! { child = "Post" }),
+            NestedMeta::Meta(parse_quote! { fk = "user_id" }),
+            NestedMeta::Meta(parse_quote! { backend = "postgres, oracle" }),
+        ];
+
+        assert!(parse_attributes(attrs).is_err());
+    }
+
+    #[test]
+    fn test_pool_validation() {
+        let attrs = vec![
+            NestedMeta::Meta(parse_quote! { relation_type = "one_to_many" }),
+            NestedMeta::Meta(parse_quote! { child = "Post" }),
+            NestedMeta::Meta(parse_quote! { fk = "user_id" }),
+            NestedMeta::Meta(parse_quote! { pool = true }),
+            NestedMeta::Meta(parse_quote! { pool_error = "crate::error::AppError" }),
+        ];
+        let parsed = parse_attributes(attrs).unwrap();
+        assert!(parsed.pool);
+        assert_eq!(parsed.pool_error.as_deref(), Some("crate::error::AppError"));
+
+        let attrs = vec![
+            NestedMeta::Meta(parse_quote! { relation_type = "one_to_many" }),
+            NestedMeta::Meta(parse_quote! { child = "Post" }),
+            NestedMeta::Meta(parse_quote! { fk = "user_id" }),
+            NestedMeta::Meta(parse_quote! { pool_error = "crate::error::AppError" }),
+        ];
+        let error = parse_attributes(attrs).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Attribute 'pool_error' requires 'pool = true'"
+        );
+
+        let attrs = vec![
+            NestedMeta::Meta(parse_quote! { relation_type = "one_to_many" }),
+            NestedMeta::Meta(parse_quote! { child = "Post" }),
+            NestedMeta::Meta(parse_quote! { fk = "user_id" }),
+            NestedMeta::Meta(parse_quote! { pool = true }),
+            NestedMeta::Meta(parse_quote! { connection_type = "crate::db::SharedConn" }),
+        ];
+        let error = parse_attributes(attrs).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Attribute 'pool' is not supported with 'connection_type'"
+        );
+    }
+
+    #[test]
+    fn test_conn_type_validation() {
+        let attrs = vec![
+            NestedMeta::Meta(parse_quote! { relation_type = "one_to_many" }),
+            NestedMeta::Meta(parse_quote! { child = "Post" }),
+            NestedMeta::Meta(parse_quote! { fk = "user_id" }),
+            NestedMeta::Meta(parse_quote! { conn_type = "crate::db::LoggingConnection" }),
+        ];
+        assert_eq!(
+            parse_attributes(attrs).unwrap().conn_type.as_deref(),
+            Some("crate::db::LoggingConnection")
+        );
+
+        let attrs = vec![
+            NestedMeta::Meta(parse_quote! { relation_type = "one_to_many" }),
+            NestedMeta::Meta(parse_quote! { child = "Post" }),
+            NestedMeta::Meta(parse_quote! { fk = "user_id" }),
+            NestedMeta::Meta(parse_quote! { conn_type = "crate::db::LoggingConnection" }),
+            NestedMeta::Meta(parse_quote! { connection_type = "crate::db::SharedConn" }),
+        ];
+        let error = parse_attributes(attrs).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Attributes 'conn_type' and 'connection_type' are mutually exclusive"
+        );
+
+        let attrs = vec![
+            NestedMeta::Meta(parse_quote! { relation_type = "one_to_many" }),
+            NestedMeta::Meta(parse_quote! { child = "Post" }),
+            NestedMeta::Meta(parse_quote! { fk = "user_id" }),
+            NestedMeta::Meta(parse_quote! { conn_type = "not a type" }),
+        ];
+        assert!(parse_attributes(attrs).is_err());
+    }
+
+    #[test]
+    fn test_duplicate_backend_attribute() {
+        let attrs = vec![
+            NestedMeta::Meta(parse_quote! { relation_type = "one_to_many" }),
+            NestedMeta::Meta(parse_quote! { child = "Post" }),
+            NestedMeta::Meta(parse_quote! { fk = "user_id" }),
+            NestedMeta::Meta(parse_quote! { backend = "postgres, sqlite, postgres" }),
+        ];
+
+        let error = parse_attributes(attrs).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Backend 'postgres' is listed more than once"
+        );
+    }
+
+    #[test]
+    fn test_recent_requires_order_by() {
+        let attrs = vec![
+            NestedMeta::Meta(parse_quote! { relation_type = "one_to_many" }),
+            NestedMeta::Meta(parse_quote! { child = "Post" }),
+            NestedMeta::Meta(parse_quote! { fk = "user_id" }),
+            NestedMeta::Meta(parse_quote! { recent = true }),
+        ];
+        assert!(parse_attributes(attrs).is_err());
+
+        let attrs = vec![
+            NestedMeta::Meta(parse_quote! { relation_type = "one_to_many" }),
+            NestedMeta::Meta(parse_quote! { child = "Post" }),
+            NestedMeta::Meta(parse_quote! { fk = "user_id" }),
+            NestedMeta::Meta(parse_quote! { recent = true }),
+            NestedMeta::Meta(parse_quote! { order_by = "created_at" }),
+        ];
+        let parsed = parse_attributes(attrs).unwrap();
+        assert!(parsed.recent);
+        assert_eq!(parsed.order_by.unwrap(), "created_at");
+    }
+
+    #[test]
+    fn test_latest_requires_limit() {
+        let attrs = vec![
+            NestedMeta::Meta(parse_quote! { relation_type = "one_to_many" }),
+            NestedMeta::Meta(parse_quote! { child = "Post" }),
+            NestedMeta::Meta(parse_quote! { fk = "user_id" }),
+            NestedMeta::Meta(parse_quote! { latest = "created_at" }),
+        ];
+        assert!(parse_attributes(attrs).is_err());
+
+        let attrs = vec![
+            NestedMeta::Meta(parse_quote! { relation_type = "one_to_many" }),
+            NestedMeta::Meta(parse_quote! { child = "Post" }),
+            NestedMeta::Meta(parse_quote! { fk = "user_id" }),
+            NestedMeta::Meta(parse_quote! { latest = "created_at" }),
+            NestedMeta::Meta(parse_quote! { latest_limit = 5 }),
+        ];
+        let parsed = parse_attributes(attrs).unwrap();
+        assert_eq!(parsed.latest.as_deref(), Some("created_at"));
+        assert_eq!(parsed.latest_limit, Some(5));
+    }
+
+    #[test]
+    fn test_tuple_child_requires_columns() {
+        let attrs = vec![
+            NestedMeta::Meta(parse_quote! { relation_type = "one_to_many" }),
+            NestedMeta::Meta(parse_quote! { child = "(i32, String)" }),
+            NestedMeta::Meta(parse_quote! { fk = "user_id" }),
+            NestedMeta::Meta(parse_quote! { related_table = "posts" }),
+        ];
+        assert_eq!(
+            parse_attributes(attrs).unwrap_err().to_string(),
+            "Attributes 'columns', 'related_table', 'singular' and 'plural' are required when 'child' is a tuple"
+        );
+
+        let attrs = vec![
+            NestedMeta::Meta(parse_quote! { relation_type = "one_to_many" }),
+            NestedMeta::Meta(parse_quote! { child = "(i32, String)" }),
+            NestedMeta::Meta(parse_quote! { fk = "user_id" }),
+            NestedMeta::Meta(parse_quote! { related_table = "posts" }),
+            NestedMeta::Meta(parse_quote! { singular = "post_title" }),
+            NestedMeta::Meta(parse_quote! { plural = "post_titles" }),
+            NestedMeta::Meta(parse_quote! { columns = "id, title" }),
+        ];
+        assert_eq!(
+            parse_attributes(attrs).unwrap().columns.as_deref(),
+            Some("id, title")
+        );
+
+        let attrs = vec![
+            NestedMeta::Meta(parse_quote! { relation_type = "one_to_many" }),
+            NestedMeta::Meta(parse_quote! { child = "Post" }),
+            NestedMeta::Meta(parse_quote! { fk = "user_id" }),
+            NestedMeta::Meta(parse_quote! { columns = "id, title.len()" }),
+        ];
+        assert_eq!(
+            parse_attributes(attrs).unwrap_err().to_string(),
+            "Invalid column 'title.len()' in 'columns'"
+        );
+    }
+
+    #[test]
+    fn test_eager_loading_relation_types() {
+        let attrs = vec![
+            NestedMeta::Meta(parse_quote! { relation_type = "one_to_one" }),
+            NestedMeta::Meta(parse_quote! { child = "Profile" }),
+            NestedMeta::Meta(parse_quote! { fk = "user_id" }),
+            NestedMeta::Meta(parse_quote! { eager_loading = true }),
+        ];
+        let error = parse_attributes(attrs).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Attribute 'eager_loading' is only supported by one_to_many and many_to_one relations, not one_to_one"
+        );
+
+        let attrs = vec![
+            NestedMeta::Meta(parse_quote! { relation_type = "one_to_many" }),
+            NestedMeta::Meta(parse_quote! { child = "Post" }),
+            NestedMeta::Meta(parse_quote! { fk = "user_id" }),
+            NestedMeta::Meta(parse_quote! { eager_loading = true }),
+        ];
+        assert_eq!(parse_attributes(attrs).unwrap().eager_loading, Some(true));
+
+        let attrs = vec![
+            NestedMeta::Meta(parse_quote! { relation_type = "many_to_one" }),
+            NestedMeta::Meta(parse_quote! { child = "User" }),
+            NestedMeta::Meta(parse_quote! { fk = "user_id" }),
+            NestedMeta::Meta(parse_quote! { eager_loading = true }),
+        ];
+        assert_eq!(parse_attributes(attrs).unwrap().eager_loading, Some(true));
+
+        let attrs = vec![
+            NestedMeta::Meta(parse_quote! { relation_type = "many_to_many" }),
+            NestedMeta::Meta(parse_quote! { child = "Tag" }),
+            NestedMeta::Meta(parse_quote! { join_table = "post_tags" }),
+            NestedMeta::Meta(parse_quote! { fk_parent = "post_id" }),
+            NestedMeta::Meta(parse_quote! { fk_child = "tag_id" }),
+            NestedMeta::Meta(parse_quote! { eager_loading_stream = true }),
+        ];
+        assert_eq!(
+            parse_attributes(attrs).unwrap_err().to_string(),
+            "Attribute 'eager_loading_stream' is only supported by one_to_many relations, not many_to_many"
+        );
+    }
+
+    #[test]
+    fn test_eager_loading_stream_requires_async_feature() {
+        let attrs = vec![
+            NestedMeta::Meta(parse_quote! { relation_type = "one_to_many" }),
+            NestedMeta::Meta(parse_quote! { child = "Post" }),
+            NestedMeta::Meta(parse_quote! { fk = "user_id" }),
+            NestedMeta::Meta(parse_quote! { eager_loading_stream = true }),
+        ];
+        let result = parse_attributes(attrs);
+        if cfg!(feature = "async") {
+            assert!(result.unwrap().eager_loading_stream);
+        } else {
+            assert_eq!(
+                result.unwrap_err().to_string(),
+                "Attribute 'eager_loading_stream' requires the 'async' feature of diesel_linker"
+            );
+        }
+    }
+
+    #[test]
+    fn test_async_requires_async_feature() {
+        let attrs = vec![
+            NestedMeta::Meta(parse_quote! { relation_type = "one_to_many" }),
+            NestedMeta::Meta(parse_quote! { child = "Post" }),
+            NestedMeta::Meta(parse_quote! { fk = "user_id" }),
+            NestedMeta::Meta(parse_quote! { async = true }),
+        ];
+        let result = parse_attributes(attrs);
+        if cfg!(feature = "async") {
+            assert!(result.unwrap().async_);
+        } else {
+            assert_eq!(
+                result.unwrap_err().to_string(),
+                "Attribute 'async' requires the 'async' feature of diesel_linker"
+            );
+        }
+    }
+
+    #[test]
+    fn test_streaming_requires_async() {
+        let attrs = vec![
+            NestedMeta::Meta(parse_quote! { relation_type = "one_to_many" }),
+            NestedMeta::Meta(parse_quote! { child = "Post" }),
+            NestedMeta::Meta(parse_quote! { fk = "user_id" }),
+            NestedMeta::Meta(parse_quote! { streaming = true }),
+        ];
+        assert_eq!(
+            parse_attributes(attrs).unwrap_err().to_string(),
+            "Attribute 'streaming' requires 'async' and is only supported by one_to_many relations"
+        );
+    }
+
+    #[test]
+    fn test_debug_sql_requires_backend() {
+        let attrs = vec![
+            NestedMeta::Meta(parse_quote! { relation_type = "one_to_many" }),
+            NestedMeta::Meta(parse_quote! { child = "Post" }),
+            NestedMeta::Meta(parse_quote! { fk = "user_id" }),
+            NestedMeta::Meta(parse_quote! { debug_sql = true }),
+        ];
+        assert_eq!(
+            parse_attributes(attrs).unwrap_err().to_string(),
+            "Attribute 'backend' is required when 'debug_sql' is enabled"
+        );
+
+        let attrs = vec![
+            NestedMeta::Meta(parse_quote! { relation_type = "one_to_many" }),
+            NestedMeta::Meta(parse_quote! { child = "Post" }),
+            NestedMeta::Meta(parse_quote! { fk = "user_id" }),
+            NestedMeta::Meta(parse_quote! { backend = "sqlite" }),
+            NestedMeta::Meta(parse_quote! { debug_sql = true }),
+        ];
+        assert!(parse_attributes(attrs).unwrap().debug_sql);
+    }
+
+    #[test]
+    fn test_natural_keys_relation_types() {
+        let attrs = vec![
+            NestedMeta::Meta(parse_quote! { relation_type = "one_to_many" }),
+            NestedMeta::Meta(parse_quote! { child = "Post" }),
+            NestedMeta::Meta(parse_quote! { fk = "user_id" }),
+            NestedMeta::Meta(parse_quote! { child_key = "external_ref" }),
+        ];
+        assert!(parse_attributes(attrs).is_err());
+
+        let attrs = vec![
+            NestedMeta::Meta(parse_quote! { relation_type = "many_to_many" }),
+            NestedMeta::Meta(parse_quote! { child = "Tag" }),
+            NestedMeta::Meta(parse_quote! { join_table = "post_tags" }),
+            NestedMeta::Meta(parse_quote! { fk_parent = "post_id" }),
+            NestedMeta::Meta(parse_quote! { fk_child = "tag_id" }),
+            NestedMeta::Meta(parse_quote! { parent_key = "external_id" }),
+        ];
+        assert!(parse_attributes(attrs).is_err());
+
+        let attrs = vec![
+            NestedMeta::Meta(parse_quote! { relation_type = "one_to_many" }),
+            NestedMeta::Meta(parse_quote! { child = "Post" }),
+            NestedMeta::Meta(parse_quote! { fk = "user_external_id" }),
+            NestedMeta::Meta(parse_quote! { parent_key = "external_id" }),
+        ];
+        assert!(parse_attributes(attrs).is_ok());
+
+        let attrs = vec![
+            NestedMeta::Meta(parse_quote! { relation_type = "one_to_one" }),
+            NestedMeta::Meta(parse_quote! { child = "Profile" }),
+            NestedMeta::Meta(parse_quote! { fk = "user_id" }),
+            NestedMeta::Meta(parse_quote! { parent_key = "external_id" }),
+            NestedMeta::Meta(parse_quote! { child_key = "external_ref" }),
+        ];
+        let parsed = parse_attributes(attrs).unwrap();
+        assert_eq!(parsed.parent_key.as_deref(), Some("external_id"));
+        assert_eq!(parsed.child_key.as_deref(), Some("external_ref"));
+    }
+
+    #[test]
+    fn test_mutator_verbs() {
+        let many_to_many = || {
+            vec![
+                NestedMeta::Meta(parse_quote! { relation_type = "many_to_many" }),
+                NestedMeta::Meta(parse_quote! { child = "Tag" }),
+                NestedMeta::Meta(parse_quote! { join_table = "post_tags" }),
+                NestedMeta::Meta(parse_quote! { fk_parent = "post_id" }),
+                NestedMeta::Meta(parse_quote! { fk_child = "tag_id" }),
+            ]
+        };
+        let mut attrs = many_to_many();
+        attrs.push(NestedMeta::Meta(parse_quote! { link_verb = "attach" }));
+        attrs.push(NestedMeta::Meta(parse_quote! { unlink_verb = "detach" }));
+        let parsed = parse_attributes(attrs).unwrap();
+        assert_eq!(parsed.link_verb.as_deref(), Some("attach"));
+        assert_eq!(parsed.unlink_verb.as_deref(), Some("detach"));
+
+        let mut attrs = many_to_many();
+        attrs.push(NestedMeta::Meta(parse_quote! { link_verb = "attach to" }));
+        assert_eq!(
+            parse_attributes(attrs).unwrap_err().to_string(),
+            "Invalid mutator verb 'attach to'"
+        );
+    }
+
+    #[test]
+    fn test_related_table_path() {
+        let attrs = vec![
+            NestedMeta::Meta(parse_quote! { relation_type = "one_to_many" }),
+            NestedMeta::Meta(parse_quote! { child = "Event" }),
+            NestedMeta::Meta(parse_quote! { fk = "user_id" }),
+            NestedMeta::Meta(parse_quote! { related_table = "analytics::events" }),
+        ];
+        assert_eq!(
+            parse_attributes(attrs).unwrap().related_table.as_deref(),
+            Some("analytics::events")
+        );
+
+        let attrs = vec![
+            NestedMeta::Meta(parse_quote! { relation_type = "one_to_many" }),
+            NestedMeta::Meta(parse_quote! { child = "Event" }),
+            NestedMeta::Meta(parse_quote! { fk = "user_id" }),
+            NestedMeta::Meta(parse_quote! { related_table = "analytics.events" }),
+        ];
+        assert_eq!(
+            parse_attributes(attrs).unwrap_err().to_string(),
+            "Attribute 'related_table' must be a table name or a relative module path, got 'analytics.events'"
+        );
+    }
+
+    #[test]
+    fn test_no_inflection_requires_explicit_names() {
+        let attrs = vec![
+            NestedMeta::Meta(parse_quote! { relation_type = "one_to_many" }),
+            NestedMeta::Meta(parse_quote! { child = "Post" }),
+            NestedMeta::Meta(parse_quote! { fk = "user_id" }),
+            NestedMeta::Meta(parse_quote! { no_inflection = true }),
+            NestedMeta::Meta(parse_quote! { singular = "post" }),
+            NestedMeta::Meta(parse_quote! { plural = "posts" }),
+        ];
+        assert!(parse_attributes(attrs).is_err());
+
+        let attrs = vec![
+            NestedMeta::Meta(parse_quote! { relation_type = "one_to_many" }),
+            NestedMeta::Meta(parse_quote! { child = "Post" }),
+            NestedMeta::Meta(parse_quote! { fk = "user_id" }),
+            NestedMeta::Meta(parse_quote! { no_inflection = true }),
+            NestedMeta::Meta(parse_quote! { singular = "post" }),
+            NestedMeta::Meta(parse_quote! { plural = "posts" }),
+            NestedMeta::Meta(parse_quote! { related_table = "posts" }),
+        ];
+        assert!(parse_attributes(attrs).unwrap().no_inflection);
+
+        let attrs = vec![
+            NestedMeta::Meta(parse_quote! { relation_type = "one_to_many" }),
+            NestedMeta::Meta(parse_quote! { child = "Post" }),
+            NestedMeta::Meta(parse_quote! { fk = "user_id" }),
+            NestedMeta::Meta(parse_quote! { no_inflection = true }),
+            NestedMeta::Meta(parse_quote! { singular = "post" }),
+            NestedMeta::Meta(parse_quote! { plural = "posts" }),
+            NestedMeta::Meta(parse_quote! { related_table = "posts" }),
+            NestedMeta::Meta(parse_quote! { serialize_as = "PostDto" }),
+        ];
+        assert!(parse_attributes(attrs).is_err());
+    }
+
+    #[test]
+    fn test_parse_aggregates() {
+        assert_eq!(
+            parse_aggregates("count, sum(views)").unwrap(),
+            vec![Aggregate::Count, Aggregate::Sum("views".to_string())]
+        );
+        assert!(parse_aggregates("avg(views)").is_err());
+        assert!(parse_aggregates("sum()").is_err());
+    }
+
+    #[test]
+    fn test_parse_default_filter() {
+        assert_eq!(
+            parse_default_filter(r#"status = "active" AND deleted_at IS NULL"#).unwrap(),
+            vec![
+                FilterCondition::Eq("status".to_string(), "\"active\"".to_string()),
+                FilterCondition::IsNull("deleted_at".to_string()),
+            ]
+        );
+        assert_eq!(
+            parse_default_filter("views > -1 and rank != 3 AND id IN (1, 2) AND body is not null")
+                .unwrap(),
+            vec![
+                FilterCondition::Gt("views".to_string(), "-1".to_string()),
+                FilterCondition::NotEq("rank".to_string(), "3".to_string()),
+                FilterCondition::In("id".to_string(), vec!["1".to_string(), "2".to_string()]),
+                FilterCondition::IsNotNull("body".to_string()),
+            ]
+        );
+        assert!(parse_default_filter("status = 'active'").is_err());
+        assert!(parse_default_filter("status LIKE \"a%\"").is_err());
+        assert!(parse_default_filter("status = \"a\" OR id = 1").is_err());
+        assert!(parse_default_filter("status = \"a\" AND").is_err());
+        assert!(parse_default_filter("id IN ()").is_err());
+    }
+
+    #[test]
+    fn test_parse_join_condition() {
+        assert_eq!(
+            parse_join_condition("child.lft BETWEEN self.lft AND self.rgt").unwrap(),
+            JoinCondition::Between("lft".to_string(), "lft".to_string(), "rgt".to_string())
+        );
+        assert_eq!(
+            parse_join_condition("child.starts_at < self.ends_at").unwrap(),
+            JoinCondition::Lt("starts_at".to_string(), "ends_at".to_string())
+        );
+        assert_eq!(
+            parse_join_condition("child.ends_at >= self.starts_at").unwrap(),
+            JoinCondition::Ge("ends_at".to_string(), "starts_at".to_string())
+        );
+        assert_eq!(
+            parse_join_condition("child.depth <= self.depth").unwrap(),
+            JoinCondition::Le("depth".to_string(), "depth".to_string())
+        );
+        assert!(parse_join_condition("child.lft = self.lft").is_err());
+        assert!(parse_join_condition("self.lft < child.rgt").is_err());
+        assert!(parse_join_condition("child.lft BETWEEN self.lft").is_err());
+        assert!(parse_join_condition("child.lft < 3").is_err());
+    }
+
+    #[test]
+    fn test_full_leaves_explicit_flags_unset() {
+        let attrs = vec![
+            NestedMeta::Meta(parse_quote! { relation_type = "one_to_many" }),
+            NestedMeta::Meta(parse_quote! { child = "Post" }),
+            NestedMeta::Meta(parse_quote! { fk = "user_id" }),
+            NestedMeta::Meta(parse_quote! { full = true }),
+            NestedMeta::Meta(parse_quote! { wrapper = false }),
+        ];
+        let parsed_attrs = parse_attributes(attrs).unwrap();
+        assert!(parsed_attrs.full);
+        assert_eq!(parsed_attrs.wrapper, Some(false));
+        assert_eq!(parsed_attrs.count, None);
+        assert_eq!(parsed_attrs.exists, None);
+        assert_eq!(parsed_attrs.eager_loading, None);
+    }
+
+    #[test]
+    fn test_child_must_be_a_type_path() {
+        let attrs = vec![
+            NestedMeta::Meta(parse_quote! { relation_type = "one_to_many" }),
+            NestedMeta::Meta(parse_quote! { child = "crate::models::Post" }),
+            NestedMeta::Meta(parse_quote! { fk = "user_id" }),
+        ];
+        assert!(parse_attributes(attrs).is_ok());
+
+        let attrs = vec![
+            NestedMeta::Meta(parse_quote! { relation_type = "one_to_many" }),
+            NestedMeta::Meta(parse_quote! { child = "user profile" }),
+            NestedMeta::Meta(parse_quote! { fk = "user_id" }),
+        ];
+        assert!(parse_attributes(attrs).is_err());
+    }
+
+    #[test]
+    fn test_visibility_must_be_a_visibility() {
+        let attrs = vec![
+            NestedMeta::Meta(parse_quote! { relation_type = "one_to_many" }),
+            NestedMeta::Meta(parse_quote! { child = "Post" }),
+            NestedMeta::Meta(parse_quote! { fk = "user_id" }),
+            NestedMeta::Meta(parse_quote! { visibility = "pub(crate)" }),
+        ];
+        assert!(parse_attributes(attrs).is_ok());
+
+        let attrs = vec![
+            NestedMeta::Meta(parse_quote! { relation_type = "one_to_many" }),
+            NestedMeta::Meta(parse_quote! { child = "Post" }),
+            NestedMeta::Meta(parse_quote! { fk = "user_id" }),
+            NestedMeta::Meta(parse_quote! { visibility = "crate only" }),
+        ];
+        let error = parse_attributes(attrs).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Attribute 'visibility' must be a visibility such as 'pub(crate)', got 'crate only'"
+        );
+    }
+
+    #[test]
+    fn test_optional_requires_eager_many_to_one() {
+        let attrs = vec![
+            NestedMeta::Meta(parse_quote! { relation_type = "many_to_one" }),
+            NestedMeta::Meta(parse_quote! { child = "User" }),
+            NestedMeta::Meta(parse_quote! { fk = "user_id" }),
+            NestedMeta::Meta(parse_quote! { eager_loading = true }),
+            NestedMeta::Meta(parse_quote! { optional = true }),
+        ];
+        assert!(parse_attributes(attrs).unwrap().optional);
+
+        let attrs = vec![
+            NestedMeta::Meta(parse_quote! { relation_type = "many_to_one" }),
+            NestedMeta::Meta(parse_quote! { child = "User" }),
+            NestedMeta::Meta(parse_quote! { fk = "user_id" }),
+            NestedMeta::Meta(parse_quote! { optional = true }),
+        ];
+        let error = parse_attributes(attrs).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Attribute 'optional' is only supported by many_to_one relations with 'eager_loading'"
+        );
+    }
+
+    #[test]
+    fn test_soft_delete_column_is_one_to_many_only() {
+        let attrs = vec![
+            NestedMeta::Meta(parse_quote! { relation_type = "one_to_many" }),
+            NestedMeta::Meta(parse_quote! { child = "Post" }),
+            NestedMeta::Meta(parse_quote! { fk = "user_id" }),
+            NestedMeta::Meta(parse_quote! { soft_delete_column = "deleted_at" }),
+        ];
+        assert_eq!(
+            parse_attributes(attrs)
+                .unwrap()
+                .soft_delete_column
+                .as_deref(),
+            Some("deleted_at")
+        );
+
+        let attrs = vec![
+            NestedMeta::Meta(parse_quote! { relation_type = "one_to_one" }),
+            NestedMeta::Meta(parse_quote! { child = "Profile" }),
+            NestedMeta::Meta(parse_quote! { fk = "user_id" }),
+            NestedMeta::Meta(parse_quote! { soft_delete_column = "deleted_at" }),
+        ];
+        let error = parse_attributes(attrs).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Attribute 'soft_delete_column' is only supported by one_to_many relations"
+        );
+    }
+
+    #[test]
+    fn test_parse_order_by() {
+        assert_eq!(
+            parse_order_by("created_at").unwrap(),
+            ("created_at".to_string(), false)
+        );
+        assert_eq!(
+            parse_order_by("created_at ASC").unwrap(),
+            ("created_at".to_string(), false)
+        );
+        assert_eq!(
+            parse_order_by(" created_at desc ").unwrap(),
+            ("created_at".to_string(), true)
+        );
+        for order_by in [
+            "",
+            "created_at sideways",
+            "created_at desc id",
+            "created-at",
+        ] {
+            assert_eq!(
+                parse_order_by(order_by).unwrap_err().to_string(),
+                format!(
+                    "Unsupported order_by '{}', expected `<column>`, `<column> asc` or `<column> desc`",
+                    order_by
+                )
+            );
+        }
+    }
+
+    #[test]
+    fn test_ids_relation_types() {
+        let attrs = vec![
+            NestedMeta::Meta(parse_quote! { relation_type = "one_to_many" }),
+            NestedMeta::Meta(parse_quote! { child = "Post" }),
+            NestedMeta::Meta(parse_quote! { fk = "user_id" }),
+            NestedMeta::Meta(parse_quote! { ids = true }),
+            NestedMeta::Meta(parse_quote! { ids_type = "i64" }),
+        ];
+        let parsed_attrs = parse_attributes(attrs).unwrap();
+        assert!(parsed_attrs.ids);
+        assert_eq!(parsed_attrs.ids_type.as_deref(), Some("i64"));
+
+        let attrs = vec![
+            NestedMeta::Meta(parse_quote! { relation_type = "many_to_one" }),
+            NestedMeta::Meta(parse_quote! { child = "User" }),
+            NestedMeta::Meta(parse_quote! { fk = "user_id" }),
+            NestedMeta::Meta(parse_quote! { ids = true }),
+        ];
+        let error = parse_attributes(attrs).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Attribute 'ids' is only supported by one_to_many and many_to_many relations"
+        );
+    }
+
+    #[test]
+    fn test_chunk_size_validation() {
+        let attrs = vec![
+            NestedMeta::Meta(parse_quote! { relation_type = "one_to_many" }),
+            NestedMeta::Meta(parse_quote! { child = "Post" }),
+            NestedMeta::Meta(parse_quote! { fk = "user_id" }),
+            NestedMeta::Meta(parse_quote! { chunk_size = 900 }),
+        ];
+        assert_eq!(parse_attributes(attrs).unwrap().chunk_size, Some(900));
+
+        let attrs = vec![
+            NestedMeta::Meta(parse_quote! { relation_type = "one_to_many" }),
+            NestedMeta::Meta(parse_quote! { child = "Post" }),
+            NestedMeta::Meta(parse_quote! { fk = "user_id" }),
+            NestedMeta::Meta(parse_quote! { chunk_size = 0 }),
+        ];
+        let error = parse_attributes(attrs).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Attribute 'chunk_size' must be greater than 0"
+        );
+
+        let attrs = vec![
+            NestedMeta::Meta(parse_quote! { relation_type = "one_to_one" }),
+            NestedMeta::Meta(parse_quote! { child = "Profile" }),
+            NestedMeta::Meta(parse_quote! { fk = "user_id" }),
+            NestedMeta::Meta(parse_quote! { chunk_size = 900 }),
+        ];
+        let error = parse_attributes(attrs).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Attribute 'chunk_size' is only supported by one_to_many and many_to_one relations, not one_to_one"
+        );
+    }
+
+    #[test]
+    fn test_latest_by_validation() {
+        let attrs = vec![
+            NestedMeta::Meta(parse_quote! { relation_type = "one_to_many" }),
+            NestedMeta::Meta(parse_quote! { child = "Post" }),
+            NestedMeta::Meta(parse_quote! { fk = "user_id" }),
+            NestedMeta::Meta(parse_quote! { latest_by = "created_at" }),
+        ];
+        assert_eq!(
+            parse_attributes(attrs).unwrap().latest_by.as_deref(),
+            Some("created_at")
+        );
+
+        let attrs = vec![
+            NestedMeta::Meta(parse_quote! { relation_type = "one_to_one" }),
+            NestedMeta::Meta(parse_quote! { child = "Profile" }),
+            NestedMeta::Meta(parse_quote! { fk = "user_id" }),
+            NestedMeta::Meta(parse_quote! { latest_by = "created_at" }),
+        ];
+        let error = parse_attributes(attrs).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Attribute 'latest_by' is only supported by one_to_many relations"
+        );
+
+        let attrs = vec![
+            NestedMeta::Meta(parse_quote! { relation_type = "one_to_many" }),
+            NestedMeta::Meta(parse_quote! { child = "Post" }),
+            NestedMeta::Meta(parse_quote! { fk = "user_id" }),
+            NestedMeta::Meta(parse_quote! { latest_by = "created_at desc" }),
+        ];
+        let error = parse_attributes(attrs).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Attribute 'latest_by' must be a column name, got 'created_at desc'"
+        );
+    }
+
+    #[test]
+    fn test_any_backend_validation() {
+        let attrs = vec![
+            NestedMeta::Meta(parse_quote! { relation_type = "one_to_many" }),
+            NestedMeta::Meta(parse_quote! { child = "Post" }),
+            NestedMeta::Meta(parse_quote! { fk = "user_id" }),
+            NestedMeta::Meta(parse_quote! { backend = "any" }),
+            NestedMeta::Meta(parse_quote! { count = true }),
+        ];
+        assert_eq!(
+            parse_attributes(attrs).unwrap().backend.as_deref(),
+            Some("any")
+        );
+
+        let attrs = vec![
+            NestedMeta::Meta(parse_quote! { relation_type = "one_to_many" }),
+            NestedMeta::Meta(parse_quote! { child = "Post" }),
+            NestedMeta::Meta(parse_quote! { fk = "user_id" }),
+            NestedMeta::Meta(parse_quote! { backend = "any" }),
+            NestedMeta::Meta(parse_quote! { eager_loading = true }),
+        ];
+        let error = parse_attributes(attrs).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Attribute 'eager_loading' is not supported with backend 'any'"
+        );
+
+        let attrs = vec![
+            NestedMeta::Meta(parse_quote! { relation_type = "many_to_one" }),
+            NestedMeta::Meta(parse_quote! { child = "User" }),
+            NestedMeta::Meta(parse_quote! { fk = "user_id" }),
+            NestedMeta::Meta(parse_quote! { backend = "any" }),
+        ];
+        assert!(parse_attributes(attrs).is_err());
+
+        let attrs = vec![
+            NestedMeta::Meta(parse_quote! { relation_type = "one_to_many" }),
+            NestedMeta::Meta(parse_quote! { child = "Post" }),
+            NestedMeta::Meta(parse_quote! { fk = "user_id" }),
+            NestedMeta::Meta(parse_quote! { backend = "any, sqlite" }),
+        ];
+        let error = parse_attributes(attrs).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Unsupported backend 'any', expected 'postgres', 'sqlite', 'mysql' or 'any'"
+        );
+    }
+
+    #[test]
+    fn test_inverse_validation() {
+        let attrs = vec![
+            NestedMeta::Meta(parse_quote! { relation_type = "many_to_one" }),
+            NestedMeta::Meta(parse_quote! { child = "Employee" }),
+            NestedMeta::Meta(parse_quote! { fk = "manager_id" }),
+            NestedMeta::Meta(parse_quote! { inverse = "reports" }),
+        ];
+        assert_eq!(
+            parse_attributes(attrs).unwrap().inverse.as_deref(),
+            Some("reports")
+        );
+
+        let attrs = vec![
+            NestedMeta::Meta(parse_quote! { relation_type = "one_to_many" }),
+            NestedMeta::Meta(parse_quote! { child = "Employee" }),
+            NestedMeta::Meta(parse_quote! { fk = "manager_id" }),
+            NestedMeta::Meta(parse_quote! { inverse = "reports" }),
+        ];
+        let error = parse_attributes(attrs).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Attribute 'inverse' is only supported by many_to_one relations"
+        );
+    }
+
+    #[test]
+    fn test_polymorphic_validation() {
+        let attrs = vec![
+            NestedMeta::Meta(parse_quote! { relation_type = "one_to_many" }),
+            NestedMeta::Meta(parse_quote! { child = "Comment" }),
+            NestedMeta::Meta(parse_quote! { polymorphic = true }),
+            NestedMeta::Meta(parse_quote! { type_column = "commentable_type" }),
+            NestedMeta::Meta(parse_quote! { id_column = "commentable_id" }),
+        ];
+        let parsed = parse_attributes(attrs).unwrap();
+        assert!(parsed.polymorphic);
+        assert_eq!(parsed.type_column.as_deref(), Some("commentable_type"));
+        assert_eq!(parsed.id_column.as_deref(), Some("commentable_id"));
+
+        let attrs = vec![
+            NestedMeta::Meta(parse_quote! { relation_type = "one_to_many" }),
+            NestedMeta::Meta(parse_quote! { child = "Comment" }),
+            NestedMeta::Meta(parse_quote! { polymorphic = true }),
+            NestedMeta::Meta(parse_quote! { id_column = "commentable_id" }),
+        ];
+        let error = parse_attributes(attrs).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Attributes 'type_column' and 'id_column' are required for polymorphic relations"
+        );
+
+        let attrs = vec![
+            NestedMeta::Meta(parse_quote! { relation_type = "one_to_many" }),
+            NestedMeta::Meta(parse_quote! { child = "Comment" }),
+            NestedMeta::Meta(parse_quote! { fk = "post_id" }),
+            NestedMeta::Meta(parse_quote! { polymorphic = true }),
+            NestedMeta::Meta(parse_quote! { type_column = "commentable_type" }),
+            NestedMeta::Meta(parse_quote! { id_column = "commentable_id" }),
+        ];
+        let error = parse_attributes(attrs).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Attribute 'fk' can't be combined with 'polymorphic', use 'id_column' instead"
+        );
+
+        let attrs = vec![
+            NestedMeta::Meta(parse_quote! { relation_type = "one_to_many" }),
+            NestedMeta::Meta(parse_quote! { child = "Comment" }),
+            NestedMeta::Meta(parse_quote! { fk = "post_id" }),
+            NestedMeta::Meta(parse_quote! { type_value = "Post" }),
+        ];
+        let error = parse_attributes(attrs).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Attributes 'type_column', 'id_column' and 'type_value' require 'polymorphic'"
+        );
+    }
+
+    #[test]
+    fn test_has_many_through_relation_attributes() {
+        let attrs = vec![
+            NestedMeta::Meta(parse_quote! { relation_type = "has_many_through" }),
+            NestedMeta::Meta(parse_quote! { child = "Comment" }),
+            NestedMeta::Meta(parse_quote! { fk = "post_id" }),
+            NestedMeta::Meta(parse_quote! { through = "posts" }),
+            NestedMeta::Meta(parse_quote! { through_fk = "user_id" }),
+        ];
+        let parsed = parse_attributes(attrs).unwrap();
+        assert_eq!(parsed.through.as_deref(), Some("posts"));
+        assert_eq!(parsed.through_fk.as_deref(), Some("user_id"));
+
+        let attrs = vec![
+            NestedMeta::Meta(parse_quote! { relation_type = "has_many_through" }),
+            NestedMeta::Meta(parse_quote! { child = "Comment" }),
+            NestedMeta::Meta(parse_quote! { fk = "post_id" }),
+            NestedMeta::Meta(parse_quote! { through = "posts" }),
+        ];
+        let error = parse_attributes(attrs).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Attributes 'child', 'fk', 'through', and 'through_fk' are required for 'has_many_through' relations"
+        );
+
+        let attrs = vec![
+            NestedMeta::Meta(parse_quote! { relation_type = "one_to_many" }),
+            NestedMeta::Meta(parse_quote! { child = "Post" }),
+            NestedMeta::Meta(parse_quote! { fk = "user_id" }),
+            NestedMeta::Meta(parse_quote! { through = "posts" }),
+        ];
+        let error = parse_attributes(attrs).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Attributes 'through' and 'through_fk' are only supported by has_many_through relations"
+        );
+    }
+
+    #[test]
+    fn test_nested_validation() {
+        let attrs = vec![
+            NestedMeta::Meta(parse_quote! { relation_type = "one_to_many" }),
+            NestedMeta::Meta(parse_quote! { child = "Post" }),
+            NestedMeta::Meta(parse_quote! { fk = "user_id" }),
+            NestedMeta::Meta(parse_quote! { eager_loading = true }),
+            NestedMeta::Meta(parse_quote! { nested = "comments" }),
+            NestedMeta::Meta(parse_quote! { nested_child = "Comment" }),
+        ];
+        let parsed = parse_attributes(attrs).unwrap();
+        assert_eq!(parsed.nested.as_deref(), Some("comments"));
+        assert_eq!(parsed.nested_child.as_deref(), Some("Comment"));
+
+        let attrs = vec![
+            NestedMeta::Meta(parse_quote! { relation_type = "one_to_many" }),
+            NestedMeta::Meta(parse_quote! { child = "Post" }),
+            NestedMeta::Meta(parse_quote! { fk = "user_id" }),
+            NestedMeta::Meta(parse_quote! { nested = "comments" }),
+            NestedMeta::Meta(parse_quote! { nested_child = "Comment" }),
+        ];
+        let error = parse_attributes(attrs).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Attribute 'nested' requires 'eager_loading' and is only supported by one_to_many relations"
+        );
+
+        let attrs = vec![
+            NestedMeta::Meta(parse_quote! { relation_type = "one_to_many" }),
+            NestedMeta::Meta(parse_quote! { child = "Post" }),
+            NestedMeta::Meta(parse_quote! { fk = "user_id" }),
+            NestedMeta::Meta(parse_quote! { eager_loading = true }),
+            NestedMeta::Meta(parse_quote! { nested = "comments" }),
+        ];
+        let error = parse_attributes(attrs).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Attribute 'nested' requires 'nested_child'"
+        );
+
+        let attrs = vec![
+            NestedMeta::Meta(parse_quote! { relation_type = "one_to_many" }),
+            NestedMeta::Meta(parse_quote! { child = "Post" }),
+            NestedMeta::Meta(parse_quote! { fk = "user_id" }),
+            NestedMeta::Meta(parse_quote! { eager_loading = true }),
+            NestedMeta::Meta(parse_quote! { nested = "posts.comments" }),
+            NestedMeta::Meta(parse_quote! { nested_child = "Comment" }),
+        ];
+        let error = parse_attributes(attrs).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Attribute 'nested' must be the plural name of a relation, got 'posts.comments'"
+        );
+    }
+
+    #[test]
+    fn test_cursor_column_validation() {
+        let attrs = vec![
+            NestedMeta::Meta(parse_quote! { relation_type = "many_to_many" }),
+            NestedMeta::Meta(parse_quote! { child = "Tag" }),
+            NestedMeta::Meta(parse_quote! { join_table = "posts_tags" }),
+            NestedMeta::Meta(parse_quote! { fk_parent = "post_id" }),
+            NestedMeta::Meta(parse_quote! { fk_child = "tag_id" }),
+            NestedMeta::Meta(parse_quote! { cursor_column = "id" }),
+        ];
+        assert_eq!(
+            parse_attributes(attrs).unwrap().cursor_column.as_deref(),
+            Some("id")
+        );
+
+        let attrs = vec![
+            NestedMeta::Meta(parse_quote! { relation_type = "one_to_one" }),
+            NestedMeta::Meta(parse_quote! { child = "Profile" }),
+            NestedMeta::Meta(parse_quote! { fk = "user_id" }),
+            NestedMeta::Meta(parse_quote! { cursor_column = "id" }),
+        ];
+        let error = parse_attributes(attrs).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Attribute 'cursor_column' is only supported by one_to_many and many_to_many relations"
+        );
+
+        let attrs = vec![
+            NestedMeta::Meta(parse_quote! { relation_type = "one_to_many" }),
+            NestedMeta::Meta(parse_quote! { child = "Post" }),
+            NestedMeta::Meta(parse_quote! { fk = "user_id" }),
+            NestedMeta::Meta(parse_quote! { cursor_column = "created at" }),
+        ];
+        let error = parse_attributes(attrs).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Attribute 'cursor_column' must be a column name, got 'created at'"
+        );
+    }
+
+    #[test]
+    fn test_fk_column_and_fk_field_relation_types() {
+        let attrs = vec![
+            NestedMeta::Meta(parse_quote! { relation_type = "one_to_many" }),
+            NestedMeta::Meta(parse_quote! { child = "Post" }),
+            NestedMeta::Meta(parse_quote! { fk = "user_id" }),
+            NestedMeta::Meta(parse_quote! { fk_column = "user_fk" }),
+        ];
+        assert_eq!(
+            parse_attributes(attrs).unwrap().fk_column.as_deref(),
+            Some("user_fk")
+        );
+
+        let attrs = vec![
+            NestedMeta::Meta(parse_quote! { relation_type = "one_to_many" }),
+            NestedMeta::Meta(parse_quote! { child = "Post" }),
+            NestedMeta::Meta(parse_quote! { fk = "user_id" }),
+            NestedMeta::Meta(parse_quote! { fk_field = "author" }),
+        ];
+        let error = parse_attributes(attrs).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Attribute 'fk_field' is only supported by many_to_one relations"
+        );
+
+        let attrs = vec![
+            NestedMeta::Meta(parse_quote! { relation_type = "many_to_one" }),
+            NestedMeta::Meta(parse_quote! { child = "User" }),
+            NestedMeta::Meta(parse_quote! { fk = "user_fk" }),
+            NestedMeta::Meta(parse_quote! { fk_column = "user_fk" }),
+        ];
+        let error = parse_attributes(attrs).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Attribute 'fk_column' is only supported by one_to_many and one_to_one relations"
+        );
+    }
 }